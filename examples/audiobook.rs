@@ -0,0 +1,65 @@
+//! 把纯文本或Markdown文档渲染为有声书，中断后再次运行会从上次的进度继续
+//!
+//! ```shell
+//! cargo run --release --example audiobook -- book.md audiobook af_heart
+//! ```
+
+use {
+    kokoro_tts::{AudiobookOptions, DocumentFormat, KokoroTts, Voice, parse_document},
+    std::env::args,
+    tokio::fs::read_to_string,
+};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = args().skip(1);
+    let (Some(document), Some(dir)) = (args.next(), args.next()) else {
+        anyhow::bail!("Usage: audiobook <document.txt|document.md> <output dir> [voice] [model] [voices]");
+    };
+    let voice = args.next().unwrap_or("af_heart".to_owned());
+    let Some(voice) = Voice::from_name(&voice) else {
+        anyhow::bail!("Unknown voice: {}", voice);
+    };
+    let model = args.next().unwrap_or("kokoro-v1.0.int8.onnx".to_owned());
+    let voices = args.next().unwrap_or("voices.bin".to_owned());
+    let tts = KokoroTts::new(&model, &voices).await?;
+
+    let text = read_to_string(&document).await?;
+    let chapters = parse_document(&text, DocumentFormat::from_path(&document));
+    let paragraphs = chapters.iter().map(|i| i.paragraphs.len()).sum::<usize>();
+    println!("{}: {} chapter(s), {} paragraph(s)", document, chapters.len(), paragraphs);
+
+    let index = tts
+        .render_audiobook(&chapters, voice, &AudiobookOptions::default(), &dir, |p| {
+            let title = match chapters[p.chapter].title.as_str() {
+                "" => "(untitled)",
+                title => title,
+            };
+            if p.resumed {
+                println!(
+                    "[{}/{}] {}: resumed at {}/{} ({:.1?})",
+                    p.chapter + 1,
+                    p.chapters,
+                    title,
+                    p.paragraph,
+                    p.paragraphs,
+                    p.audio
+                );
+            } else {
+                println!(
+                    "[{}/{}] {}: {}/{} ({:.1?}, RTF {:.3})",
+                    p.chapter + 1,
+                    p.chapters,
+                    title,
+                    p.paragraph,
+                    p.paragraphs,
+                    p.audio,
+                    p.stats.rtf()
+                );
+            }
+        })
+        .await?;
+    println!("Rendered {:.1?} of audio to {}", index.duration(), dir);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use {
+    kokoro_tts::{KokoroTts, SynthStats, Voice},
+    std::env::args,
+};
+
+const ROUNDS: u32 = 5;
+
+const SHORT_EN: &str = "Hello, world!";
+const MEDIUM_EN: &str = "The quick brown fox jumps over the lazy dog, and then it runs back home to read a book.";
+const SHORT_ZH: &str = "你好，世界。";
+const MEDIUM_ZH: &str = "你好，我们是一群追逐梦想的人。今天天气很好，我们一起去公园散步吧。";
+
+async fn bench(tts: &KokoroTts, name: &str, text: &str, voice: Voice) -> anyhow::Result<()> {
+    // 第一次合成包含语音包的加载和模型的预热，不计入结果
+    tts.synth(text, voice).await?;
+
+    let mut total = SynthStats::default();
+    for _ in 0..ROUNDS {
+        let (_, stats) = tts.synth(text, voice).await?;
+        total += stats;
+    }
+
+    println!(
+        "{:<12} {:<14} {:>5} {:>10.2?} {:>10.2?} {:>10.2?} {:>9.2?} {:>7.3} {:>6}",
+        format!("{:?}", voice),
+        name,
+        text.chars().count(),
+        total.inference / ROUNDS,
+        total.g2p / ROUNDS,
+        total.tokenization / ROUNDS,
+        total.audio / ROUNDS,
+        total.rtf(),
+        total.chunks as u32 / ROUNDS,
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = args().skip(1);
+    let model = args.next().unwrap_or("kokoro-v1.0.int8.onnx".to_owned());
+    let voices = args.next().unwrap_or("voices.bin".to_owned());
+    let tts = KokoroTts::new(&model, &voices).await?;
+
+    let long_en = MEDIUM_EN.repeat(8);
+    let long_zh = MEDIUM_ZH.repeat(8);
+    let english = [("en short", SHORT_EN), ("en medium", MEDIUM_EN), ("en long", &long_en)];
+    let chinese = [("zh short", SHORT_ZH), ("zh medium", MEDIUM_ZH), ("zh long", &long_zh)];
+    let cases = [
+        (Voice::AfHeart, &english),
+        (Voice::BmGeorge, &english),
+        (Voice::ZfXiaoxiao, &chinese),
+    ];
+
+    println!("=== Synthesis benchmark: {} ({} rounds) ===\n", model, ROUNDS);
+    println!(
+        "{:<12} {:<14} {:>5} {:>10} {:>10} {:>10} {:>9} {:>7} {:>6}",
+        "voice", "text", "chars", "inference", "g2p", "tokenize", "audio", "RTF", "chunks"
+    );
+    for (voice, texts) in cases {
+        for (name, text) in texts {
+            bench(&tts, name, text, voice).await?;
+        }
+    }
+
+    Ok(())
+}
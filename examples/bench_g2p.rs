@@ -0,0 +1,41 @@
+//! 测量g2p第一次调用和之后每次调用的耗时，只是一个示例程序，不是`cargo bench`的基准测试
+//!
+//! ```shell
+//! cargo run --release --example bench_g2p
+//! ```
+
+use {kokoro_tts::g2p, std::time::Instant};
+
+const ROUNDS: u32 = 200;
+
+fn bench(name: &str, text: &str, use_v11: bool) -> anyhow::Result<()> {
+    // 第一次调用包含正则编译、结巴分词词典等资源的加载
+    let start = Instant::now();
+    g2p(text, use_v11)?;
+    let first = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        g2p(text, use_v11)?;
+    }
+    let per_call = start.elapsed() / ROUNDS;
+
+    println!("{:<12} first call: {:>10.2?}  per call: {:>10.2?}", name, first, per_call);
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    println!("=== G2P per-call cost ({} rounds) ===\n", ROUNDS);
+
+    bench("en short", "Hello world!", false)?;
+    bench(
+        "en long",
+        "The quick brown fox jumps over the lazy dog, and then it runs back home to read a book.",
+        false,
+    )?;
+    bench("zh v1.0", "你好世界，今天天气很好。", false)?;
+    bench("zh v1.1", "你好世界，今天天气很好，气温是25度。", true)?;
+    bench("mixed v1.1", "我们明天用Rust写一个TTS demo。", true)?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use kokoro_tts::{KokoroTts, Voice};
+use kokoro_tts::{KokoroTts, SAMPLE_RATE, Voice};
 use std::fs::File;
 use std::io::Write;
 use std::time::Instant;
@@ -43,9 +43,9 @@ async fn main() -> anyhow::Result<()> {
 
     // Test different voices
     let voices_to_test = vec![
-        ("af_jessica", Voice::AfJessica(1.0)),
-        ("bf_lily", Voice::BfLily(1.0)),
-        ("am_puck", Voice::AmPuck(1.0)),
+        ("af_jessica", Voice::AfJessica),
+        ("bf_lily", Voice::BfLily),
+        ("am_puck", Voice::AmPuck),
     ];
 
     for (voice_name, voice) in &voices_to_test {
@@ -55,25 +55,14 @@ async fn main() -> anyhow::Result<()> {
             println!("[{}/{}] \"{}\"", i + 1, test_sentences.len(), text);
 
             // Generate audio
-            let start_time = Instant::now();
-            let (audio, _duration) = tts.synth(text, voice.clone()).await?;
-            let generation_time = start_time.elapsed().as_secs_f32();
-
-            // Calculate audio duration
-            // Kokoro outputs at 24000 Hz
-            let sample_rate = 24000.0;
-            let audio_duration = audio.len() as f32 / sample_rate;
+            let (audio, stats) = tts.synth(text, voice.clone()).await?;
+            let generation_time = stats.processing().as_secs_f32();
+            let audio_duration = stats.audio.as_secs_f32();
 
             total_audio_duration += audio_duration;
             total_generation_time += generation_time;
 
-            // Calculate Real-Time Factor (RTF)
-            let rtf = if audio_duration > 0.0 {
-                generation_time / audio_duration
-            } else {
-                0.0
-            };
-
+            let rtf = stats.rtf();
             println!("  ✓ Generated in: {:.3}s", generation_time);
             println!("  ✓ Audio duration: {:.2}s", audio_duration);
             println!(
@@ -88,7 +77,7 @@ async fn main() -> anyhow::Result<()> {
                 voice_name,
                 i + 1
             );
-            save_wav(&audio, 24000, &output_file)?;
+            save_wav(&audio, SAMPLE_RATE, &output_file)?;
             println!("  ✓ Saved to: {}", output_file);
         }
     }
@@ -0,0 +1,36 @@
+use kokoro_tts::{DialogueOptions, KokoroTts, SAMPLE_RATE, Voice, parse_script};
+
+const SCRIPT: &str = "
+# 对话脚本：说话人可以是角色名，也可以直接使用语音名称
+Teacher: Good morning, everyone. Today we will talk about the weather.
+Student: Good morning! Will it rain tomorrow?
+[800ms]
+Teacher: The forecast says it will be sunny.
+bf_emma: Then let's have the lesson outside!
+";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let tts = KokoroTts::new("kokoro-v1.0.int8.onnx", "voices.bin").await?;
+    let lines = parse_script(SCRIPT, &[("Teacher", Voice::AmMichael), ("Student", Voice::AfHeart)])?;
+    let (audio, stats, timings) = tts.synth_dialogue(&lines, &DialogueOptions::default()).await?;
+    for (line, timing) in lines.iter().zip(&timings) {
+        println!("{:>8.2?} - {:>8.2?}  {}: {}", timing.start, timing.end, line.speaker, line.text);
+    }
+    println!("Synth stats: {}", stats);
+
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create("dialogue.wav", spec)?;
+    for sample in audio {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    println!("Saved to dialogue.wav");
+
+    Ok(())
+}
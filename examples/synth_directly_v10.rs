@@ -1,16 +1,20 @@
-use kokoro_tts::{KokoroTts, Voice};
+use kokoro_tts::{KokoroTts, SynthOptions, Voice};
 use rodio::{OutputStreamBuilder, Sink, buffer::SamplesBuffer};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let tts = KokoroTts::new("kokoro-v1.0.int8.onnx", "voices.bin").await?;
-    let (audio, took) = tts
-        .synth(
+    let (audio, stats) = tts
+        .synth_with_options(
             "Hello, world!你好，我们是一群追逐梦想的人。我正在使用qq。",
-            Voice::ZfXiaoxiao(1.2),
+            Voice::ZfXiaoxiao,
+            &SynthOptions {
+                speed: 1.2,
+                ..Default::default()
+            },
         )
         .await?;
-    println!("Synth took: {:?}", took);
+    println!("Synth stats: {}", stats);
     play_sound(&audio);
     Ok(())
 }
@@ -4,13 +4,13 @@ use rodio::{OutputStreamBuilder, Sink, buffer::SamplesBuffer};
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let tts = KokoroTts::new("kokoro-v1.1-zh.onnx", "voices-v1.1-zh.bin").await?;
-    let (audio, took) = tts
+    let (audio, stats) = tts
         .synth(
             "Hello, world!你好，我们是一群追逐梦想的人。我正在使用qq。",
-            Voice::Zm045(1),
+            Voice::Zm045,
         )
         .await?;
-    println!("Synth took: {:?}", took);
+    println!("Synth stats: {}", stats);
     play_sound(&audio);
     Ok(())
 }
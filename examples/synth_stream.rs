@@ -7,16 +7,19 @@ use tokio::time::{Duration, sleep};
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let tts = KokoroTts::new("kokoro-v1.1-zh.onnx", "voices-v1.1-zh.bin").await?;
-    let (mut sink, mut stream) = tts.stream(Voice::Zm098(1));
+    let (mut sink, mut stream) = tts.stream(Voice::Zm098);
     sink.synth("hello world.").await?;
     sink.synth("你好，我们是一群追逐梦想的人。").await?;
-    sink.set_voice(Voice::Zf032(2));
+    sink.set_voice(Voice::Zf032);
+    sink.set_speed(1.5)?;
     sink.synth("我正在使用qq。").await?;
-    sink.set_voice(Voice::Zf090(3));
+    sink.set_voice(Voice::Zf090);
+    sink.set_speed(2.0)?;
     sink.synth("今天天气如何？").await?;
-    sink.set_voice(Voice::Zm045(1));
+    sink.set_voice(Voice::Zm045);
+    sink.set_speed(1.0)?;
     sink.synth("你在使用Rust编程语言吗？").await?;
-    sink.set_voice(Voice::Zf039(1));
+    sink.set_voice(Voice::Zf039);
     sink.synth(
         "你轻轻地走过那
 在风雨花丛中
@@ -27,7 +30,7 @@ async fn main() -> anyhow::Result<()> {
 在那彩虹 最温柔的风",
     )
     .await?;
-    sink.set_voice(Voice::Zf088(1));
+    sink.set_voice(Voice::Zf088);
     sink.synth(
         "你静静看着我们
 最不舍的面容
@@ -45,9 +48,14 @@ async fn main() -> anyhow::Result<()> {
     let player = Arc::new(Sink::connect_new(&stream_handle));
     let player2 = player.clone();
     tokio::spawn(async move {
-        while let Some((audio, took)) = stream.next().await {
-            player.append(SamplesBuffer::new(1, 24000, audio));
-            println!("Synth took: {:?}", took);
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok((audio, stats)) => {
+                    player.append(SamplesBuffer::new(1, 24000, audio));
+                    println!("Synth stats: {}", stats);
+                }
+                Err(e) => eprintln!("{}", e),
+            }
         }
     });
 
@@ -0,0 +1,94 @@
+use crate::{G2POptions, G2PSource, G2PWord, KokoroError, UnknownPhoneme, Vocabulary, g2p::g2p_traced};
+
+/// 一个词的分析结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WordAnalysis {
+    pub word: G2PWord,
+    /// 音素在词表中的编号，不含首尾的边界符号
+    pub token_ids: Vec<i64>,
+    /// 被丢弃的字符：词表中没有的音素符号，或者无法处理的原文
+    pub dropped: Vec<char>,
+}
+
+/// 文本的读音分析，参见`KokoroTts::analyze`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// 按原文顺序排列的每个词
+    pub words: Vec<WordAnalysis>,
+    /// 整段文本的音素，与合成时使用的相同
+    pub phonemes: String,
+    /// 送入模型的编号，包含首尾的边界符号
+    pub token_ids: Vec<i64>,
+    /// 整段音素中词表没有的符号
+    pub unknown: Vec<UnknownPhoneme>,
+}
+
+pub(crate) fn analyze(
+    text: &str,
+    g2p_options: &G2POptions,
+    use_v11: bool,
+    vocabulary: &Vocabulary,
+) -> Result<Analysis, KokoroError> {
+    let mut words = Vec::new();
+    let phonemes = g2p_traced(text, g2p_options, use_v11, Some(&mut words))?;
+    let (token_ids, unknown) = vocabulary.encode(&phonemes);
+    let words = words
+        .into_iter()
+        .map(|word| {
+            let (token_ids, unknown) = vocabulary.encode(&word.phonemes);
+            let dropped = match word.source {
+                G2PSource::Dropped => word.text.chars().filter(|i| !i.is_whitespace()).collect(),
+                _ => unknown.into_iter().map(|i| i.symbol).collect(),
+            };
+            WordAnalysis {
+                token_ids: token_ids[1..token_ids.len() - 1].to_vec(),
+                word,
+                dropped,
+            }
+        })
+        .collect();
+
+    Ok(Analysis {
+        words,
+        phonemes,
+        token_ids,
+        unknown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ModelVersion};
+
+    #[test]
+    fn test_analyze() -> Result<(), KokoroError> {
+        let analysis = analyze(
+            "AI 🙂 你好",
+            &G2POptions::default(),
+            false,
+            &Vocabulary::builtin(ModelVersion::V10),
+        )?;
+        // 启用CMUdict时AI直接从词典中查到
+        let english = if cfg!(feature = "use-cmudict") {
+            G2PSource::Cmudict
+        } else {
+            G2PSource::Lexicon
+        };
+        assert_eq!(
+            vec![english, G2PSource::Dropped, G2PSource::Pinyin],
+            analysis.words.iter().map(|i| i.word.source).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!['🙂'], analysis.words[1].dropped);
+        assert!(analysis.words[1].token_ids.is_empty());
+        let words = analysis
+            .words
+            .iter()
+            .flat_map(|i| i.token_ids.iter().copied())
+            .collect::<Vec<_>>();
+        // 词之间的空格也是音素
+        let spaces = analysis.phonemes.matches(' ').count();
+        assert_eq!(analysis.token_ids.len(), words.len() + spaces + 2);
+
+        Ok(())
+    }
+}
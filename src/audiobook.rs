@@ -0,0 +1,669 @@
+//! 长文档的分章节渲染，见[`KokoroTts::render_audiobook`](crate::KokoroTts::render_audiobook)
+
+use {
+    crate::{
+        KokoroError, SynthOptions, SynthStats,
+        cache::Fnv,
+        subtitle::is_cjk,
+        synthesizer::samples,
+        wav::{WAV_HEADER_LEN, wav_data, wav_data_len, wav_header},
+    },
+    regex::Regex,
+    serde_json::{Value, json},
+    std::{
+        fmt::Write,
+        hash::Hasher,
+        path::Path,
+        sync::LazyLock,
+        time::Duration,
+    },
+    tokio::{
+        fs::{File, OpenOptions, create_dir_all, metadata, read_to_string, rename, write},
+        io::{AsyncSeekExt, AsyncWriteExt, SeekFrom},
+    },
+};
+
+/// 纯文本中单独成段的章节标题
+static CHAPTER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:(?:chapter|part|book|prologue|epilogue|interlude|appendix)\b.{0,60}|第[\d零〇一二三四五六七八九十百千两]+[章回节卷部集篇].{0,30}|(?:序章|序言|楔子|引子|尾声|后记).{0,30})$",
+    )
+    .unwrap()
+});
+static HEADING_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*?)(?:\s+#+)?\s*$").unwrap());
+static LIST_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+").unwrap());
+static RULE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:(?:-\s*){3,}|(?:\*\s*){3,}|(?:_\s*){3,})$").unwrap());
+static IMAGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap());
+static LINK_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+static MARKUP_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>|\*+|~~|`+|\b_+|_+\b").unwrap());
+
+/// 进度文件，记录每章的渲染进度，用于中断之后继续渲染
+const MANIFEST: &str = "audiobook.json";
+
+/// 文档的格式
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DocumentFormat {
+    /// 空行分段，单独成段的“Chapter 1”、“第一章”等作为章节标题
+    #[default]
+    PlainText,
+    /// 一级和二级标题作为章节标题，去掉代码块、图片、链接地址和强调等标记
+    Markdown,
+}
+
+impl DocumentFormat {
+    /// 根据扩展名判断格式，`.md`和`.markdown`为Markdown，其他都按纯文本处理
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|i| i.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => Self::Markdown,
+            _ => Self::PlainText,
+        }
+    }
+}
+
+/// 文档中的一章
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Chapter {
+    /// 章节标题，第一个标题之前的正文所在的章节没有标题
+    pub title: String,
+    /// 每个段落的文本，段内的换行已经合并
+    pub paragraphs: Vec<String>,
+}
+
+impl Chapter {
+    /// 需要合成的文本，依次为标题（可选）和各个段落
+    fn texts(&self, speak_title: bool) -> Vec<&str> {
+        let title = Some(self.title.as_str()).filter(|i| speak_title && !i.is_empty());
+        title.into_iter().chain(self.paragraphs.iter().map(String::as_str)).collect()
+    }
+}
+
+/// 把文档切分为章节和段落，只有标题没有正文的章节（例如书名）会被忽略
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{DocumentFormat, parse_document};
+///
+/// let chapters = parse_document("# Chapter 1\n\nIt was a *dark* night.\n\n# Chapter 2\n\nThe end.", DocumentFormat::Markdown);
+/// assert_eq!(2, chapters.len());
+/// assert_eq!("Chapter 1", chapters[0].title);
+/// assert_eq!(vec!["It was a dark night."], chapters[0].paragraphs);
+/// ```
+///
+pub fn parse_document(text: &str, format: DocumentFormat) -> Vec<Chapter> {
+    let mut document = Document::default();
+    match format {
+        DocumentFormat::PlainText => {
+            for line in text.lines() {
+                let line = line.trim();
+                if line.is_empty() {
+                    document.end_paragraph(true);
+                } else {
+                    document.lines.push(line.to_owned());
+                }
+            }
+            document.end_paragraph(true);
+        }
+        DocumentFormat::Markdown => {
+            let mut fence = None;
+            for line in text.lines() {
+                let trimmed = line.trim();
+                // 代码块不朗读
+                if let Some(marker) = fence {
+                    if trimmed.starts_with(marker) {
+                        fence = None;
+                    }
+                    continue;
+                }
+                if let Some(marker) = ["```", "~~~"].into_iter().find(|i| trimmed.starts_with(i)) {
+                    document.end_paragraph(false);
+                    fence = Some(marker);
+                    continue;
+                }
+
+                if trimmed.is_empty() {
+                    document.end_paragraph(false);
+                } else if let Some(heading) = HEADING_PATTERN.captures(trimmed) {
+                    let title = markdown_inline(&heading[2]);
+                    match heading[1].len() {
+                        1 | 2 => document.start_chapter(title),
+                        _ => {
+                            document.end_paragraph(false);
+                            document.lines.push(title);
+                            document.end_paragraph(false);
+                        }
+                    }
+                } else if document.lines.len() == 1 && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-')) {
+                    // Setext风格的标题：下一行是`===`或`---`
+                    let title = document.lines.remove(0);
+                    document.start_chapter(title);
+                } else if RULE_PATTERN.is_match(trimmed) {
+                    document.end_paragraph(false);
+                } else {
+                    // 每个列表项单独成段
+                    if LIST_PATTERN.is_match(line) {
+                        document.end_paragraph(false);
+                    }
+                    let line = LIST_PATTERN.replace(line, "");
+                    let line = line.trim().trim_start_matches('>').trim();
+                    let line = markdown_inline(line);
+                    if !line.is_empty() {
+                        document.lines.push(line);
+                    }
+                }
+            }
+            document.end_paragraph(false);
+        }
+    }
+
+    document.chapters.retain(|i| !i.paragraphs.is_empty());
+    document.chapters
+}
+
+/// 去掉行内的Markdown标记
+fn markdown_inline(text: &str) -> String {
+    let text = IMAGE_PATTERN.replace_all(text, "");
+    let text = LINK_PATTERN.replace_all(&text, "$1");
+    MARKUP_PATTERN.replace_all(&text, "").trim().to_owned()
+}
+
+/// 合并段内的行，汉字之间不加空格
+fn join_lines(lines: &[String]) -> String {
+    let mut paragraph = String::new();
+    for line in lines {
+        if let (Some(prev), Some(next)) = (paragraph.chars().last(), line.chars().next())
+            && !(is_cjk(prev) && is_cjk(next))
+        {
+            paragraph.push(' ');
+        }
+        paragraph.push_str(line);
+    }
+    paragraph
+}
+
+#[derive(Default)]
+struct Document {
+    chapters: Vec<Chapter>,
+    /// 当前段落中的行
+    lines: Vec<String>,
+}
+
+impl Document {
+    /// 结束当前段落，纯文本中单独成段的章节标题开始新的一章
+    fn end_paragraph(&mut self, detect_chapter: bool) {
+        if self.lines.is_empty() {
+            return;
+        }
+        if detect_chapter && self.lines.len() == 1 && CHAPTER_PATTERN.is_match(&self.lines[0]) {
+            let title = self.lines.remove(0);
+            return self.start_chapter(title);
+        }
+        let paragraph = join_lines(&self.lines);
+        self.lines.clear();
+        if self.chapters.is_empty() {
+            self.chapters.push(Default::default());
+        }
+        if let Some(chapter) = self.chapters.last_mut() {
+            chapter.paragraphs.push(paragraph);
+        }
+    }
+
+    fn start_chapter(&mut self, title: String) {
+        self.end_paragraph(false);
+        self.chapters.push(Chapter {
+            title,
+            paragraphs: Vec::new(),
+        });
+    }
+}
+
+/// 有声书的渲染选项
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudiobookOptions {
+    /// 每个段落的合成选项
+    pub synth: SynthOptions,
+    /// 段落之间插入的静音
+    pub paragraph_pause: Duration,
+    /// 是否在每章开头朗读章节标题
+    pub speak_titles: bool,
+}
+
+impl Default for AudiobookOptions {
+    fn default() -> Self {
+        Self {
+            synth: Default::default(),
+            paragraph_pause: Duration::from_millis(800),
+            speak_titles: true,
+        }
+    }
+}
+
+/// 渲染进度，每完成一个段落报告一次
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderProgress {
+    /// 当前章节的序号，从0开始
+    pub chapter: usize,
+    pub chapters: usize,
+    /// 本章已完成的段落数，朗读的标题也算作一个段落
+    pub paragraph: usize,
+    pub paragraphs: usize,
+    /// 本章已渲染的音频时长
+    pub audio: Duration,
+    /// 刚完成的段落的合成统计，从上次的进度恢复时为默认值
+    pub stats: SynthStats,
+    /// 进度是否来自上次中断的渲染
+    pub resumed: bool,
+}
+
+/// 渲染完成的一章
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderedChapter {
+    pub title: String,
+    /// 音频文件名，相对于输出目录
+    pub file: String,
+    pub duration: Duration,
+}
+
+/// 章节索引，可以导出为CUE或FFmpeg的元数据文件
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudiobookIndex {
+    pub chapters: Vec<RenderedChapter>,
+}
+
+impl AudiobookIndex {
+    /// 所有章节的总时长
+    pub fn duration(&self) -> Duration {
+        self.chapters.iter().map(|i| i.duration).sum()
+    }
+
+    /// 导出为CUE，每章对应一个音频文件
+    pub fn to_cue(&self) -> String {
+        let mut cue = String::new();
+        for (n, chapter) in self.chapters.iter().enumerate() {
+            let _ = write!(
+                cue,
+                "FILE \"{}\" WAVE\n  TRACK {:02} AUDIO\n    TITLE \"{}\"\n    INDEX 01 00:00:00\n",
+                chapter.file,
+                n + 1,
+                chapter.title().replace('"', "'")
+            );
+        }
+        cue
+    }
+
+    /// 导出为FFmpeg的元数据文件，章节时间按所有文件依次拼接计算，
+    /// 可用于`ffmpeg -f concat -i list.txt -i ffmetadata.txt -map_metadata 1 book.m4b`
+    pub fn to_ffmetadata(&self) -> String {
+        let mut metadata = ";FFMETADATA1\n".to_owned();
+        let mut start = Duration::ZERO;
+        for chapter in &self.chapters {
+            let end = start + chapter.duration;
+            let _ = write!(
+                metadata,
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+                start.as_millis(),
+                end.as_millis(),
+                escape_ffmetadata(chapter.title())
+            );
+            start = end;
+        }
+        metadata
+    }
+}
+
+impl RenderedChapter {
+    /// 没有标题时使用文件名
+    fn title(&self) -> &str {
+        match self.title.as_str() {
+            "" => &self.file,
+            title => title,
+        }
+    }
+}
+
+fn escape_ffmetadata(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '=' | ';' | '#' | '\\' | '\n' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 进度文件中一章的记录
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Entry {
+    /// 见[`chapter_key`]，文本或选项改变后需要重新渲染
+    key: u64,
+    paragraphs: usize,
+    samples: usize,
+    complete: bool,
+}
+
+impl Entry {
+    fn to_json(self) -> Value {
+        json!({
+            "key": format!("{:016x}", self.key),
+            "paragraphs": self.paragraphs,
+            "samples": self.samples,
+            "complete": self.complete,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            key: u64::from_str_radix(value["key"].as_str()?, 16).ok()?,
+            paragraphs: value["paragraphs"].as_u64()? as usize,
+            samples: value["samples"].as_u64()? as usize,
+            complete: value["complete"].as_bool()?,
+        })
+    }
+}
+
+/// 读取上次的进度，文件不存在或无法解析时从头开始
+async fn load_manifest(dir: &Path) -> Vec<Entry> {
+    let Ok(json) = read_to_string(dir.join(MANIFEST)).await else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Value>(&json)
+        .ok()
+        .and_then(|i| i["chapters"].as_array()?.iter().map(Entry::from_json).collect())
+        .unwrap_or_default()
+}
+
+async fn save_manifest<'a, I>(dir: &Path, entries: I) -> Result<(), KokoroError>
+where
+    I: IntoIterator<Item = &'a Entry>,
+{
+    let chapters = entries.into_iter().map(|i| i.to_json()).collect::<Vec<_>>();
+    let path = dir.join(MANIFEST);
+    let tmp = path.with_extension("json.tmp");
+    write(&tmp, serde_json::to_string_pretty(&json!({ "chapters": chapters }))?).await?;
+    rename(&tmp, &path).await?;
+    Ok(())
+}
+
+/// 章节的键：`base`包含模型、语音和合成选项，再加上段落间隔和要合成的每段文本
+fn chapter_key(base: u64, texts: &[&str], paragraph_pause: Duration) -> u64 {
+    let mut hasher = Fnv::default();
+    hasher.write_u64(base);
+    hasher.write_u128(paragraph_pause.as_nanos());
+    for i in texts {
+        hasher.write(i.as_bytes());
+        hasher.write_u8(0xff);
+    }
+    hasher.finish()
+}
+
+async fn file_len(path: &Path) -> Option<u64> {
+    metadata(path).await.ok().map(|i| i.len())
+}
+
+fn data_len(samples: usize) -> u64 {
+    WAV_HEADER_LEN + samples as u64 * 4
+}
+
+/// 逐段合成并追加到每章的WAV文件，内存中只保留一个段落的音频。
+/// 每完成一段都会更新进度文件，再次调用时跳过已完成的章节，并从未完成章节的下一段继续。
+pub(crate) async fn render<F, P>(
+    chapters: &[Chapter],
+    options: &AudiobookOptions,
+    dir: &Path,
+    base_key: u64,
+    mut synth: F,
+    mut progress: P,
+) -> Result<AudiobookIndex, KokoroError>
+where
+    F: AsyncFnMut(&str) -> Result<(Vec<f32>, SynthStats), KokoroError>,
+    P: FnMut(&RenderProgress),
+{
+    create_dir_all(dir).await?;
+    let previous = load_manifest(dir).await;
+    let pause = wav_data(&vec![0.; samples(options.paragraph_pause)]);
+    let mut manifest = Vec::with_capacity(chapters.len());
+    let mut index = AudiobookIndex::default();
+
+    for (n, chapter) in chapters.iter().enumerate() {
+        let texts = chapter.texts(options.speak_titles);
+        let file = format!("{:03}.wav", n + 1);
+        let path = dir.join(&file);
+        let part = dir.join(format!("{}.part", file));
+        let mut entry = Entry {
+            key: chapter_key(base_key, &texts, options.paragraph_pause),
+            ..Default::default()
+        };
+        let mut report = RenderProgress {
+            chapter: n,
+            chapters: chapters.len(),
+            paragraph: 0,
+            paragraphs: texts.len(),
+            audio: Duration::ZERO,
+            stats: Default::default(),
+            resumed: false,
+        };
+        let resume = previous
+            .get(n)
+            .copied()
+            .filter(|i| i.key == entry.key && i.paragraphs <= texts.len());
+
+        match resume {
+            // 上次已经完成的章节
+            Some(done) if done.complete && file_len(&path).await == Some(data_len(done.samples)) => {
+                entry = done;
+                report.paragraph = texts.len();
+                report.audio = SynthStats::audio_duration(entry.samples);
+                report.resumed = true;
+                progress(&report);
+            }
+            resume => {
+                let mut out = match resume {
+                    Some(partial)
+                        if !partial.complete && file_len(&part).await >= Some(data_len(partial.samples)) =>
+                    {
+                        // 去掉上次写了一半的段落
+                        let mut out = OpenOptions::new().write(true).open(&part).await?;
+                        out.set_len(data_len(partial.samples)).await?;
+                        out.seek(SeekFrom::End(0)).await?;
+                        entry = partial;
+                        out
+                    }
+                    _ => {
+                        let mut out = File::create(&part).await?;
+                        out.write_all(&wav_header(0)?).await?;
+                        out
+                    }
+                };
+                if entry.paragraphs > 0 {
+                    report.paragraph = entry.paragraphs;
+                    report.audio = SynthStats::audio_duration(entry.samples);
+                    report.resumed = true;
+                    progress(&report);
+                }
+
+                for (i, text) in texts.iter().enumerate().skip(entry.paragraphs) {
+                    let (audio, stats) = synth(text).await?;
+                    // 超出WAV能表示的长度时尽早报错，不再继续合成
+                    let pause_samples = if i > 0 { pause.len() / 4 } else { 0 };
+                    wav_data_len(entry.samples + pause_samples + audio.len())?;
+                    if i > 0 {
+                        out.write_all(&pause).await?;
+                        entry.samples += pause.len() / 4;
+                    }
+                    out.write_all(&wav_data(&audio)).await?;
+                    out.flush().await?;
+                    out.sync_data().await?;
+                    entry.samples += audio.len();
+                    entry.paragraphs = i + 1;
+                    save_manifest(dir, manifest.iter().chain([&entry]).chain(previous.iter().skip(n + 1))).await?;
+
+                    report.paragraph = entry.paragraphs;
+                    report.audio = SynthStats::audio_duration(entry.samples);
+                    report.stats = stats;
+                    report.resumed = false;
+                    progress(&report);
+                }
+
+                out.seek(SeekFrom::Start(0)).await?;
+                out.write_all(&wav_header(entry.samples)?).await?;
+                out.flush().await?;
+                out.sync_all().await?;
+                drop(out);
+                rename(&part, &path).await?;
+                entry.complete = true;
+            }
+        }
+        manifest.push(entry);
+        save_manifest(dir, manifest.iter().chain(previous.iter().skip(n + 1))).await?;
+        index.chapters.push(RenderedChapter {
+            title: chapter.title.clone(),
+            file,
+            duration: SynthStats::audio_duration(entry.samples),
+        });
+    }
+
+    save_manifest(dir, &manifest).await?;
+    write(dir.join("index.cue"), index.to_cue()).await?;
+    write(dir.join("ffmetadata.txt"), index.to_ffmetadata()).await?;
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::cell::RefCell};
+
+    #[test]
+    fn test_parse_plain_text() {
+        let text = "Preface line one\nline two.\n\nChapter 1: The Start\n\nIt was a dark\nand stormy night.\n\n第二章 风雨\n\n天色已晚，\n我们回家吧。\n";
+        let chapters = parse_document(text, DocumentFormat::PlainText);
+        assert_eq!(
+            vec![
+                Chapter {
+                    title: "".to_owned(),
+                    paragraphs: vec!["Preface line one line two.".to_owned()]
+                },
+                Chapter {
+                    title: "Chapter 1: The Start".to_owned(),
+                    paragraphs: vec!["It was a dark and stormy night.".to_owned()]
+                },
+                Chapter {
+                    title: "第二章 风雨".to_owned(),
+                    paragraphs: vec!["天色已晚，我们回家吧。".to_owned()]
+                },
+            ],
+            chapters
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let text = "# My Book\n\n## Chapter One\n\nSome **bold** and _italic_ text with a [link](https://example.com) and snake_case.\n![cover](cover.png)\n\n```rust\nfn main() {}\n```\n\n### A Section\n\n- first item\n- second item\n\n> quoted\n> text\n\n---\n\nChapter Two\n===========\n\nThe `end`.";
+        let chapters = parse_document(text, DocumentFormat::Markdown);
+        assert_eq!(2, chapters.len());
+        assert_eq!("Chapter One", chapters[0].title);
+        assert_eq!(
+            vec![
+                "Some bold and italic text with a link and snake_case.",
+                "A Section",
+                "first item",
+                "second item",
+                "quoted text",
+            ],
+            chapters[0].paragraphs
+        );
+        assert_eq!("Chapter Two", chapters[1].title);
+        assert_eq!(vec!["The end."], chapters[1].paragraphs);
+        assert_eq!(DocumentFormat::Markdown, DocumentFormat::from_path("book.MD"));
+        assert_eq!(DocumentFormat::PlainText, DocumentFormat::from_path("book.txt"));
+    }
+
+    #[test]
+    fn test_index() {
+        let index = AudiobookIndex {
+            chapters: vec![
+                RenderedChapter {
+                    title: "One; \"Start\"".to_owned(),
+                    file: "001.wav".to_owned(),
+                    duration: Duration::from_millis(1500),
+                },
+                RenderedChapter {
+                    title: "".to_owned(),
+                    file: "002.wav".to_owned(),
+                    duration: Duration::from_secs(2),
+                },
+            ],
+        };
+        assert_eq!(Duration::from_millis(3500), index.duration());
+        assert_eq!(
+            "FILE \"001.wav\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"One; 'Start'\"\n    INDEX 01 00:00:00\nFILE \"002.wav\" WAVE\n  TRACK 02 AUDIO\n    TITLE \"002.wav\"\n    INDEX 01 00:00:00\n",
+            index.to_cue()
+        );
+        assert_eq!(
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=1500\ntitle=One\\; \"Start\"\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=1500\nEND=3500\ntitle=002.wav\n",
+            index.to_ffmetadata()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_resume() -> Result<(), KokoroError> {
+        let dir = std::env::temp_dir().join(format!("kokoro-audiobook-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let chapters = parse_document("Chapter 1\n\nOne.\n\nTwo.\n\nChapter 2\n\nThree.\n\nFour.", DocumentFormat::PlainText);
+        let options = AudiobookOptions {
+            paragraph_pause: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let pause = samples(options.paragraph_pause);
+        // 每段输出与文本长度相同的采样，合成第`fail`段时失败，模拟中断
+        let calls = RefCell::new(Vec::new());
+        let synth = |fail: usize| {
+            let calls = &calls;
+            async move |text: &str| {
+                calls.borrow_mut().push(text.to_owned());
+                if calls.borrow().len() == fail {
+                    return Err(KokoroError::ModelReleased);
+                }
+                Ok((vec![0.5; text.len()], SynthStats::default()))
+            }
+        };
+
+        let result = render(&chapters, &options, &dir, 1, synth(5), |_| ()).await;
+        assert!(matches!(result, Err(KokoroError::ModelReleased)));
+        assert!(dir.join("001.wav").exists());
+        assert!(dir.join("002.wav.part").exists());
+
+        // 继续时跳过第一章和第二章已完成的标题
+        calls.borrow_mut().clear();
+        let mut reports = Vec::new();
+        let index = render(&chapters, &options, &dir, 1, synth(0), |p| reports.push(*p)).await?;
+        assert_eq!(vec!["Three.", "Four."], *calls.borrow());
+        assert_eq!(
+            vec![(0, 3, true), (1, 1, true), (1, 2, false), (1, 3, false)],
+            reports.iter().map(|i| (i.chapter, i.paragraph, i.resumed)).collect::<Vec<_>>()
+        );
+        let first = "Chapter 1".len() + "One.".len() + "Two.".len() + pause * 2;
+        assert_eq!(SynthStats::audio_duration(first), index.chapters[0].duration);
+        let second = "Chapter 2".len() + "Three.".len() + "Four.".len() + pause * 2;
+        let wav = std::fs::read(dir.join("002.wav"))?;
+        assert_eq!(Some(vec![0.5; "Chapter 2".len()]), crate::wav::decode_wav(&wav).map(|i| i[..9].to_vec()));
+        assert_eq!(data_len(second), wav.len() as u64);
+        assert!(!dir.join("002.wav.part").exists());
+        assert!(dir.join("index.cue").exists() && dir.join("ffmetadata.txt").exists());
+
+        // 全部完成后不再合成；改变选项后重新渲染
+        calls.borrow_mut().clear();
+        render(&chapters, &options, &dir, 1, synth(0), |_| ()).await?;
+        assert!(calls.borrow().is_empty());
+        render(&chapters, &options, &dir, 2, synth(0), |_| ()).await?;
+        assert_eq!(6, calls.borrow().len());
+
+        let _ = std::fs::remove_dir_all(dir);
+        Ok(())
+    }
+}
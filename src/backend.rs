@@ -0,0 +1,73 @@
+use {
+    crate::{KokoroError, ModelVersion},
+    futures::future::BoxFuture,
+    ndarray::{Array, ArrayView},
+    ort::{
+        inputs,
+        session::{RunOptions, Session},
+        value::TensorRef,
+    },
+    tokio::sync::Mutex,
+};
+
+/// 一次推理的输入
+pub(crate) struct ModelInput<'a> {
+    /// 决定输入输出的名称和语速的类型
+    pub(crate) version: ModelVersion,
+    /// 包含首尾边界符号的音素编号
+    pub(crate) tokens: &'a [i64],
+    pub(crate) style: &'a [f32],
+    /// v1.1模型只接受整数语速，调用方需要事先取整
+    pub(crate) speed: f32,
+}
+
+/// 推理后端，把音素编号和风格向量转换为音频采样
+///
+/// 合成流程中的分段、风格选择、语速和拼接都在后端之外完成，测试时可以换成确定性的假模型。
+pub(crate) trait Backend: Send {
+    fn infer<'a>(&'a mut self, input: ModelInput<'a>) -> BoxFuture<'a, Result<Vec<f32>, KokoroError>>;
+}
+
+/// 共享的模型，合成时加锁，`KokoroTts`释放后合成会返回`KokoroError::ModelReleased`
+pub(crate) type Model = Mutex<dyn Backend>;
+
+impl Backend for Session {
+    fn infer<'a>(&'a mut self, input: ModelInput<'a>) -> BoxFuture<'a, Result<Vec<f32>, KokoroError>> {
+        Box::pin(async move {
+            let tokens = ArrayView::from_shape((1, input.tokens.len()), input.tokens)?;
+            let style = ArrayView::from_shape((1, input.style.len()), input.style)?;
+            let options = RunOptions::new()?;
+            let audio = if input.version == ModelVersion::V11 {
+                let speed = Array::from_vec(vec![input.speed as i32]);
+                let output = self
+                    .run_async(
+                        inputs![
+                            "input_ids" => TensorRef::from_array_view(tokens)?,
+                            "style" => TensorRef::from_array_view(style)?,
+                            "speed" => TensorRef::from_array_view(&speed)?,
+                        ],
+                        &options,
+                    )?
+                    .await?;
+                let (_, audio) = output["waveform"].try_extract_tensor::<f32>()?;
+                audio.to_owned()
+            } else {
+                let speed = Array::from_vec(vec![input.speed]);
+                let output = self
+                    .run_async(
+                        inputs![
+                            "tokens" => TensorRef::from_array_view(tokens)?,
+                            "style" => TensorRef::from_array_view(style)?,
+                            "speed" => TensorRef::from_array_view(&speed)?,
+                        ],
+                        &options,
+                    )?
+                    .await?;
+                let (_, audio) = output["audio"].try_extract_tensor::<f32>()?;
+                audio.to_owned()
+            };
+
+            Ok(audio)
+        })
+    }
+}
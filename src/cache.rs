@@ -0,0 +1,510 @@
+use {
+    crate::{
+        KokoroError, Loudness, ModelVersion, Pauses, SynthOptions, SynthStats, VariantSelection, Vocabulary, VoicePack,
+        telemetry,
+        wav::{decode_wav, encode_wav},
+    },
+    log::warn,
+    lru::LruCache,
+    ort::session::Session,
+    std::{
+        future::Future,
+        hash::Hasher,
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, PoisonError},
+    },
+    tokio::{
+        fs::{File, create_dir_all, read, rename, write},
+        io::AsyncReadExt,
+    },
+};
+
+/// 合成结果的缓存
+///
+/// 缓存键由规范化之后的文本（去掉首尾空白、合并连续空白）、语音的风格向量、所有合成选项以及模型的标识组成，
+/// 模型的标识来自ONNX模型文件的长度和内容摘要、元数据、输入输出名称和词表，因此同一模型的fp32、int8和fp16导出不会共用缓存。
+///
+/// 内存中按最近最少使用淘汰；设置了目录时，合成结果还会以32位浮点WAV保存到磁盘，内存中未命中时从磁盘读取。
+///
+/// # 示例
+///
+/// ```rust
+/// use std::num::NonZeroUsize;
+/// use kokoro_tts::{KokoroTts, SynthCache, Voice};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+///         return;
+///     };
+///     let cache = SynthCache::new(NonZeroUsize::new(500).unwrap()).with_dir("../kokoro-cache");
+///     let tts = tts.with_cache(cache);
+///     let _ = tts.warm_cache(["Welcome.", "Please hold."], Voice::AfHeart, &Default::default()).await;
+///     if let Ok((_audio, stats)) = tts.synth("Please  hold. ", Voice::AfHeart).await {
+///         assert!(stats.cached);
+///     }
+/// }
+/// ```
+///
+pub struct SynthCache {
+    memory: Mutex<LruCache<u64, Arc<[f32]>>>,
+    dir: Option<PathBuf>,
+}
+
+impl SynthCache {
+    /// 只在内存中缓存，最多保留`capacity`条合成结果
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            memory: Mutex::new(LruCache::new(capacity)),
+            dir: None,
+        }
+    }
+
+    /// 同时缓存到磁盘目录，目录在第一次写入时创建
+    pub fn with_dir<P: Into<PathBuf>>(self, dir: P) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    /// 磁盘缓存的目录
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// 内存中的条目数
+    pub fn len(&self) -> usize {
+        self.memory.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 清空内存中的条目，磁盘上的文件保持不变
+    pub fn clear(&self) {
+        self.memory.lock().unwrap_or_else(PoisonError::into_inner).clear()
+    }
+
+    fn path(&self, key: u64) -> Option<PathBuf> {
+        self.dir.as_ref().map(|i| i.join(format!("{:016x}.wav", key)))
+    }
+
+    /// 先查内存再查磁盘，磁盘上命中的结果会放回内存
+    pub(crate) async fn get(&self, key: u64) -> Option<Vec<f32>> {
+        if let Some(audio) = self.memory.lock().unwrap_or_else(PoisonError::into_inner).get(&key) {
+            return Some(audio.to_vec());
+        }
+        let path = self.path(key)?;
+        let audio = match read(&path).await {
+            Ok(bytes) => decode_wav(&bytes),
+            Err(_) => return None,
+        };
+        let Some(audio) = audio else {
+            warn!("Invalid cache file {}, ignored.", path.display());
+            return None;
+        };
+        self.memory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(key, audio.as_slice().into());
+        Some(audio)
+    }
+
+    /// 写入缓存，磁盘写入失败只输出警告，不影响合成结果
+    pub(crate) async fn insert(&self, key: u64, audio: &[f32]) {
+        self.memory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(key, audio.into());
+        let Some(path) = self.path(key) else {
+            return;
+        };
+        // 先写临时文件再改名，读取时不会看到写了一半的文件
+        let tmp = path.with_extension("wav.tmp");
+        let result = async {
+            if let Some(dir) = &self.dir {
+                create_dir_all(dir).await?;
+            }
+            write(&tmp, encode_wav(audio)?).await?;
+            rename(&tmp, &path).await?;
+            Ok::<_, KokoroError>(())
+        }
+        .await;
+        if let Err(e) = result {
+            warn!("Failed to write cache file {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// 缓存键的组成部分，词表和风格向量使用预先计算好的摘要，每次查询只需要处理文本
+pub(crate) struct CacheKey<'a> {
+    /// 见[`model_fingerprint`]
+    pub(crate) model: u64,
+    /// 见[`vocabulary_digest`]
+    pub(crate) vocabulary: u64,
+    pub(crate) ssml: bool,
+    pub(crate) text: &'a str,
+    pub(crate) voice: &'a str,
+    /// 见[`pack_digest`]，语音可以在运行时被同名的语音替换，所以还要包含风格向量本身
+    pub(crate) pack: u64,
+    pub(crate) options: &'a SynthOptions,
+}
+
+impl CacheKey<'_> {
+    pub(crate) fn hash(&self) -> u64 {
+        let mut hasher = Fnv::default();
+        hasher.write_u64(self.model);
+        hasher.write_u64(self.vocabulary);
+        hasher.write_u8(self.ssml as u8);
+        // 规范化文本：合并连续的空白，去掉首尾空白
+        for word in self.text.split_whitespace() {
+            hasher.write(word.as_bytes());
+            hasher.write_u8(b' ');
+        }
+        hasher.write_u8(0xff);
+        hasher.write(self.voice.as_bytes());
+        hasher.write_u8(0xff);
+        hasher.write_u64(self.pack);
+        // 解构所有字段，新增选项时必须在这里决定是否参与缓存键
+        let SynthOptions {
+            speed,
+            variant,
+            pitch_semitones,
+            loudness,
+            gain_db,
+            trim_silence,
+            pauses,
+            crossfade,
+            strict,
+        } = *self.options;
+        hasher.write_u32(speed.to_bits());
+        match variant {
+            VariantSelection::First => hasher.write_u8(0),
+            VariantSelection::Seeded(seed) => {
+                hasher.write_u8(1);
+                hasher.write_u64(seed);
+            }
+            VariantSelection::Random => hasher.write_u8(2),
+        }
+        hasher.write_u32(pitch_semitones.to_bits());
+        match loudness {
+            None => hasher.write_u8(0),
+            Some(Loudness::Peak(i)) => {
+                hasher.write_u8(1);
+                hasher.write_u32(i.to_bits());
+            }
+            Some(Loudness::Rms(i)) => {
+                hasher.write_u8(2);
+                hasher.write_u32(i.to_bits());
+            }
+            Some(Loudness::Lufs(i)) => {
+                hasher.write_u8(3);
+                hasher.write_u32(i.to_bits());
+            }
+        }
+        hasher.write_u32(gain_db.to_bits());
+        hasher.write_u8(trim_silence.is_some() as u8);
+        hasher.write_u32(trim_silence.unwrap_or_default().to_bits());
+        hasher.write_u8(pauses.is_some() as u8);
+        if let Some(Pauses {
+            clause,
+            sentence,
+            paragraph,
+        }) = pauses
+        {
+            for i in [clause, sentence, paragraph] {
+                hasher.write_u128(i.as_nanos());
+            }
+        }
+        hasher.write_u128(crossfade.as_nanos());
+        hasher.write_u8(strict as u8);
+        hasher.finish()
+    }
+}
+
+/// 词表的摘要，在加载或替换词表时计算一次
+pub(crate) fn vocabulary_digest(vocabulary: &Vocabulary) -> u64 {
+    let mut vocabulary = vocabulary.iter().collect::<Vec<_>>();
+    vocabulary.sort_unstable();
+    let mut hasher = Fnv::default();
+    for (symbol, id) in vocabulary {
+        hasher.write_u32(symbol as u32);
+        hasher.write_i64(id);
+    }
+    hasher.finish()
+}
+
+/// 语音风格向量的摘要，在语音加载或添加时计算一次
+pub(crate) fn pack_digest(pack: VoicePack<'_>) -> u64 {
+    let shape = [pack.len() as u64, pack.width() as u64];
+    let mut state = shape.iter().fold(FNV_OFFSET, |s, i| digest_words(s, &i.to_le_bytes()));
+    for row in (0..pack.len()).filter_map(|i| pack.style(i)) {
+        for i in row.chunks(2) {
+            let word = i.iter().fold(0u64, |w, i| w << 32 | i.to_bits() as u64);
+            state = (state ^ word).wrapping_mul(FNV_PRIME);
+        }
+    }
+    state
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+/// 计算模型文件摘要时每次读取的字节数，必须是8的倍数，分块读取和整体计算的结果才会一致
+const DIGEST_CHUNK: usize = 1 << 20;
+
+/// 按8字节一组计算FNV-1a，比逐字节计算快得多，几百MB的模型也只需要很短的时间
+fn digest_words(state: u64, bytes: &[u8]) -> u64 {
+    let mut words = bytes.chunks_exact(8);
+    let state = words.by_ref().fold(state, |s, i| {
+        (s ^ u64::from_le_bytes([i[0], i[1], i[2], i[3], i[4], i[5], i[6], i[7]])).wrapping_mul(FNV_PRIME)
+    });
+    words
+        .remainder()
+        .iter()
+        .fold(state, |s, i| (s ^ *i as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// 内存中的模型的长度和内容摘要
+pub(crate) fn model_digest(bytes: &[u8]) -> u64 {
+    digest_words(digest_words(FNV_OFFSET, &(bytes.len() as u64).to_le_bytes()), bytes)
+}
+
+/// 模型文件的长度和内容摘要，与[`model_digest`]的结果相同，分块读取，不需要把整个模型读入内存
+pub(crate) async fn model_file_digest<P: AsRef<Path>>(path: P) -> Result<u64, KokoroError> {
+    let mut file = File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let mut state = digest_words(FNV_OFFSET, &len.to_le_bytes());
+    let mut buf = vec![0; DIGEST_CHUNK];
+    loop {
+        let mut n = 0;
+        while n < buf.len() {
+            match file.read(&mut buf[n..]).await? {
+                0 => break,
+                read => n += read,
+            }
+        }
+        state = digest_words(state, &buf[..n]);
+        if n < buf.len() {
+            return Ok(state);
+        }
+    }
+}
+
+/// 根据模型文件的摘要（见[`model_digest`]）、ONNX模型的元数据和输入输出名称计算模型的标识
+pub(crate) fn model_fingerprint(model: &Session, model_version: ModelVersion, digest: u64) -> Result<u64, KokoroError> {
+    let mut hasher = Fnv::default();
+    hasher.write_u64(digest);
+    let mut write = |s: &str| {
+        hasher.write(s.as_bytes());
+        hasher.write_u8(0xff);
+    };
+    write(&format!("{:?}", model_version));
+    let metadata = model.metadata()?;
+    write(&metadata.name()?);
+    write(&metadata.producer()?);
+    write(&metadata.domain()?);
+    write(&metadata.description()?);
+    write(&metadata.graph_description()?);
+    write(&metadata.version()?.to_string());
+    let mut keys = metadata.custom_keys()?;
+    keys.sort();
+    for key in keys {
+        let value = metadata.custom(&key)?.unwrap_or_default();
+        write(&key);
+        write(&value);
+    }
+    for i in &model.inputs {
+        write(&i.name);
+    }
+    for i in &model.outputs {
+        write(&i.name);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// 命中缓存时直接返回缓存的音频，否则合成并写入缓存。`cache`为`None`时直接合成。
+pub(crate) async fn cached<K, F>(cache: Option<&SynthCache>, key: K, synth: F) -> Result<(Vec<f32>, SynthStats), KokoroError>
+where
+    K: FnOnce() -> u64,
+    F: Future<Output = Result<(Vec<f32>, SynthStats), KokoroError>>,
+{
+    let Some(cache) = cache else {
+        return synth.await;
+    };
+    let key = key();
+    if let Some(audio) = cache.get(key).await {
+        telemetry::record_cache(true);
+        let stats = SynthStats {
+            audio: SynthStats::audio_duration(audio.len()),
+            cached: true,
+            ..Default::default()
+        };
+        return Ok((audio, stats));
+    }
+
+    telemetry::record_cache(false);
+    let (audio, stats) = synth.await?;
+    cache.insert(key, &audio).await;
+    Ok((audio, stats))
+}
+
+/// 64位FNV-1a，与标准库的哈希不同，结果在不同的Rust版本之间保持不变，可以用作磁盘上的文件名
+pub(crate) struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for i in bytes {
+            self.0 = (self.0 ^ *i as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::VoiceStore, std::time::Duration};
+
+    fn voices() -> VoiceStore {
+        let mut store = VoiceStore::with_capacity(2, 16);
+        store.push("a".to_owned(), 2, 4, [0.; 8]);
+        store.push("b".to_owned(), 2, 4, [1.; 8]);
+        store
+    }
+
+    #[test]
+    fn test_key() {
+        let voices = voices();
+        let vocabulary = Vocabulary::builtin(ModelVersion::V10);
+        let options = SynthOptions::default();
+        let key = CacheKey {
+            model: 1,
+            vocabulary: vocabulary_digest(&vocabulary),
+            ssml: false,
+            text: "Please hold.",
+            voice: "a",
+            pack: pack_digest(voices.get("a").unwrap()),
+            options: &options,
+        };
+        let hash = key.hash();
+        // 规范化之后相同的文本
+        assert_eq!(
+            hash,
+            CacheKey {
+                text: "  Please\n hold. ",
+                ..key
+            }
+            .hash()
+        );
+        assert_ne!(hash, CacheKey { text: "please hold.", ..key }.hash());
+        assert_ne!(hash, CacheKey { ssml: true, ..key }.hash());
+        assert_ne!(hash, CacheKey { model: 2, ..key }.hash());
+        // 同名但风格向量不同
+        assert_ne!(
+            hash,
+            CacheKey {
+                pack: pack_digest(voices.get("b").unwrap()),
+                ..key
+            }
+            .hash()
+        );
+        let faster = SynthOptions {
+            speed: 1.2,
+            ..options
+        };
+        assert_ne!(hash, CacheKey { options: &faster, ..key }.hash());
+        let louder = SynthOptions {
+            loudness: Some(Loudness::Rms(-20.)),
+            ..options
+        };
+        let peak = SynthOptions {
+            loudness: Some(Loudness::Peak(-20.)),
+            ..options
+        };
+        assert_ne!(CacheKey { options: &louder, ..key }.hash(), CacheKey { options: &peak, ..key }.hash());
+        let v11 = Vocabulary::builtin(ModelVersion::V11);
+        assert_ne!(
+            hash,
+            CacheKey {
+                vocabulary: vocabulary_digest(&v11),
+                ..key
+            }
+            .hash()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_model_digest() -> Result<(), KokoroError> {
+        // 跨过读取文件时的分块边界，长度也不是8的倍数
+        let mut bytes = (0..DIGEST_CHUNK + 13).map(|i| i as u8).collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(format!("kokoro-model-digest-test-{}.onnx", std::process::id()));
+        write(&path, &bytes).await?;
+        let digest = model_digest(&bytes);
+        assert_eq!(digest, model_file_digest(&path).await?);
+        tokio::fs::remove_file(&path).await?;
+        // 同样长度的不同权重，以及只多出一个零字节的文件
+        bytes[DIGEST_CHUNK + 3] ^= 1;
+        assert_ne!(digest, model_digest(&bytes));
+        bytes[DIGEST_CHUNK + 3] ^= 1;
+        bytes.push(0);
+        assert_ne!(digest, model_digest(&bytes));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached() -> Result<(), KokoroError> {
+        let dir = std::env::temp_dir().join(format!("kokoro-cache-test-{}", std::process::id()));
+        let cache = SynthCache::new(NonZeroUsize::new(1).unwrap()).with_dir(&dir);
+        let synth = |audio: Vec<f32>| async move {
+            Ok((
+                audio,
+                SynthStats {
+                    inference: Duration::from_millis(5),
+                    chunks: 1,
+                    ..Default::default()
+                },
+            ))
+        };
+
+        let (audio, stats) = cached(Some(&cache), || 1, synth(vec![0.5; 10])).await?;
+        assert!(!stats.cached);
+        assert_eq!(vec![0.5; 10], audio);
+        let (audio, stats) = cached(Some(&cache), || 1, synth(vec![0.; 10])).await?;
+        assert!(stats.cached);
+        assert_eq!(Duration::ZERO, stats.inference);
+        assert_eq!(vec![0.5; 10], audio);
+
+        // 内存中只能保留一条，被淘汰的结果从磁盘读取
+        cached(Some(&cache), || 2, synth(vec![0.25; 4])).await?;
+        assert_eq!(1, cache.len());
+        let (audio, stats) = cached(Some(&cache), || 1, synth(vec![0.; 10])).await?;
+        assert!(stats.cached);
+        assert_eq!(vec![0.5; 10], audio);
+
+        // 没有目录时只在内存中缓存
+        let memory = SynthCache::new(NonZeroUsize::new(1).unwrap());
+        cached(Some(&memory), || 1, synth(vec![0.5; 10])).await?;
+        cached(Some(&memory), || 2, synth(vec![0.5; 10])).await?;
+        let (_, stats) = cached(Some(&memory), || 1, synth(vec![0.5; 10])).await?;
+        assert!(!stats.cached);
+
+        let (_, stats) = cached(None, || unreachable!(), synth(vec![0.5; 10])).await?;
+        assert!(!stats.cached);
+
+        let _ = std::fs::remove_dir_all(dir);
+        Ok(())
+    }
+}
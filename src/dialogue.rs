@@ -0,0 +1,243 @@
+//! 多人对话的合成，见[`KokoroTts::synth_dialogue`](crate::KokoroTts::synth_dialogue)
+
+use {
+    crate::{KokoroError, SynthOptions, SynthSink, SynthStats, Voice, ssml::parse_duration, synthesizer::samples},
+    futures::{Stream, StreamExt},
+    std::time::Duration,
+};
+
+/// 对话中的一句
+#[derive(Clone, Debug, PartialEq)]
+pub struct DialogueLine {
+    /// 说话人，从脚本解析时为冒号之前的名称
+    pub speaker: String,
+    pub voice: Voice,
+    pub text: String,
+    /// 这一句之后的停顿，为`None`时使用[`DialogueOptions::gap`]
+    pub pause: Option<Duration>,
+}
+
+impl DialogueLine {
+    /// 以语音名称作为说话人
+    pub fn new<S: Into<String>>(voice: Voice, text: S) -> Self {
+        Self {
+            speaker: voice.get_name().to_owned(),
+            voice,
+            text: text.into(),
+            pause: None,
+        }
+    }
+
+    /// 设置这一句之后的停顿
+    pub fn with_pause(self, pause: Duration) -> Self {
+        Self {
+            pause: Some(pause),
+            ..self
+        }
+    }
+}
+
+/// 对话的合成选项
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DialogueOptions {
+    /// 每一句的合成选项
+    pub synth: SynthOptions,
+    /// 相邻两句之间的默认停顿
+    pub gap: Duration,
+}
+
+impl Default for DialogueOptions {
+    fn default() -> Self {
+        Self {
+            synth: Default::default(),
+            gap: Duration::from_millis(300),
+        }
+    }
+}
+
+/// 一句在合成的音频中的位置
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LineTiming {
+    pub start: Duration,
+    pub end: Duration,
+    /// 这一句的合成统计
+    pub stats: SynthStats,
+}
+
+/// 解析对话脚本
+///
+/// 每行为`说话人: 文本`（也可以使用全角冒号），说话人先在`cast`中查找，找不到时按语音名称（例如`af_heart`）查找。
+/// 单独一行的`[500ms]`或`[1.5s]`设置上一句之后的停顿，空行和以`#`开头的行会被忽略。
+///
+/// # 示例
+///
+/// ```rust
+/// use std::time::Duration;
+/// use kokoro_tts::{Voice, parse_script};
+///
+/// let script = "Teacher: Good morning!\n[2s]\nbf_emma: Good morning.";
+/// let lines = parse_script(script, &[("Teacher", Voice::AmMichael)]).unwrap();
+/// assert_eq!(Voice::AmMichael, lines[0].voice);
+/// assert_eq!(Some(Duration::from_secs(2)), lines[0].pause);
+/// assert_eq!(Voice::BfEmma, lines[1].voice);
+/// assert!(parse_script("Nobody: Hello", &[]).is_err());
+/// ```
+///
+pub fn parse_script(script: &str, cast: &[(&str, Voice)]) -> Result<Vec<DialogueLine>, KokoroError> {
+    let mut lines: Vec<DialogueLine> = Vec::new();
+    for (n, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |msg: String| KokoroError::ScriptInvalid(format!("line {}: {}", n + 1, msg));
+
+        if let Some(pause) = line.strip_prefix('[').and_then(|i| i.strip_suffix(']')) {
+            let pause = parse_duration(pause).ok_or_else(|| invalid(format!("invalid pause {:?}", pause)))?;
+            let last = lines
+                .last_mut()
+                .ok_or_else(|| invalid("pause before the first line".to_owned()))?;
+            last.pause = Some(pause);
+            continue;
+        }
+
+        let Some((speaker, text)) = line.split_once([':', '：']) else {
+            return Err(invalid(format!("expected `speaker: text`, found {:?}", line)));
+        };
+        let speaker = speaker.trim();
+        let voice = cast
+            .iter()
+            .find(|(name, _)| *name == speaker)
+            .map(|(_, voice)| *voice)
+            .or_else(|| Voice::from_name(speaker))
+            .ok_or_else(|| invalid(format!("unknown speaker {:?}", speaker)))?;
+        lines.push(DialogueLine {
+            speaker: speaker.to_owned(),
+            voice,
+            text: text.trim().to_owned(),
+            pause: None,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// 切换语音后把每一句发送到`sink`，再按顺序从`stream`取回音频，拼接并插入停顿
+pub(crate) async fn synth_dialogue<St>(
+    lines: &[DialogueLine],
+    gap: Duration,
+    mut sink: SynthSink<String>,
+    mut stream: St,
+) -> Result<(Vec<f32>, SynthStats, Vec<LineTiming>), KokoroError>
+where
+    St: Stream<Item = Result<(Vec<f32>, SynthStats), KokoroError>> + Unpin,
+{
+    // 先发送所有请求，拼接前面的句子时后面的句子已经在合成
+    for line in lines {
+        sink.set_voice(line.voice);
+        sink.synth(line.text.clone()).await?;
+    }
+    drop(sink);
+
+    let mut audio = Vec::new();
+    let mut stats = SynthStats::default();
+    let mut timings = Vec::with_capacity(lines.len());
+    for (n, line) in lines.iter().enumerate() {
+        // 某一句合成失败时返回该句的错误
+        let Some(result) = stream.next().await else {
+            return Err(KokoroError::Send(format!("dialogue synthesis stopped at line {}", n + 1)));
+        };
+        let (data, took) = result?;
+        let start = audio.len();
+        audio.extend(data);
+        timings.push(LineTiming {
+            start: SynthStats::audio_duration(start),
+            end: SynthStats::audio_duration(audio.len()),
+            stats: took,
+        });
+        stats += took;
+        if n + 1 < lines.len() {
+            audio.resize(audio.len() + samples(line.pause.unwrap_or(gap)), 0.);
+        }
+    }
+    stats.audio = SynthStats::audio_duration(audio.len());
+
+    Ok((audio, stats, timings))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::stream::{Request, start_synth_session},
+    };
+
+    #[test]
+    fn test_parse_script() -> Result<(), KokoroError> {
+        let script = "# lesson 1\nTeacher: Hello: class.\n[1s]\n\naf_heart：Hi!\nTeacher: Bye.";
+        let lines = parse_script(script, &[("Teacher", Voice::BmGeorge)])?;
+        assert_eq!(
+            vec![
+                DialogueLine {
+                    speaker: "Teacher".to_owned(),
+                    voice: Voice::BmGeorge,
+                    text: "Hello: class.".to_owned(),
+                    pause: Some(Duration::from_secs(1)),
+                },
+                DialogueLine::new(Voice::AfHeart, "Hi!"),
+                DialogueLine {
+                    speaker: "Teacher".to_owned(),
+                    ..DialogueLine::new(Voice::BmGeorge, "Bye.")
+                },
+            ],
+            lines
+        );
+
+        for script in ["Hello", "Nobody: Hello", "[1s]\naf_heart: Hi", "af_heart: Hi\n[soon]"] {
+            assert!(matches!(parse_script(script, &[]), Err(KokoroError::ScriptInvalid(_))));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_synth_dialogue() -> Result<(), KokoroError> {
+        // 每句输出与文本长度相同的采样，值为语音的编号
+        let (sink, stream) = start_synth_session(Voice::AfHeart, Default::default(), |request: Request<String>| async move {
+            let value = if request.voice == Voice::AfHeart { 1. } else { 2. };
+            let stats = SynthStats {
+                chunks: 1,
+                ..Default::default()
+            };
+            Ok((vec![value; request.text.len()], stats))
+        });
+        let lines = [
+            DialogueLine::new(Voice::AfHeart, "Hello").with_pause(Duration::from_secs(1)),
+            DialogueLine::new(Voice::BmGeorge, "Hi"),
+            DialogueLine::new(Voice::AfHeart, "Bye"),
+        ];
+        let gap = Duration::from_millis(100);
+        let (audio, stats, timings) = synth_dialogue(&lines, gap, sink, stream).await?;
+
+        let (second, gap) = (samples(Duration::from_secs(1)), samples(gap));
+        assert_eq!(5 + second + 2 + gap + 3, audio.len());
+        assert_eq!(vec![2.; 2], audio[5 + second..5 + second + 2]);
+        assert_eq!(3, stats.chunks);
+        assert_eq!(SynthStats::audio_duration(audio.len()), stats.audio);
+        assert_eq!(Duration::ZERO, timings[0].start);
+        assert_eq!(SynthStats::audio_duration(5), timings[0].end);
+        assert_eq!(SynthStats::audio_duration(5 + second), timings[1].start);
+        assert_eq!(SynthStats::audio_duration(audio.len() - 3), timings[2].start);
+        assert_eq!(SynthStats::audio_duration(audio.len()), timings[2].end);
+
+        // 某一句合成失败
+        let (sink, stream) = start_synth_session(Voice::AfHeart, Default::default(), |request: Request<String>| async move {
+            match request.text.as_str() {
+                "Hi" => Err(KokoroError::ModelReleased),
+                text => Ok((vec![0.; text.len()], SynthStats::default())),
+            }
+        });
+        let result = synth_dialogue(&lines, Duration::ZERO, sink, stream).await;
+        assert!(matches!(result, Err(KokoroError::ModelReleased)));
+        Ok(())
+    }
+}
@@ -0,0 +1,445 @@
+use {
+    crate::{Loudness, SAMPLE_RATE, SynthOptions},
+    std::f32::consts::PI,
+};
+
+/// 分析窗长度：40毫秒
+const FRAME: usize = SAMPLE_RATE as usize / 25;
+/// 输出的帧移为半个窗长
+const HOP: usize = FRAME / 2;
+/// 搜索最佳拼接位置的范围：前后各10毫秒
+const TOLERANCE: isize = SAMPLE_RATE as isize / 100;
+
+fn hann(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2. * PI * i as f32 / len as f32).cos())
+        .collect()
+}
+
+fn sample(samples: &[f32], index: isize) -> f32 {
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| samples.get(i))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// 在`[nominal - TOLERANCE, nominal + TOLERANCE]`中寻找与`natural`处的波形最相似的位置
+fn best_offset(samples: &[f32], natural: isize, nominal: isize) -> isize {
+    let mut best = (nominal, f32::MIN);
+    for candidate in nominal - TOLERANCE..=nominal + TOLERANCE {
+        // 隔一个采样点计算互相关，对语音来说足够准确
+        let score = (0..FRAME as isize)
+            .step_by(2)
+            .map(|i| sample(samples, natural + i) * sample(samples, candidate + i))
+            .sum::<f32>();
+        if score > best.1 {
+            best = (candidate, score);
+        }
+    }
+    best.0
+}
+
+/// 使用WSOLA算法改变语速而不改变音高
+///
+/// `factor`大于1时加快，输出长度约为`samples.len() / factor`。
+pub(crate) fn time_stretch(samples: &[f32], factor: f32) -> Vec<f32> {
+    if !factor.is_finite() || factor <= 0. || (factor - 1.).abs() < 1e-3 || samples.len() < FRAME {
+        return samples.to_vec();
+    }
+
+    let out_len = (samples.len() as f32 / factor).round() as usize;
+    let window = hann(FRAME);
+    let mut output = vec![0f32; out_len + FRAME];
+    let mut norm = vec![0f32; out_len + FRAME];
+    let mut previous = 0isize;
+    for out_pos in (0..out_len).step_by(HOP) {
+        let nominal = (out_pos as f32 * factor).round() as isize;
+        let offset = if out_pos == 0 {
+            0
+        } else {
+            best_offset(samples, previous + HOP as isize, nominal)
+        };
+        for (i, w) in window.iter().enumerate() {
+            output[out_pos + i] += w * sample(samples, offset + i as isize);
+            norm[out_pos + i] += w;
+        }
+        previous = offset;
+    }
+
+    output.truncate(out_len);
+    output
+        .iter_mut()
+        .zip(norm)
+        .filter(|(_, n)| *n > 1e-3)
+        .for_each(|(o, n)| *o /= n);
+    output
+}
+
+/// 寻找零交叉点的范围：5毫秒
+const ZERO_CROSSING_SEARCH: usize = SAMPLE_RATE as usize / 200;
+
+fn is_zero_crossing(samples: &[f32], i: usize) -> bool {
+    samples[i] == 0. || (i > 0 && (samples[i - 1] < 0.) != (samples[i] < 0.))
+}
+
+/// 淡入开头的`len`个采样，`len`为0时去掉第一个零交叉点之前的部分
+pub(crate) fn fade_in(samples: &mut Vec<f32>, len: usize) {
+    if len == 0 {
+        let search = ZERO_CROSSING_SEARCH.min(samples.len());
+        if let Some(i) = (0..search).find(|&i| is_zero_crossing(samples, i)) {
+            samples.drain(..i);
+        }
+        return;
+    }
+    let len = len.min(samples.len());
+    for (i, sample) in samples[..len].iter_mut().enumerate() {
+        *sample *= ((i as f32 + 0.5) / len as f32 * PI / 2.).sin();
+    }
+}
+
+/// 淡出结尾的`len`个采样，`len`为0时去掉最后一个零交叉点之后的部分
+pub(crate) fn fade_out(samples: &mut Vec<f32>, len: usize) {
+    if len == 0 {
+        let search = ZERO_CROSSING_SEARCH.min(samples.len());
+        if let Some(i) = (samples.len() - search..samples.len())
+            .rev()
+            .find(|&i| is_zero_crossing(samples, i))
+        {
+            samples.truncate(i);
+        }
+        return;
+    }
+    let len = len.min(samples.len());
+    let start = samples.len() - len;
+    for (i, sample) in samples[start..].iter_mut().enumerate() {
+        *sample *= ((i as f32 + 0.5) / len as f32 * PI / 2.).cos();
+    }
+}
+
+/// 把`next`拼接到`output`之后，接缝处做`crossfade`个采样的等功率交叉淡化，`crossfade`为0时在零交叉点处对齐
+pub(crate) fn join(output: &mut Vec<f32>, next: &[f32], crossfade: usize) {
+    if output.is_empty() || next.is_empty() {
+        output.extend_from_slice(next);
+        return;
+    }
+    if crossfade == 0 {
+        let mut next = next.to_vec();
+        fade_out(output, 0);
+        fade_in(&mut next, 0);
+        output.extend(next);
+        return;
+    }
+
+    let len = crossfade.min(output.len()).min(next.len());
+    let start = output.len() - len;
+    for (i, (a, b)) in output[start..].iter_mut().zip(next).enumerate() {
+        let t = (i as f32 + 0.5) / len as f32 * PI / 2.;
+        *a = *a * t.cos() + b * t.sin();
+    }
+    output.extend_from_slice(&next[len..]);
+}
+
+/// 基音周期的搜索范围：60到500Hz
+const MIN_PERIOD: usize = SAMPLE_RATE as usize / 500;
+const MAX_PERIOD: usize = SAMPLE_RATE as usize / 60;
+/// 清音和静音段使用的固定周期：10毫秒
+const UNVOICED_PERIOD: usize = SAMPLE_RATE as usize / 100;
+
+/// 用归一化自相关估计从`start`开始的一帧的基音周期，清音或静音时返回`None`
+fn estimate_period(samples: &[f32], start: usize) -> Option<usize> {
+    let frame = samples.get(start..(start + 2 * MAX_PERIOD).min(samples.len()))?;
+    if frame.len() < 2 * MIN_PERIOD || frame.iter().map(|i| i * i).sum::<f32>() < 1e-4 {
+        return None;
+    }
+    let correlations = (MIN_PERIOD..=MAX_PERIOD.min(frame.len() / 2))
+        .map(|lag| {
+            let (a, b) = (&frame[..frame.len() - lag], &frame[lag..]);
+            let cross = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+            let energy = a.iter().map(|i| i * i).sum::<f32>() * b.iter().map(|i| i * i).sum::<f32>();
+            (lag, cross / energy.sqrt().max(f32::EPSILON))
+        })
+        .collect::<Vec<_>>();
+    let best = correlations.iter().map(|(_, r)| *r).fold(f32::MIN, f32::max);
+    if best < 0.6 {
+        return None;
+    }
+    // 周期的整数倍也有接近的相关性，取第一个接近最大值的峰以避免倍频错误
+    correlations
+        .windows(3)
+        .find(|i| i[1].1 >= 0.9 * best && i[1].1 >= i[0].1 && i[1].1 >= i[2].1)
+        .map(|i| i[1].0)
+}
+
+/// 使用TD-PSOLA算法改变音高，保持时长和共振峰不变
+///
+/// `ratio`是新旧基频之比，大于1时升高音高。
+pub(crate) fn pitch_shift(samples: &[f32], ratio: f32) -> Vec<f32> {
+    if !ratio.is_finite() || ratio <= 0. || (ratio - 1.).abs() < 1e-3 || samples.len() < 2 * MAX_PERIOD {
+        return samples.to_vec();
+    }
+
+    // 每10毫秒估计一次基音周期，再据此放置分析基音标记
+    let periods = (0..samples.len())
+        .step_by(UNVOICED_PERIOD)
+        .map(|i| estimate_period(samples, i.saturating_sub(MAX_PERIOD)))
+        .collect::<Vec<_>>();
+    let mut marks = Vec::new();
+    let mut t = 0;
+    while t < samples.len() {
+        let period = periods[t / UNVOICED_PERIOD];
+        marks.push((t, period.unwrap_or(UNVOICED_PERIOD), period.is_some()));
+        t += period.unwrap_or(UNVOICED_PERIOD);
+    }
+
+    let mut output = vec![0f32; samples.len()];
+    let mut position = 0f32;
+    let mut k = 0;
+    while (position as usize) < samples.len() {
+        // 取离合成位置最近的分析标记，两者时间轴相同，因此时长不变
+        while k + 1 < marks.len() && (marks[k + 1].0 as f32) <= position {
+            k += 1;
+        }
+        if k + 1 < marks.len() && marks[k + 1].0 as f32 - position < position - marks[k].0 as f32 {
+            k += 1;
+        }
+        let (mark, period, voiced) = marks[k];
+        let hop = if voiced { period as f32 / ratio } else { period as f32 };
+        // 长度为两个周期的汉宁窗在帧移为hop时叠加的幅度约为period / hop
+        let scale = hop / period as f32;
+        let center = position.round() as isize;
+        for (i, w) in hann(2 * period).iter().enumerate() {
+            let offset = i as isize - period as isize;
+            if let Ok(dst) = usize::try_from(center + offset)
+                && dst < output.len()
+            {
+                output[dst] += scale * w * sample(samples, mark as isize + offset);
+            }
+        }
+        position += hop;
+    }
+
+    output
+}
+
+/// 双二阶IIR滤波器，`a`省略了归一化后的a0
+fn biquad(samples: &[f64], b: [f64; 3], a: [f64; 2]) -> Vec<f64> {
+    let (mut x1, mut x2, mut y1, mut y2) = (0., 0., 0., 0.);
+    samples
+        .iter()
+        .map(|&x| {
+            let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+            (x2, x1, y2, y1) = (x1, x, y1, y);
+            y
+        })
+        .collect()
+}
+
+/// ITU-R BS.1770的K计权滤波，系数按采样率由模拟原型计算
+fn k_weighting(samples: &[f32]) -> Vec<f64> {
+    let rate = SAMPLE_RATE as f64;
+    let samples = samples.iter().map(|&i| i as f64).collect::<Vec<_>>();
+
+    // 高架滤波器，模拟头部的声学效应
+    let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain / 20.);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1. + k / q + k * k;
+    let shelf = biquad(
+        &samples,
+        [(vh + vb * k / q + k * k) / a0, 2. * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        [2. * (k * k - 1.) / a0, (1. - k / q + k * k) / a0],
+    );
+
+    // 高通滤波器（RLB计权）
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1. + k / q + k * k;
+    biquad(&shelf, [1., -2., 1.], [2. * (k * k - 1.) / a0, (1. - k / q + k * k) / a0])
+}
+
+/// EBU R128积分响度，单位LUFS，全部低于绝对门限时返回`None`
+pub(crate) fn integrated_loudness(samples: &[f32]) -> Option<f32> {
+    // 400毫秒的测量块，75%重叠；不足一个块时整段作为一个块
+    const BLOCK: usize = SAMPLE_RATE as usize * 2 / 5;
+    const STEP: usize = BLOCK / 4;
+    let weighted = k_weighting(samples);
+    let blocks = if weighted.len() < BLOCK {
+        vec![&weighted[..]]
+    } else {
+        (0..=weighted.len() - BLOCK)
+            .step_by(STEP)
+            .map(|i| &weighted[i..i + BLOCK])
+            .collect()
+    };
+    let powers = blocks
+        .iter()
+        .filter(|i| !i.is_empty())
+        .map(|i| i.iter().map(|x| x * x).sum::<f64>() / i.len() as f64)
+        .collect::<Vec<_>>();
+    let loudness = |power: f64| -0.691 + 10. * power.log10();
+    let gated = |threshold: f64| {
+        let powers = powers
+            .iter()
+            .filter(|&&i| loudness(i) > threshold)
+            .collect::<Vec<_>>();
+        (!powers.is_empty()).then(|| powers.iter().copied().sum::<f64>() / powers.len() as f64)
+    };
+
+    let relative = loudness(gated(-70.)?) - 10.;
+    Some(loudness(gated(relative.max(-70.))?) as f32)
+}
+
+/// 去掉开头和结尾能量低于`threshold_db`的部分，两端各保留20毫秒余量以免截掉弱辅音，全部低于门限时返回空切片
+pub(crate) fn trim_silence(samples: &[f32], threshold_db: f32) -> &[f32] {
+    const FRAME: usize = SAMPLE_RATE as usize / 100;
+    const MARGIN: usize = 2 * FRAME;
+    let threshold = 10f32.powf(threshold_db / 20.);
+    let loud = |frame: &[f32]| (frame.iter().map(|i| i * i).sum::<f32>() / frame.len() as f32).sqrt() >= threshold;
+    let frames = samples.chunks(FRAME).collect::<Vec<_>>();
+    let (Some(first), Some(last)) = (frames.iter().position(|i| loud(i)), frames.iter().rposition(|i| loud(i))) else {
+        return &[];
+    };
+
+    let start = (first * FRAME).saturating_sub(MARGIN);
+    let end = ((last + 1) * FRAME + MARGIN).min(samples.len());
+    &samples[start..end]
+}
+
+/// 按选项对合成的音频做推理之后的处理：音高偏移、响度归一化和增益
+pub(crate) fn post_process(mut samples: Vec<f32>, options: &SynthOptions) -> Vec<f32> {
+    if options.pitch_semitones != 0. {
+        samples = pitch_shift(&samples, 2f32.powf(options.pitch_semitones / 12.));
+    }
+
+    let mut gain_db = options.gain_db;
+    if let Some(loudness) = options.loudness {
+        let (target, current) = match loudness {
+            Loudness::Peak(target) => (target, samples.iter().map(|i| i.abs()).fold(0., f32::max)),
+            Loudness::Rms(target) => (
+                target,
+                (samples.iter().map(|i| i * i).sum::<f32>() / samples.len().max(1) as f32).sqrt(),
+            ),
+            Loudness::Lufs(target) => (target, integrated_loudness(&samples).map_or(0., |i| 10f32.powf(i / 20.))),
+        };
+        // 静音无法归一化
+        if current > 0. {
+            gain_db += target - 20. * current.log10();
+        }
+    }
+    if gain_db != 0. {
+        let gain = 10f32.powf(gain_db / 20.);
+        // 放大后超出范围的采样直接截断
+        samples.iter_mut().for_each(|i| *i = (*i * gain).clamp(-1., 1.));
+    }
+
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2. * PI * freq * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|i| (i[0] < 0.) != (i[1] < 0.)).count()
+    }
+
+    #[test]
+    fn test_time_stretch_length() {
+        let samples = sine(220., SAMPLE_RATE as usize);
+        assert_eq!(samples.len(), time_stretch(&samples, 1.).len());
+        assert_eq!(SAMPLE_RATE as usize / 2, time_stretch(&samples, 2.).len());
+        assert_eq!(SAMPLE_RATE as usize * 2, time_stretch(&samples, 0.5).len());
+    }
+
+    #[test]
+    fn test_time_stretch_keeps_pitch() {
+        let samples = sine(220., SAMPLE_RATE as usize);
+        for factor in [0.75, 1.25, 1.5] {
+            let stretched = time_stretch(&samples, factor);
+            // 每秒的过零次数反映频率，两端各去掉一帧以避开边缘效应
+            let middle = &stretched[FRAME..stretched.len() - FRAME];
+            let rate = zero_crossings(middle) as f32 / middle.len() as f32 * SAMPLE_RATE as f32;
+            assert!((rate - 440.).abs() < 15., "factor {}: {} crossings per second", factor, rate);
+        }
+    }
+
+    fn sawtooth(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (freq * i as f32 / SAMPLE_RATE as f32).fract() * 0.5 - 0.25)
+            .collect()
+    }
+
+    #[test]
+    fn test_pitch_shift() {
+        let samples = sawtooth(150., SAMPLE_RATE as usize);
+        assert_eq!(Some(160), estimate_period(&samples, 0));
+        for (semitones, expected) in [(12., 80), (-5., 214)] {
+            let shifted = pitch_shift(&samples, 2f32.powf(semitones / 12.));
+            assert_eq!(samples.len(), shifted.len());
+            let period = estimate_period(&shifted, SAMPLE_RATE as usize / 2).unwrap();
+            assert!(period.abs_diff(expected) <= 3, "{} semitones: period {}", semitones, period);
+        }
+    }
+
+    #[test]
+    fn test_loudness() {
+        // 满幅的997Hz正弦波的响度为-3.01LUFS
+        let samples = sine(997., SAMPLE_RATE as usize * 2);
+        let lufs = integrated_loudness(&samples).unwrap();
+        assert!((lufs + 3.01).abs() < 0.1, "{}", lufs);
+        assert_eq!(None, integrated_loudness(&[0.; SAMPLE_RATE as usize]));
+
+        let options = SynthOptions {
+            loudness: Some(Loudness::Lufs(-23.)),
+            ..Default::default()
+        };
+        let normalized = post_process(samples.clone(), &options);
+        assert!((integrated_loudness(&normalized).unwrap() + 23.).abs() < 0.1);
+
+        let options = SynthOptions {
+            loudness: Some(Loudness::Peak(-6.)),
+            gain_db: -6.,
+            ..Default::default()
+        };
+        let peak = post_process(samples, &options).iter().map(|i| i.abs()).fold(0., f32::max);
+        assert!((20. * peak.log10() + 12.).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_trim_silence() {
+        let mut samples = vec![0.; SAMPLE_RATE as usize / 2];
+        samples.extend(sine(440., SAMPLE_RATE as usize));
+        samples.extend(vec![0.001; SAMPLE_RATE as usize / 2]);
+        let trimmed = trim_silence(&samples, -40.);
+        // 两端各保留20毫秒
+        assert_eq!(SAMPLE_RATE as usize * 26 / 25, trimmed.len());
+        assert!(trim_silence(&samples[..SAMPLE_RATE as usize / 2], -40.).is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        let mut output = vec![0.5; 1000];
+        join(&mut output, &[-0.5; 1000], 240);
+        assert_eq!(1760, output.len());
+        // 交叉淡化之后相邻采样之间没有突变
+        assert!(output.windows(2).all(|i| (i[0] - i[1]).abs() < 0.01));
+
+        let mut output = sine(440., 1000);
+        join(&mut output, &sine(440., 1000)[25..], 0);
+        assert!(output.len() < 1975);
+        assert!(output.windows(2).all(|i| (i[0] - i[1]).abs() < 0.2));
+
+        let mut output = vec![0.5; 100];
+        fade_out(&mut output, 50);
+        fade_in(&mut output, 50);
+        assert!(output[0].abs() < 0.02 && output[99].abs() < 0.02);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::G2PError;
+use crate::{G2PError, UnknownPhoneme};
 use bincode::error::DecodeError;
 use ndarray::ShapeError;
 use ort::Error as OrtError;
+use serde_json::Error as JsonError;
 use std::{
     error::Error,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
@@ -11,14 +12,22 @@ use std::{
 
 #[derive(Debug)]
 pub enum KokoroError {
+    AudioTooLong(usize),
     Decode(DecodeError),
     G2P(G2PError),
     Io(IoError),
+    Json(JsonError),
     ModelReleased,
+    ModelUnsupported(String),
+    OptionInvalid(String),
     Ort(OrtError),
+    ScriptInvalid(String),
     Send(String),
     Shape(ShapeError),
+    SpeedOutOfRange(f32),
     SystemTime(SystemTimeError),
+    UnknownPhonemes(Vec<UnknownPhoneme>),
+    VocabularyInvalid(String),
     VoiceNotFound(String),
     VoiceVersionInvalid(String),
 }
@@ -27,14 +36,31 @@ impl Display for KokoroError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "KokoroError: ")?;
         match self {
+            Self::AudioTooLong(samples) => write!(f, "AudioTooLong({} samples)", samples),
             Self::Decode(e) => Display::fmt(e, f),
             Self::G2P(e) => Display::fmt(e, f),
             Self::Io(e) => Display::fmt(e, f),
+            Self::Json(e) => Display::fmt(e, f),
             Self::Ort(e) => Display::fmt(e, f),
             Self::ModelReleased => write!(f, "ModelReleased"),
+            Self::ModelUnsupported(msg) => write!(f, "ModelUnsupported({})", msg),
+            Self::OptionInvalid(msg) => write!(f, "OptionInvalid({})", msg),
+            Self::ScriptInvalid(msg) => write!(f, "ScriptInvalid({})", msg),
             Self::Send(e) => Display::fmt(e, f),
             Self::Shape(e) => Display::fmt(e, f),
+            Self::SpeedOutOfRange(speed) => write!(f, "SpeedOutOfRange({})", speed),
             Self::SystemTime(e) => Display::fmt(e, f),
+            Self::UnknownPhonemes(unknown) => {
+                write!(f, "UnknownPhonemes(")?;
+                for (n, i) in unknown.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} at {} in {}", i.symbol, i.position, i.word)?;
+                }
+                write!(f, ")")
+            }
+            Self::VocabularyInvalid(msg) => write!(f, "VocabularyInvalid({})", msg),
             Self::VoiceNotFound(name) => write!(f, "VoiceNotFound({})", name),
             Self::VoiceVersionInvalid(msg) => write!(f, "VoiceVersionInvalid({})", msg),
         }
@@ -55,6 +81,12 @@ impl From<DecodeError> for KokoroError {
     }
 }
 
+impl From<JsonError> for KokoroError {
+    fn from(value: JsonError) -> Self {
+        Self::Json(value)
+    }
+}
+
 impl From<OrtError> for KokoroError {
     fn from(value: OrtError) -> Self {
         Self::Ort(value)
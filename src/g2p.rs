@@ -1,8 +1,9 @@
 /// 文本到国际音标的转换
+mod heteronym;
 mod v10;
 mod v11;
 
-use super::PinyinError;
+use super::{Accent, PinyinError, letters_to_ipa};
 use chinese_number::{ChineseCase, ChineseCountMethod, ChineseVariant, NumberToChinese};
 #[cfg(feature = "use-cmudict")]
 use cmudict_fast::{Cmudict, Error as CmudictError};
@@ -10,20 +11,37 @@ use pinyin::ToPinyin;
 use regex::{Captures, Error as RegexError, Regex};
 use std::{
     error::Error,
+    ffi::{CStr, CString, NulError, c_char, c_int},
     fmt::{Display, Formatter, Result as FmtResult},
+    str::Utf8Error,
+    sync::{LazyLock, Mutex},
 };
 
+static SENTENCE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"([\u4E00-\u9FFF]+)|([，。：·？、！《》（）【】〖〗〔〕""''〈〉…—　]+)|([\u0000-\u024F\u0900-\u097F]+)+"#,
+    )
+    .unwrap()
+});
+// Keep apostrophes within words to handle contractions like "you're"
+static EN_WORD_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[\w']+|[^\w']+").unwrap());
+static NUM_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\d+(\.\d+)?"#).unwrap());
+// 只匹配ASCII数字，整数部分可以用逗号三位一组分隔
+static EN_NUM_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:[0-9]{1,3}(?:,[0-9]{3})+|[0-9]+)(?:\.[0-9]+)?").unwrap());
+/// 后面的点不表示句末的英语称谓缩写
+const EN_TITLES: [&str; 5] = ["mr", "mrs", "ms", "dr", "prof"];
+static JIEBA: LazyLock<jieba_rs::Jieba> = LazyLock::new(jieba_rs::Jieba::new);
+
 #[derive(Debug)]
 pub enum G2PError {
     #[cfg(feature = "use-cmudict")]
     CmudictError(CmudictError),
     EnptyData,
-    #[cfg(not(feature = "use-cmudict"))]
-    Nul(std::ffi::NulError),
+    Nul(NulError),
     Pinyin(PinyinError),
     Regex(RegexError),
-    #[cfg(not(feature = "use-cmudict"))]
-    Utf8(std::str::Utf8Error),
+    Utf8(Utf8Error),
 }
 
 impl Display for G2PError {
@@ -33,11 +51,9 @@ impl Display for G2PError {
             #[cfg(feature = "use-cmudict")]
             Self::CmudictError(e) => Display::fmt(e, f),
             Self::EnptyData => Display::fmt("EmptyData", f),
-            #[cfg(not(feature = "use-cmudict"))]
             Self::Nul(e) => Display::fmt(e, f),
             Self::Pinyin(e) => Display::fmt(e, f),
             Self::Regex(e) => Display::fmt(e, f),
-            #[cfg(not(feature = "use-cmudict"))]
             Self::Utf8(e) => Display::fmt(e, f),
         }
     }
@@ -64,21 +80,253 @@ impl From<CmudictError> for G2PError {
     }
 }
 
-#[cfg(not(feature = "use-cmudict"))]
-impl From<std::ffi::NulError> for G2PError {
-    fn from(value: std::ffi::NulError) -> Self {
+impl From<NulError> for G2PError {
+    fn from(value: NulError) -> Self {
         Self::Nul(value)
     }
 }
 
-#[cfg(not(feature = "use-cmudict"))]
-impl From<std::str::Utf8Error> for G2PError {
-    fn from(value: std::str::Utf8Error) -> Self {
+impl From<Utf8Error> for G2PError {
+    fn from(value: Utf8Error) -> Self {
         Self::Utf8(value)
     }
 }
 
+/// 文本的语言
+///
+/// 决定非中文部分使用哪一份内置的eSpeak词典和音素表进行转换。
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    French,
+    Italian,
+    Portuguese,
+    Hindi,
+    Japanese,
+    Chinese,
+}
+
+impl Language {
+    /// (词典, 翻译器名称, 音素表名称, dictrules条件, 字母偏移)，对应espeak-ng-data/lang中的语音定义
+    fn espeak_voice(&self, accent: Accent) -> (&'static [u8], &'static CStr, &'static CStr, c_int, c_int) {
+        static EN_DICT: &[u8] = include_bytes!("../espeak-ng-data/en_dict");
+        static ES_DICT: &[u8] = include_bytes!("../espeak-ng-data/es_dict");
+        static FR_DICT: &[u8] = include_bytes!("../espeak-ng-data/fr_dict");
+        static IT_DICT: &[u8] = include_bytes!("../espeak-ng-data/it_dict");
+        static PT_DICT: &[u8] = include_bytes!("../espeak-ng-data/pt_dict");
+        static HI_DICT: &[u8] = include_bytes!("../espeak-ng-data/hi_dict");
+
+        match self {
+            Self::English | Self::Japanese | Self::Chinese => match accent {
+                Accent::British => (EN_DICT, c"en", c"en", 0, 0),
+                _ => (EN_DICT, c"en", c"en-us", 1 << 3 | 1 << 6, 0),
+            },
+            Self::Spanish => (ES_DICT, c"es", c"es", 1 << 1, 0),
+            Self::French => (FR_DICT, c"fr", c"fr", 1 << 1, 0),
+            Self::Italian => (IT_DICT, c"it", c"it", 0, 0),
+            // Kokoro的p*_语音为巴西葡萄牙语（pt-BR），其余口音按欧洲葡萄牙语（pt-PT）处理
+            Self::Portuguese if accent == Accent::Brazilian => (PT_DICT, c"pt", c"pt", 1 << 2, 0),
+            Self::Portuguese => (PT_DICT, c"pt", c"pt-pt", 1 << 1, 0),
+            Self::Hindi => (HI_DICT, c"hi", c"hi", 0, 0x900),
+        }
+    }
+}
+
+/// 词典中有多个候选读音时的选择方式
+///
+/// 只有启用`use-cmudict`特性时单词才会有多个候选读音，默认总是使用第一个，保证相同的文本每次得到相同的音素。
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VariantSelection {
+    /// 使用词典中的第一个读音
+    #[default]
+    First,
+    /// 由种子和单词共同决定读音，相同的种子总是得到相同的结果
+    Seeded(u64),
+    /// 每次随机选择读音
+    Random,
+}
+
+impl VariantSelection {
+    #[cfg_attr(not(feature = "use-cmudict"), allow(dead_code))]
+    fn select(&self, word: &str, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        match self {
+            Self::First => 0,
+            Self::Seeded(seed) => {
+                // FNV-1a hash of the word mixed with the seed (splitmix64), stable across runs and platforms
+                let mut x = word.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+                    (h ^ b as u64).wrapping_mul(0x100000001b3)
+                }) ^ seed;
+                x = x.wrapping_add(0x9e3779b97f4a7c15);
+                x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+                x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+                x ^= x >> 31;
+                (x % count as u64) as usize
+            }
+            Self::Random => rand::random_range(0..count),
+        }
+    }
+}
+
+/// 文本到音素转换的选项
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct G2POptions {
+    pub language: Language,
+    /// 英语的方言，参见[`g2p_with_language`]
+    pub accent: Accent,
+    pub variant: VariantSelection,
+}
+
+/// 单词音素的来源
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum G2PSource {
+    /// 内置的缩写、缩略词和英式读音词表
+    Lexicon,
+    /// 按上下文选定的同形异音词读音
+    Heteronym,
+    /// 内置的eSpeak引擎
+    Espeak,
+    /// CMUdict词典，需要启用`use-cmudict`特性
+    Cmudict,
+    /// 逐个字母拼读
+    Letters,
+    /// 中文按拼音转换
+    Pinyin,
+    /// 标点等原样保留的文本
+    Verbatim,
+    /// 无法处理而被丢弃的文本
+    Dropped,
+}
+
+/// 分词后的一个词及其转换结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G2PWord {
+    /// 分词器输出的原文，v1.1模型的数字已经转换为中文
+    pub text: String,
+    /// 中文的jieba词性标注
+    pub tag: Option<String>,
+    pub source: G2PSource,
+    /// 转换之后又应用的美式英语修正规则
+    pub post_fix: Option<&'static str>,
+    pub phonemes: String,
+}
+
+/// 美式英语的修正规则：(规则名称, eSpeak的输出, 修正后的音素)，按顺序在整段音素上替换
+///
+/// `en-us`规则已经给出现代美式读音（what → wˌʌt，job → dʒˈɑːb），这里只处理词表之外的符号。
+const POST_FIXES: [(&str, &str, &str); 1] = [
+    // eSpeak的w#音素（what、which、why中的wh）在部分语音中输出为ʍ，美式英语读作w
+    ("wh", "ʍ", "w"),
+];
+
+/// 对单个词应用修正规则，返回修正后的音素和第一条生效的规则
+fn post_fix_word(phonemes: &str) -> (String, Option<&'static str>) {
+    let mut result = phonemes.to_owned();
+    let mut rule = None;
+    for (name, from, to) in POST_FIXES {
+        if result.contains(from) {
+            result = result.replace(from, to);
+            rule = rule.or(Some(name));
+        }
+    }
+    (result, rule)
+}
+
+/// 使用内置的eSpeak引擎转换单个单词
+///
+/// 引擎的状态是全局的，切换语言时需要重新初始化，因此所有调用都在同一把锁内完成。
+fn word2ipa_espeak(word: &str, language: Language, accent: Accent) -> Result<String, G2PError> {
+    unsafe extern "C" {
+        fn TextToPhonemes(text: *const c_char) -> *const c_char;
+        fn Initialize(data_dictlist: *const c_char);
+        fn SelectPhonemeTable(data_phontab: *const c_char, name: *const c_char) -> c_int;
+        fn SetDictRules(condition: c_int);
+        fn SelectTranslator(name: *const c_char);
+        fn SetIndicLetters(offset: c_int);
+    }
+
+    static PHONTAB: &[u8] = include_bytes!("../espeak-ng-data/phontab");
+    static CURRENT: Mutex<Option<(&CStr, &CStr)>> = Mutex::new(None);
+
+    let word = CString::new(word.to_lowercase())?;
+    let (dict, translator, phtab, dictrules, offset) = language.espeak_voice(accent);
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe {
+        if *current != Some((translator, phtab)) {
+            Initialize(dict.as_ptr() as _);
+            SelectPhonemeTable(PHONTAB.as_ptr() as _, phtab.as_ptr());
+            SetDictRules(dictrules);
+            // 翻译器会修改字母分组，必须在SetIndicLetters之前调用
+            SelectTranslator(translator.as_ptr());
+            if offset > 0 {
+                SetIndicLetters(offset);
+            }
+            *current = Some((translator, phtab));
+        }
+
+        let res = TextToPhonemes(word.as_ptr());
+        let post_fix = language == Language::English && accent != Accent::British;
+        Ok(clean_espeak(CStr::from_ptr(res).to_str()?, post_fix))
+    }
+}
+
+/// 去掉eSpeak输出中不在词表里的标记：重音级别的数字和连读单词之间的`-`，并把ʍ换成w。
+/// 美式英语的ʍ留给[`POST_FIXES`]处理，分析结果中会记录修正规则。
+fn clean_espeak(phonemes: &str, post_fix: bool) -> String {
+    phonemes
+        .chars()
+        .filter(|i| !i.is_ascii_digit() && *i != '-')
+        .map(|i| if i == 'ʍ' && !post_fix { 'w' } else { i })
+        .collect()
+}
+
+fn word2ipa(word: &str, options: &G2POptions) -> Result<(String, G2PSource), G2PError> {
+    let language = options.language;
+    match language {
+        Language::English if options.accent == Accent::British => word2ipa_en_gb(word),
+        Language::English | Language::Japanese | Language::Chinese => {
+            word2ipa_en(word, options.variant)
+        }
+        _ => Ok((word2ipa_espeak(word, language, options.accent)?, G2PSource::Espeak)),
+    }
+}
+
+/// 常用词中多音字的读音，`pinyin`按单字给出的默认读音在这些词中是错的
+const ZH_PHRASES: [(&str, &str); 19] = [
+    ("重庆", "chong2 qing4"),
+    ("重新", "chong2 xin1"),
+    ("重复", "chong2 fu4"),
+    ("长城", "chang2 cheng2"),
+    ("长江", "chang2 jiang1"),
+    ("长度", "chang2 du4"),
+    ("长期", "chang2 qi1"),
+    ("很长", "hen3 chang2"),
+    ("银行", "yin2 hang2"),
+    ("行长", "hang2 zhang3"),
+    ("行业", "hang2 ye4"),
+    ("音乐", "yin1 yue4"),
+    ("教书", "jiao1 shu1"),
+    ("睡觉", "shui4 jiao4"),
+    ("首都", "shou3 du1"),
+    ("成都", "cheng2 du1"),
+    ("便宜", "pian2 yi2"),
+    ("空调", "kong1 tiao2"),
+    ("出差", "chu1 chai1"),
+];
+
 fn word2ipa_zh(word: &str) -> Result<String, G2PError> {
+    if let Some((_, pinyin)) = ZH_PHRASES.iter().find(|(i, _)| *i == word) {
+        let mut result = String::new();
+        for i in pinyin.split(' ') {
+            result.push_str(&v10::py2ipa(i)?);
+        }
+        return Ok(result);
+    }
+
     let iter = word.chars().map(|i| match i.to_pinyin() {
         None => Ok(i.to_string()),
         Some(p) => v10::py2ipa(p.with_tone_num_end()),
@@ -92,12 +340,11 @@ fn word2ipa_zh(word: &str) -> Result<String, G2PError> {
 }
 
 #[cfg(feature = "use-cmudict")]
-fn word2ipa_en(word: &str) -> Result<String, G2PError> {
-    use super::{arpa_to_ipa, letters_to_ipa};
+fn word2ipa_en(word: &str, variant: VariantSelection) -> Result<(String, G2PSource), G2PError> {
+    use super::arpa_to_ipa;
     use std::{
         io::{Error as IoError, ErrorKind},
         str::FromStr,
-        sync::LazyLock,
     };
 
     fn get_cmudict<'a>() -> Result<&'a Cmudict, CmudictError> {
@@ -112,6 +359,10 @@ fn word2ipa_en(word: &str) -> Result<String, G2PError> {
 
     // Lowercase the word for CMUDict lookup (CMUDict only has lowercase entries)
     let word_lower = word.to_lowercase();
+    // 与eSpeak的路径一致，小写的冠词a读作弱读的ɐ，大写的A（Plan A）仍按字母读
+    if word == "a" {
+        return Ok(("ɐ".to_owned(), G2PSource::Lexicon));
+    }
 
     // Try compound word splitting for common prefixes if word not found
     let Some(rules) = get_cmudict()?.get(&word_lower) else {
@@ -123,34 +374,110 @@ fn word2ipa_en(word: &str) -> Result<String, G2PError> {
                 // Check if both parts exist in dictionary
                 if get_cmudict()?.get(prefix).is_some() && get_cmudict()?.get(rest).is_some() {
                     // Recursively get phonemes for both parts
-                    let prefix_ipa = word2ipa_en(prefix)?;
-                    let rest_ipa = word2ipa_en(rest)?;
-                    return Ok(format!("{}{}", prefix_ipa, rest_ipa));
+                    let (prefix_ipa, _) = word2ipa_en(prefix, variant)?;
+                    let (rest_ipa, _) = word2ipa_en(rest, variant)?;
+                    return Ok((format!("{}{}", prefix_ipa, rest_ipa), G2PSource::Cmudict));
                 }
             }
         }
-        return Ok(letters_to_ipa(word));
+        return Ok((letters_to_ipa(word), G2PSource::Letters));
     };
     if rules.is_empty() {
-        return Ok(word.to_owned());
+        return Ok((word.to_owned(), G2PSource::Verbatim));
     }
-    let i = rand::random_range(0..rules.len());
+    let i = variant.select(&word_lower, rules.len());
     let result = rules[i]
         .pronunciation()
         .iter()
         .map(|i| arpa_to_ipa(&i.to_string()).unwrap_or_default())
         .collect::<String>();
-    Ok(result)
+    Ok((result, G2PSource::Cmudict))
 }
 
-#[cfg(not(feature = "use-cmudict"))]
-fn word2ipa_en(word: &str) -> Result<String, G2PError> {
-    use super::letters_to_ipa;
-    use std::{
-        ffi::{CStr, CString, c_char},
-        sync::Once,
-    };
+/// 转换按上下文选定的同形异音词读音，音标风格与同一口音下eSpeak的输出一致
+fn heteronym2ipa(reading: &heteronym::Reading, accent: Accent) -> String {
+    match accent {
+        Accent::British => reading.gb.to_owned(),
+        #[cfg(feature = "use-cmudict")]
+        _ => reading
+            .arpa
+            .split(' ')
+            .map(|i| super::arpa_to_ipa(i).unwrap_or_default())
+            .collect(),
+        #[cfg(not(feature = "use-cmudict"))]
+        _ => reading.us.to_owned(),
+    }
+}
 
+fn abbreviation_to_ipa(word_lower: &str) -> Option<&'static str> {
+    match word_lower {
+        "ai" => Some("ˈeɪaɪ"),     // "ay-eye"
+        "ui" => Some("juːˈaɪ"),    // "you-eye"
+        "api" => Some("ˈeɪpiːˈaɪ"), // "ay-pee-eye"
+        "cpu" => Some("siːpiːjˈuː"), // "see-pee-you"
+        "gpu" => Some("dʒiːpiːjˈuː"), // "gee-pee-you"
+        "usb" => Some("juːˈɛsbiː"), // "you-ess-bee"
+        _ => None
+    }
+}
+
+/// 英式英语（en-GB）的单词转换
+///
+/// 英式和美式读音的差异（tomato、schedule、can't等）来自eSpeak的`en`和`en-us`两套规则，
+/// 这里只需要使用英式的缩略词读音，并且不做美式英语的修正。
+/// 即使启用了`use-cmudict`特性也使用eSpeak，因为CMUdict只收录美式发音。
+fn word2ipa_en_gb(word: &str) -> Result<(String, G2PSource), G2PError> {
+    let word_lower = word.to_lowercase();
+    if word.contains('\'') || word.contains('\u{2019}') {
+        let contraction_phonemes = match word_lower.replace('\u{2019}', "'").as_str() {
+            "you're" => Some("jɔː"),
+            "they're" => Some("ðeə"),
+            "we're" => Some("wɪə"),
+            "you'll" => Some("juːl"),
+            "i'll" => Some("aɪl"),
+            "he'll" => Some("hiːl"),
+            "she'll" => Some("ʃiːl"),
+            "we'll" => Some("wiːl"),
+            "they'll" => Some("ðeɪl"),
+            "won't" => Some("wəʊnt"),
+            "can't" => Some("kɑːnt"),
+            "don't" => Some("dəʊnt"),
+            "doesn't" => Some("dʌzənt"),
+            "didn't" => Some("dɪdənt"),
+            "wouldn't" => Some("wʊdənt"),
+            "shouldn't" => Some("ʃʊdənt"),
+            "couldn't" => Some("kʊdənt"),
+            "i'm" => Some("aɪm"),
+            "that's" => Some("ðats"),
+            "what's" => Some("wɒts"),
+            "it's" => Some("ɪts"),
+            "let's" => Some("lɛts"),
+            _ => None,
+        };
+        if let Some(phonemes) = contraction_phonemes {
+            return Ok((phonemes.to_owned(), G2PSource::Lexicon));
+        }
+    }
+
+    if let Some(phonemes) = abbreviation_to_ipa(&word_lower) {
+        return Ok((phonemes.to_owned(), G2PSource::Lexicon));
+    }
+
+    // eSpeak单独转换时把冠词a读作字母名称，大写的A（Plan A）仍按字母读
+    if word == "a" {
+        return Ok(("ɐ".to_owned(), G2PSource::Lexicon));
+    }
+
+    if word.chars().count() < 4 && word.chars().all(|c| c.is_ascii_uppercase()) {
+        return Ok((letters_to_ipa(word), G2PSource::Letters));
+    }
+
+    Ok((word2ipa_espeak(word, Language::English, Accent::British)?, G2PSource::Espeak))
+}
+
+/// eSpeak对每个单词只给出一个读音，因此不需要选择
+#[cfg(not(feature = "use-cmudict"))]
+fn word2ipa_en(word: &str, _variant: VariantSelection) -> Result<(String, G2PSource), G2PError> {
     // Handle common contractions that eSpeak pronounces incorrectly
     let word_lower = word.to_lowercase();
     let contraction_phonemes = match word_lower.as_str() {
@@ -182,113 +509,25 @@ fn word2ipa_en(word: &str) -> Result<String, G2PError> {
     if let Some(phonemes) = contraction_phonemes {
         // Only use contraction if word actually has apostrophe or is known contraction
         if word.contains('\'') || word.contains('\u{2019}') {
-            return Ok(phonemes.to_string());
+            return Ok((phonemes.to_string(), G2PSource::Lexicon));
         }
     }
 
     // Handle common abbreviations before they get spelled out letter-by-letter
-    let abbreviation_phonemes = match word_lower.as_str() {
-        "ai" => Some("ˈeɪaɪ"),     // "ay-eye"
-        "ui" => Some("juːˈaɪ"),    // "you-eye"
-        "api" => Some("ˈeɪpiːˈaɪ"), // "ay-pee-eye"
-        "cpu" => Some("siːpiːjˈuː"), // "see-pee-you"
-        "gpu" => Some("dʒiːpiːjˈuː"), // "gee-pee-you"
-        "usb" => Some("juːˈɛsbiː"), // "you-ess-bee"
-        _ => None
-    };
-
-    if let Some(phonemes) = abbreviation_phonemes {
-        return Ok(phonemes.to_string());
+    if let Some(phonemes) = abbreviation_to_ipa(&word_lower) {
+        return Ok((phonemes.to_string(), G2PSource::Lexicon));
     }
 
-    if word.chars().count() < 4 && word.chars().all(|c| c.is_ascii_uppercase()) {
-        return Ok(letters_to_ipa(word));
+    // eSpeak单独转换时把冠词a读作字母名称，大写的A（Plan A）仍按字母读
+    if word == "a" {
+        return Ok(("ɐ".to_owned(), G2PSource::Lexicon));
     }
 
-    unsafe extern "C" {
-        fn TextToPhonemes(text: *const c_char) -> *const ::std::os::raw::c_char;
-        fn Initialize(data_dictlist: *const c_char);
+    if word.chars().count() < 4 && word.chars().all(|c| c.is_ascii_uppercase()) {
+        return Ok((letters_to_ipa(word), G2PSource::Letters));
     }
 
-    unsafe {
-        static INIT: Once = Once::new();
-
-        INIT.call_once(|| {
-            // Priority 1: Try bundled espeak-ng-data (shipped with library)
-            static BUNDLED_EN_DICT: &[u8] = include_bytes!("../espeak-ng-data/en_dict");
-            Initialize(BUNDLED_EN_DICT.as_ptr() as _);
-
-            // Note: If ESPEAK_NG_DATA_DIR override is needed, users can still set it
-            // but bundled data takes priority for consistency
-        });
-
-        let word_lower = word.to_lowercase();
-        let word_cstr = CString::new(word_lower.clone())?.into_raw() as *const c_char;
-        let res = TextToPhonemes(word_cstr);
-        let mut result = CStr::from_ptr(res).to_str()?.to_string();
-
-        // eSpeak sometimes includes lookahead context after ||
-        // e.g., "too" -> "tˈuː||mʌtʃ" which causes "much" to appear twice
-        // Strip everything after || to get only the current word's phonemes
-        if let Some(pos) = result.find("||") {
-            result = result[..pos].to_string();
-        }
-
-        // eSpeak includes stress/prosody markers as ASCII digits (0-9)
-        // v1.0 NOTE: Keep stress markers for better intonation!
-        // Stress markers provide crucial prosody information for natural speech
-        // v0.19 had issues with them, but v1.0 handles them correctly
-        // Examples: "the" → "ðə2", "time" → "t2ˈaɪmɛ", "improvement" → "ˈɪmpɹəʊvɪmˌɛnt"
-        let mut cleaned_result = result.clone(); // Keep stress markers!
-
-        // eSpeak also appends context words that should be stripped
-        // Examples:
-        // "this" → "ðˈɪswˌɒn" (includes "one")
-        // "will" → "wˈɪltə" (includes "to")
-        // "fastest" → "fˈastɛsənt" (includes "escent/ascent")
-        // "for" → "fˈɔːwɒn" (includes "one")
-        // "that" → "ðɐthɐzbˈɪn" (includes "has been")
-        // "should" → "ʃˈʊdhavtə" (includes "have" + "to")
-        // "we" → "wiːʃˈal" (includes "shall")
-        // "adjust" → "ɐdʒˈʌsənt" (includes "ənt")
-        // These are common context suffixes that need removal
-        // Ordered by length (longest first) to match greedily
-        let context_suffixes = [
-            "hɐzbˈɪn", // "has been"
-            "ɛsənt",   // "escent/ascent" (appears in superlatives like "fastest")
-            "ɔnðə",    // "on the"
-            "ʃˈal",    // "shall"
-            "wˌɒn",    // "one" (with stress)
-            "wɒn",     // "one" (without stress)
-            "ɪntʊ",    // "into" (when it's context)
-            "ənt",     // schwa + "nt" suffix (appears in adjust, etc.)
-            "hav",     // "have"
-            "tə",      // "to"
-            "ðə",      // "the"
-        ];
-
-        for suffix in &context_suffixes {
-            if cleaned_result.ends_with(suffix) && cleaned_result.len() > suffix.len() {
-                cleaned_result = cleaned_result[..cleaned_result.len() - suffix.len()].to_string();
-                break;
-            }
-        }
-
-        // Fix archaic "hw" pronunciation to modern American English "w"
-        // eSpeak uses "hw" for words like "what", "when", "where", "which", "why"
-        // Modern American English pronounces these with just "w" (not "hw")
-        // Examples:
-        //   "what" → "hwˈət" should be "wˈət"
-        //   "when" → "hwˈɛn" should be "wˈɛn"
-        //   "where" → "hwˈɛɹ" should be "wˈɛɹ"
-        //   "which" → "hwˈɪtʃ" should be "wˈɪtʃ"
-        //   "why" → "hwˈaɪ" should be "wˈaɪ"
-        if cleaned_result.starts_with("hw") {
-            cleaned_result = cleaned_result.replacen("hw", "w", 1);
-        }
-
-        Ok(cleaned_result)
-    }
+    Ok((word2ipa_espeak(word, Language::English, Accent::American)?, G2PSource::Espeak))
 }
 
 fn to_half_shape(text: &str) -> String {
@@ -304,7 +543,7 @@ fn to_half_shape(text: &str) -> String {
             '）' => result.push_str(")"),
             // 简单替换规则
             '、' | '，' => result.push_str(","),
-            '。' => result.push_str("."),
+            '。' | '।' => result.push('.'),
             '！' => result.push_str("!"),
             '：' => result.push_str(":"),
             '；' => result.push_str(";"),
@@ -318,10 +557,9 @@ fn to_half_shape(text: &str) -> String {
     result
 }
 
-fn num_repr(text: &str) -> Result<String, G2PError> {
-    let regex = Regex::new(r#"\d+(\.\d+)?"#)?;
-    Ok(regex
-        .replace(text, |caps: &Captures| {
+fn num_repr(text: &str) -> String {
+    NUM_PATTERN
+        .replace_all(text, |caps: &Captures| {
             let text = &caps[0];
             if let Ok(num) = text.parse::<f64>() {
                 num.to_chinese(
@@ -341,21 +579,192 @@ fn num_repr(text: &str) -> Result<String, G2PError> {
                 text.to_owned()
             }
         })
-        .to_string())
+        .to_string()
+}
+
+const EN_ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+    "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const EN_TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const EN_SCALES: [&str; 7] = ["", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion"];
+
+/// 小于1000的数的英语读法，英式英语在百位之后加and
+fn hundreds_to_english(n: u64, british: bool, words: &mut Vec<&'static str>) {
+    if n >= 100 {
+        words.extend([EN_ONES[(n / 100) as usize], "hundred"]);
+        if british && !n.is_multiple_of(100) {
+            words.push("and");
+        }
+    }
+    match n % 100 {
+        0 => (),
+        n @ 1..20 => words.push(EN_ONES[n as usize]),
+        n => {
+            words.push(EN_TENS[(n / 10) as usize]);
+            if !n.is_multiple_of(10) {
+                words.push(EN_ONES[(n % 10) as usize]);
+            }
+        }
+    }
+}
+
+/// 把数字转换为英语单词，整数按三位一组读出，小数点之后逐位读出
+fn num_repr_en(text: &str, british: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for i in EN_NUM_PATTERN.find_iter(text) {
+        let (int, frac) = i.as_str().split_once('.').unwrap_or((i.as_str(), ""));
+        let Ok(mut n) = int.replace(',', "").parse::<u64>() else {
+            continue;
+        };
+        let mut groups = Vec::new();
+        while n > 0 {
+            groups.push(n % 1000);
+            n /= 1000;
+        }
+        let mut words = Vec::new();
+        if groups.is_empty() {
+            words.push(EN_ONES[0]);
+        }
+        for (scale, group) in groups.into_iter().enumerate().rev().filter(|(_, i)| *i != 0) {
+            hundreds_to_english(group, british, &mut words);
+            if scale > 0 {
+                words.push(EN_SCALES[scale]);
+            }
+        }
+        if !frac.is_empty() {
+            words.push("point");
+            words.extend(frac.chars().filter_map(|i| i.to_digit(10)).map(|i| EN_ONES[i as usize]));
+        }
+        result.push_str(&text[last..i.start()]);
+        result.push_str(&words.join(" "));
+        // 221B这样紧跟字母的数字与字母分开读
+        if text[i.end()..].starts_with(char::is_alphabetic) {
+            result.push(' ');
+        }
+        last = i.end();
+    }
+    result.push_str(&text[last..]);
+    result
 }
 
 pub fn g2p(text: &str, use_v11: bool) -> Result<String, G2PError> {
-    // Only convert numbers to Chinese for v1.1 (Chinese model)
-    // v1.0 is English and should keep numbers as-is or spell them out
-    let text = if use_v11 { num_repr(&text)? } else { text.to_string() };
-    let sentence_pattern = Regex::new(
-        r#"([\u4E00-\u9FFF]+)|([，。：·？、！《》（）【】〖〗〔〕""''〈〉…—　]+)|([\u0000-\u00FF]+)+"#,
-    )?;
-    // Keep apostrophes within words to handle contractions like "you're"
-    let en_word_pattern = Regex::new(r"[\w']+|[^\w']+")?;
-    let jieba = jieba_rs::Jieba::new();
+    g2p_with_language(text, Language::English, Accent::American, use_v11)
+}
+
+/// 按指定语言将文本转换为音素
+///
+/// 中文部分始终按中文处理，其余拉丁字母和天城文部分使用`language`对应的eSpeak词典。
+/// 中文和日语语音中夹杂的拉丁字母按英语处理。
+/// 英语按`accent`区分方言：`Accent::British`使用英式词典且不做美式英语的修正，其余按美式英语处理。
+pub fn g2p_with_language(
+    text: &str,
+    language: Language,
+    accent: Accent,
+    use_v11: bool,
+) -> Result<String, G2PError> {
+    let options = G2POptions {
+        language,
+        accent,
+        ..Default::default()
+    };
+    g2p_with_options(text, &options, use_v11)
+}
+
+/// 按选项将文本转换为音素
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{G2POptions, VariantSelection, g2p_with_options};
+///
+/// let options = G2POptions {
+///     variant: VariantSelection::Seeded(42),
+///     ..Default::default()
+/// };
+/// let phonemes = g2p_with_options("read it again", &options, false).unwrap();
+/// assert_eq!(phonemes, g2p_with_options("read it again", &options, false).unwrap());
+/// ```
+///
+pub fn g2p_with_options(text: &str, options: &G2POptions, use_v11: bool) -> Result<String, G2PError> {
+    g2p_traced(text, options, use_v11, None)
+}
+
+/// 按选项将文本转换为音素，返回每个词的分词结果、音素来源和音素
+///
+/// 用于排查读音错误：拼接所有词的音素大致就是[`g2p_with_options`]的结果，无法处理的文本以[`G2PSource::Dropped`]列出。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{G2POptions, G2PSource, g2p_words};
+///
+/// let words = g2p_words("AI 你好", &G2POptions::default(), false).unwrap();
+/// // 启用CMUdict时AI直接从词典中查到
+/// let english = if cfg!(feature = "use-cmudict") { G2PSource::Cmudict } else { G2PSource::Lexicon };
+/// assert_eq!(("AI", english), (words[0].text.as_str(), words[0].source));
+/// assert_eq!(Some("l"), words[1].tag.as_deref());
+/// assert_eq!(G2PSource::Pinyin, words[1].source);
+/// ```
+///
+pub fn g2p_words(text: &str, options: &G2POptions, use_v11: bool) -> Result<Vec<G2PWord>, G2PError> {
+    let mut words = Vec::new();
+    g2p_traced(text, options, use_v11, Some(&mut words))?;
+    Ok(words)
+}
+
+/// 转换文本，`words`不为`None`时记录每个词的转换过程
+pub(crate) fn g2p_traced(
+    text: &str,
+    options: &G2POptions,
+    use_v11: bool,
+    mut words: Option<&mut Vec<G2PWord>>,
+) -> Result<String, G2PError> {
+    let accent = options.accent;
+    let original_language = options.language;
+    let options = G2POptions {
+        language: match options.language {
+            Language::Japanese | Language::Chinese => Language::English,
+            l => l,
+        },
+        ..*options
+    };
+    let language = options.language;
+    let post_fix = language == Language::English && accent != Accent::British;
+    let mut trace = |text: &str, tag: Option<&str>, source: G2PSource, phonemes: &str| {
+        let Some(words) = words.as_deref_mut() else {
+            return;
+        };
+        let (phonemes, rule) = match source {
+            G2PSource::Verbatim | G2PSource::Dropped | G2PSource::Pinyin => (phonemes.to_owned(), None),
+            _ if post_fix => post_fix_word(phonemes),
+            _ => (phonemes.to_owned(), None),
+        };
+        words.push(G2PWord {
+            text: text.to_owned(),
+            tag: tag.map(str::to_owned),
+            source,
+            post_fix: rule,
+            phonemes,
+        });
+    };
+    // v1.1模型和中文语音把数字读作中文，英语和日语语音读作英语，其他语言保留数字
+    let text = match original_language {
+        _ if use_v11 => num_repr(text),
+        Language::Chinese => num_repr(text),
+        Language::English | Language::Japanese => num_repr_en(text, accent == Accent::British),
+        _ => text.to_owned(),
+    };
     let mut result = String::new();
-    for i in sentence_pattern.captures_iter(&text) {
+    let mut last = 0;
+    for i in SENTENCE_PATTERN.captures_iter(&text) {
+        let matched = i.get(0).map_or(last..last, |i| i.range());
+        let skipped = &text[last..matched.start];
+        if !skipped.trim().is_empty() {
+            trace(skipped, None, G2PSource::Dropped, "");
+        }
+        last = matched.end;
         match (i.get(1), i.get(2), i.get(3)) {
             (Some(text), _, _) => {
                 let text = to_half_shape(text.as_str());
@@ -363,31 +772,53 @@ pub fn g2p(text: &str, use_v11: bool) -> Result<String, G2PError> {
                     if !result.is_empty() && !result.ends_with(' ') {
                         result.push(' ');
                     }
-                    result.push_str(&v11::g2p(&text, true));
+                    let tokens = v11::segment(&text, true);
+                    for tk in tokens.iter() {
+                        let source = match tk.phonemes.as_str() {
+                            p if p == tk.word => G2PSource::Verbatim,
+                            _ => G2PSource::Pinyin,
+                        };
+                        trace(&tk.word, Some(&tk.tag), source, tk.phonemes());
+                    }
+                    result.push_str(&v11::join(&tokens));
                     result.push(' ');
                 } else {
-                    for i in jieba.cut(&text, true) {
-                        result.push_str(&word2ipa_zh(i)?);
+                    if !result.is_empty() && !result.ends_with(' ') {
+                        result.push(' ');
+                    }
+                    for i in JIEBA.tag(&text, true) {
+                        let phonemes = word2ipa_zh(i.word)?;
+                        trace(i.word, Some(i.tag), G2PSource::Pinyin, &phonemes);
+                        result.push_str(&phonemes);
                         result.push(' ');
                     }
                 }
             }
             (_, Some(text), _) => {
-                let text = to_half_shape(text.as_str());
+                let half = to_half_shape(text.as_str());
+                trace(text.as_str(), None, G2PSource::Verbatim, &half);
                 result = result.trim_end().to_string();
-                result.push_str(&text);
+                result.push_str(&half);
                 result.push(' ');
             }
             (_, _, Some(text)) => {
-                for i in en_word_pattern.captures_iter(text.as_str()) {
-                    let c = (&i[0]).chars().nth(0).unwrap_or_default();
-                    if c == '\''
-                        || c == '_'
-                        || c == '-'
-                        || c <= 'z' && c >= 'a'
-                        || c <= 'Z' && c >= 'A'
-                    {
-                        let i = &i[0];
+                let tokens = EN_WORD_PATTERN
+                    .find_iter(text.as_str())
+                    .map(|i| i.as_str())
+                    .collect::<Vec<_>>();
+                for (n, i) in tokens.iter().enumerate() {
+                    let c = i.chars().next().unwrap_or_default();
+                    let is_word = match language {
+                        Language::English => {
+                            c == '\''
+                                || c == '_'
+                                || c == '-'
+                                || c <= 'z' && c >= 'a'
+                                || c <= 'Z' && c >= 'A'
+                        }
+                        _ => c.is_alphabetic(),
+                    };
+                    if is_word {
                         if result
                             .trim_end()
                             .ends_with(|c| c == '.' || c == ',' || c == '!' || c == '?')
@@ -395,94 +826,80 @@ pub fn g2p(text: &str, use_v11: bool) -> Result<String, G2PError> {
                         {
                             result.push(' ');
                         }
-                        result.push_str(&word2ipa_en(i)?);
-                    } else if c == ' ' && result.ends_with(' ') {
-                        result.push_str((&i[0]).trim_start());
+                        let (phonemes, source) = match heteronym::disambiguate(&tokens, n) {
+                            Some(reading) if language == Language::English => {
+                                (heteronym2ipa(reading, accent), G2PSource::Heteronym)
+                            }
+                            _ => word2ipa(i, &options)?,
+                        };
+                        trace(i, None, source, &phonemes);
+                        result.push_str(&phonemes);
+                        continue;
+                    }
+                    // Dr. Smith中称谓缩写后的点不是句末，不产生停顿
+                    let i = match n.checked_sub(1).map(|n| tokens[n].to_lowercase()) {
+                        Some(prev) if language == Language::English && EN_TITLES.contains(&prev.as_str()) => {
+                            i.strip_prefix('.').unwrap_or(i)
+                        }
+                        _ => i,
+                    };
+                    let verbatim = if c == ' ' && result.ends_with(' ') {
+                        i.trim_start().to_owned()
+                    } else if language == Language::English {
+                        i.to_string()
                     } else {
-                        result.push_str(&i[0]);
+                        to_half_shape(i)
+                    };
+                    if !i.trim().is_empty() {
+                        trace(i.trim(), None, G2PSource::Verbatim, verbatim.trim());
                     }
+                    result.push_str(&verbatim);
                 }
             }
             _ => (),
         };
     }
+    if !text[last..].trim().is_empty() {
+        trace(&text[last..], None, G2PSource::Dropped, "");
+    }
 
-    // Second pass: Fix wh- word pronunciation for modern American English
-    // Strategy: Replace "hw" → "w" but also fix the vowel to distinguish from other w- words
     let mut result = result.trim().to_string();
+    if !post_fix {
+        return Ok(result);
+    }
 
-    // Fix "what" pronunciation: need to catch both "hwˈət" and "wˈət" variants
-    // eSpeak is inconsistent:
-    //   - Lowercase "what" → "hwˈət"
-    //   - Capitalized "What" → "wˈət" (no hw!)
-    // Both need to become "wˈʌt" (ʌ = "uh" vowel, distinguishes from "with" = "wˈɪθ")
-
-    // Fix "hwˈət" → "wˈʌt" (lowercase "what")
-    result = result.replace("hwˈət", "wˈʌt");
-    result = result.replace(" hwˈət", " wˈʌt");
-
-    // Fix "wˈət" → "wˈʌt" (capitalized "What" or in sentences)
-    // This is safe because English doesn't have other common words with "wˈət" phoneme
-    result = result.replace("wˈət", "wˈʌt");
-    result = result.replace(" wˈət", " wˈʌt");
-
-    // Fix "run" pronunciation: ɹˈən → ɹˈʌn
-    // The schwa (ə) makes it sound like "ren", but American English "run" uses "uh" (ʌ)
-    // Examples:
-    //   "run"         → "ɹˈən" should be "ɹˈʌn"
-    //   "another run" → "ɹˈən" should be "ɹˈʌn"
-    result = result.replace("ɹˈən", "ɹˈʌn");
-    result = result.replace(" ɹˈən", " ɹˈʌn");
-
-    // Fix "much" pronunciation: mˈətʃ → mˈʌtʃ
-    // The schwa (ə) makes it sound like "metch", but American English "much" uses "uh" (ʌ)
-    // Examples:
-    //   "much"      → "mˈətʃ" should be "mˈʌtʃ"
-    //   "too much"  → "mˈətʃ" should be "mˈʌtʃ"
-    result = result.replace("mˈətʃ", "mˈʌtʃ");
-    result = result.replace(" mˈətʃ", " mˈʌtʃ");
-
-    // Fix "good" pronunciation: ɡɪd → ɡʊd
-    // Wrong vowel: ɪ (as in "bit") instead of ʊ (as in "book")
-    // American English "good" uses the ʊ sound, same as "could/should/would"
-    // Examples:
-    //   "good"     → "ɡɪd" should be "ɡʊd"
-    //   "good job" → "ɡɪd" should be "ɡʊd"
-    result = result.replace("ɡɪd", "ɡʊd");
-    result = result.replace(" ɡɪd", " ɡʊd");
-
-    // Fix "job" pronunciation in phrases: dʒˈoʊb → dʒˈɑb
-    // espeak changes vowel in phrases from ɑ (correct) to oʊ (wrong)
-    // Examples:
-    //   "job"       → "dʒˈɑb" (correct - stays as-is)
-    //   "good job"  → "dʒˈoʊb" should be "dʒˈɑb" (fixes "joub" to "job")
-    //   "great job" → "dʒˈoʊb" should be "dʒˈɑb"
-    result = result.replace("dʒˈoʊb", "dʒˈɑb");
-    result = result.replace(" dʒˈoʊb", " dʒˈɑb");
-
-    // For other hw → w conversions that don't need vowel change
-    if result.starts_with("hw") {
-        result = format!("w{}", &result[2..]);
-    }
-    result = result.replace(" hw", " w");
+    for (_, from, to) in POST_FIXES {
+        result = result.replace(from, to);
+    }
 
     Ok(result)
 }
 
+/// 在原文中查找转换后含有`symbol`的单词，用于报告未知音素的来源
+pub(crate) fn source_word(text: &str, options: &G2POptions, use_v11: bool, symbol: char) -> Option<String> {
+    JIEBA
+        .cut(text, true)
+        .into_iter()
+        .filter(|i| i.chars().any(char::is_alphanumeric))
+        .find(|i| g2p_with_options(i, options, use_v11).is_ok_and(|p| p.contains(symbol)))
+        .map(str::to_owned)
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "use-cmudict"))]
     #[test]
     fn test_word2ipa_en() -> Result<(), super::G2PError> {
-        use super::word2ipa_en;
+        use super::{VariantSelection, word2ipa_en};
 
+        let word2ipa_en = |word| word2ipa_en(word, VariantSelection::First).map(|(i, _)| i);
         // println!("{:?}", espeak_rs::text_to_phonemes("days", "en", None, true, false));
         assert_eq!("kjˌuːkjˈuː", word2ipa_en("qq")?);
-        assert_eq!("həlˈəʊ", word2ipa_en("hello")?);
+        assert_eq!("həlˈoʊ", word2ipa_en("hello")?);
         assert_eq!("wˈɜːld", word2ipa_en("world")?);
-        assert_eq!("ˈapəl", word2ipa_en("apple")?);
-        assert_eq!("tʃˈɪldɹɛn", word2ipa_en("children")?);
-        assert_eq!("ˈaʊə", word2ipa_en("hour")?);
+        assert_eq!("ˈæpəl", word2ipa_en("apple")?);
+        assert_eq!("tʃˈɪldɹən", word2ipa_en("children")?);
+        assert_eq!("ˈaʊɚ", word2ipa_en("hour")?);
         assert_eq!("dˈeɪz", word2ipa_en("days")?);
 
         Ok(())
@@ -497,4 +914,182 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_g2p_with_language() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        let g2p = |text, language| g2p_with_language(text, language, Accent::Standard, false);
+        assert_eq!("ˈola mˈundo", g2p("Hola mundo", Language::Spanish)?);
+        assert_eq!("bɔ̃ʒˈuʁ", g2p("bonjour", Language::French)?);
+        assert_eq!("tʃˈaːo mˈondo", g2p("ciao mondo", Language::Italian)?);
+        assert_eq!("ɔlˈa", g2p("olá", Language::Portuguese)?);
+        // 切换回英语后仍然使用英语词典
+        assert_eq!("həlˈoʊ", g2p("hello", Language::English)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_g2p_stress() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        // 重音不在第一个音节的单词
+        let g2p = |text, language, accent| g2p_with_language(text, language, accent, false);
+        assert_eq!("ˌespaɲˈol", g2p("español", Language::Spanish, Accent::Standard)?);
+        assert_eq!("tɾˌaβaxˈaɾ", g2p("trabajar", Language::Spanish, Accent::Standard)?);
+        assert_eq!("italiˈaːno", g2p("italiano", Language::Italian, Accent::Standard)?);
+        assert_eq!("oʒuʁdyˈi", g2p("aujourd'hui", Language::French, Accent::Standard)?);
+        assert_eq!("ˌobriɡˈadʊ", g2p("obrigado", Language::Portuguese, Accent::Brazilian)?);
+        assert_eq!("sˌidˈadʒy", g2p("cidade", Language::Portuguese, Accent::Brazilian)?);
+        assert_eq!("sˌidˈadɨ", g2p("cidade", Language::Portuguese, Accent::Standard)?);
+        assert_eq!("nəmˈʌsteː", g2p("नमस्ते", Language::Hindi, Accent::Standard)?);
+        assert_eq!("bʰˈaːɾət", g2p("भारत", Language::Hindi, Accent::Standard)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_g2p_en_gb() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        let g2p = |text, accent| g2p_with_language(text, Language::English, accent, false);
+        assert_eq!("kɑːnt", g2p("can't", Accent::British)?);
+        assert_eq!("təmˈɑːtəʊ", g2p("tomato", Accent::British)?);
+        // 美式读音来自en-us规则或者CMUdict
+        assert!(g2p("can't", Accent::American)?.contains("ænt"));
+        assert!(g2p("tomato", Accent::American)?.contains("təmˈeɪ"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_g2p_heteronyms() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        let g2p = |text| g2p_with_language(text, Language::English, Accent::British, false);
+        assert!(g2p("I have read it.")?.contains("ɹˈɛd"));
+        assert!(g2p("I want to read it.")?.contains("ɹˈiːd"));
+        // 标点隔断了上下文
+        assert!(g2p("Stop. Record it!")?.contains("ɹˈɛkɔːd"));
+        assert!(g2p("Close the door.")?.contains("klˈəʊz"));
+
+        // 美式口音使用美式音标
+        let g2p = |text| g2p_with_language(text, Language::English, Accent::American, false);
+        assert!(g2p("I have read it.")?.contains("ɹˈɛd"));
+        #[cfg(not(feature = "use-cmudict"))]
+        {
+            assert!(g2p("Stop. Record it!")?.contains("ɹˈɛkɚd"));
+            assert!(g2p("Close the door.")?.contains("klˈoʊz"));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "use-cmudict"))]
+    #[test]
+    fn test_g2p_words() -> Result<(), super::G2PError> {
+        use super::{G2POptions, G2PSource, g2p_words};
+
+        let words = g2p_words("I have read it 🙂", &G2POptions::default(), false)?;
+        assert_eq!(
+            vec![
+                ("I", G2PSource::Letters),
+                ("have", G2PSource::Espeak),
+                ("read", G2PSource::Heteronym),
+                ("it", G2PSource::Espeak),
+                ("🙂", G2PSource::Dropped),
+            ],
+            words.iter().map(|i| (i.text.as_str(), i.source)).collect::<Vec<_>>()
+        );
+        assert_eq!("ɹˈɛd", words[2].phonemes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_repr() {
+        use super::{num_repr, num_repr_en};
+
+        assert_eq!("It costs three point one four, not forty two!", num_repr_en("It costs 3.14, not 42!", false));
+        assert_eq!("two hundred twenty one B", num_repr_en("221B", false));
+        assert_eq!("two hundred and twenty one B", num_repr_en("221B", true));
+        assert_eq!("one million five thousand and zero", num_repr_en("1005000 and 0", false));
+        // 逗号分隔的千位和全角数字
+        assert_eq!("$one thousand", num_repr_en("$1,000", false));
+        assert_eq!("one million two hundred fifty thousand", num_repr_en("1,250,000", false));
+        assert_eq!("one.５ meters", num_repr_en("1.５ meters", false));
+        assert_eq!("三個蘋果和十二個梨", num_repr("3個蘋果和12個梨"));
+    }
+
+    #[test]
+    fn test_g2p_article() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        for accent in [Accent::American, Accent::British] {
+            let phonemes = g2p_with_language("on a GPU", Language::English, accent, false)?;
+            assert!(phonemes.contains(" ɐ "), "{}", phonemes);
+            let phonemes = g2p_with_language("Plan A", Language::English, accent, false)?;
+            assert!(!phonemes.contains('ɐ'), "{}", phonemes);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_g2p_zh_phrases() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, g2p_with_language};
+
+        let g2p = |text| g2p_with_language(text, Language::Chinese, Accent::Mandarin, false);
+        assert_eq!("ꭧʰʊ↗ŋʨʰi↘ŋ", g2p("重庆")?);
+        assert_eq!("ꭧʰa↗ŋꭧʰə↗ŋ", g2p("长城")?);
+        assert_eq!("i↗nxa↗ŋ tɤ xa↗ŋꭧa↓ŋ", g2p("银行的行长")?);
+        // 不在表中的词仍使用单字的默认读音
+        assert_eq!("ɕi↗ŋɻə↗n", g2p("行人")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clean_espeak() {
+        use super::clean_espeak;
+
+        assert_eq!("lə", clean_espeak("lə-", false));
+        assert_eq!("wˈɛn", clean_espeak("ʍ2ˈɛn", false));
+        assert_eq!("ʍˈɛn", clean_espeak("ʍ2ˈɛn", true));
+        assert_eq!("ɔlˈa", clean_espeak("ɔlˈa", false));
+    }
+
+    #[test]
+    fn test_post_fix_word() {
+        use super::post_fix_word;
+
+        assert_eq!(("wˌʌt".to_owned(), Some("wh")), post_fix_word("ʍˌʌt"));
+        assert_eq!(("ɹˈiːd".to_owned(), None), post_fix_word("ɹˈiːd"));
+    }
+
+    #[test]
+    fn test_word2ipa_espeak_wh() -> Result<(), super::G2PError> {
+        use super::{Accent, Language, word2ipa_espeak};
+
+        assert_eq!("wˌʌt", word2ipa_espeak("what", Language::English, Accent::American)?);
+        assert_eq!("wˌaɪ", word2ipa_espeak("why", Language::English, Accent::American)?);
+        assert_eq!("wˌɒt", word2ipa_espeak("what", Language::English, Accent::British)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_variant_selection() {
+        use super::VariantSelection;
+
+        assert_eq!(0, VariantSelection::First.select("read", 2));
+        let seeded = VariantSelection::Seeded(7);
+        for word in ["read", "live", "either", "tomato"] {
+            let i = seeded.select(word, 3);
+            assert!(i < 3);
+            assert_eq!(i, seeded.select(word, 3));
+        }
+        assert_eq!(0, VariantSelection::Random.select("read", 0));
+    }
 }
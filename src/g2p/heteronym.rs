@@ -0,0 +1,268 @@
+//! 英语同形异音词消歧，根据相邻单词的简单规则选择读音。
+
+/// 同形异音词的一种读音
+#[derive(Debug, PartialEq)]
+pub(super) struct Reading {
+    /// CMUdict中的ARPAbet音标
+    pub(super) arpa: &'static str,
+    /// 与eSpeak英式英语（en）输出风格一致的国际音标
+    pub(super) gb: &'static str,
+    /// 与eSpeak美式英语（en-us）输出风格一致的国际音标
+    pub(super) us: &'static str,
+}
+
+const fn reading(arpa: &'static str, gb: &'static str, us: &'static str) -> Reading {
+    Reading { arpa, gb, us }
+}
+
+const DETERMINERS: [&str; 20] = [
+    "a", "an", "the", "this", "that", "these", "those", "my", "your", "his", "her", "its", "our",
+    "their", "no", "every", "each", "some", "any", "whose",
+];
+const SUBJECT_PRONOUNS: [&str; 7] = ["i", "you", "he", "she", "it", "we", "they"];
+/// 后面通常跟动词原形的单词
+const VERB_CUES: [&str; 24] = [
+    "to", "will", "would", "can", "could", "shall", "should", "may", "might", "must", "do",
+    "does", "did", "don't", "doesn't", "didn't", "won't", "wouldn't", "can't", "cannot",
+    "couldn't", "shouldn't", "let's", "please",
+];
+/// 后面通常跟过去分词的单词
+const PARTICIPLE_CUES: [&str; 14] = [
+    "have", "has", "had", "having", "i've", "we've", "you've", "they've", "was", "were", "is",
+    "are", "been", "being",
+];
+const PARTICLES: [&str; 9] = ["up", "down", "around", "back", "off", "apart", "open", "through", "out"];
+
+/// 名词和动词重音不同的单词：(单词, 名词读音, 动词读音)
+const STRESS_SHIFT: [(&str, Reading, Reading); 14] = [
+    (
+        "record",
+        reading("R EH1 K ER0 D", "ɹˈɛkɔːd", "ɹˈɛkɚd"),
+        reading("R IH0 K AO1 R D", "ɹɪkˈɔːd", "ɹᵻkˈɔːɹd"),
+    ),
+    (
+        "present",
+        reading("P R EH1 Z AH0 N T", "pɹˈɛzənt", "pɹˈɛzənt"),
+        reading("P R IY0 Z EH1 N T", "pɹɪzˈɛnt", "pɹᵻzˈɛnt"),
+    ),
+    (
+        "object",
+        reading("AA1 B JH EH0 K T", "ˈɒbdʒɛkt", "ˈɑːbdʒɛkt"),
+        reading("AH0 B JH EH1 K T", "əbdʒˈɛkt", "əbdʒˈɛkt"),
+    ),
+    (
+        "project",
+        reading("P R AA1 JH EH0 K T", "pɹˈɒdʒɛkt", "pɹˈɑːdʒɛkt"),
+        reading("P R AH0 JH EH1 K T", "pɹədʒˈɛkt", "pɹədʒˈɛkt"),
+    ),
+    (
+        "permit",
+        reading("P ER1 M IH2 T", "pˈɜːmɪt", "pˈɜːmɪt"),
+        reading("P ER0 M IH1 T", "pəmˈɪt", "pɚmˈɪt"),
+    ),
+    (
+        "produce",
+        reading("P R OW1 D UW0 S", "pɹˈɒdjuːs", "pɹˈoʊduːs"),
+        reading("P R AH0 D UW1 S", "pɹədjˈuːs", "pɹədˈuːs"),
+    ),
+    (
+        "refuse",
+        reading("R EH1 F Y UW2 S", "ɹˈɛfjuːs", "ɹˈɛfjuːs"),
+        reading("R IH0 F Y UW1 Z", "ɹɪfjˈuːz", "ɹᵻfjˈuːz"),
+    ),
+    (
+        "conduct",
+        reading("K AA1 N D AH0 K T", "kˈɒndʌkt", "kˈɑːndʌkt"),
+        reading("K AA0 N D AH1 K T", "kəndˈʌkt", "kəndˈʌkt"),
+    ),
+    (
+        "contract",
+        reading("K AA1 N T R AE2 K T", "kˈɒntɹakt", "kˈɑːntɹækt"),
+        reading("K AH0 N T R AE1 K T", "kəntɹˈakt", "kəntɹˈækt"),
+    ),
+    (
+        "increase",
+        reading("IH1 N K R IY2 S", "ˈɪnkɹiːs", "ˈɪŋkɹiːs"),
+        reading("IH2 N K R IY1 S", "ɪnkɹˈiːs", "ɪŋkɹˈiːs"),
+    ),
+    (
+        "subject",
+        reading("S AH1 B JH IH0 K T", "sˈʌbdʒɛkt", "sˈʌbdʒɛkt"),
+        reading("S AH0 B JH EH1 K T", "səbdʒˈɛkt", "səbdʒˈɛkt"),
+    ),
+    (
+        "use",
+        reading("Y UW1 S", "jˈuːs", "jˈuːs"),
+        reading("Y UW1 Z", "jˈuːz", "jˈuːz"),
+    ),
+    (
+        "wind",
+        reading("W IH1 N D", "wˈɪnd", "wˈɪnd"),
+        reading("W AY1 N D", "wˈaɪnd", "wˈaɪnd"),
+    ),
+    (
+        "tear",
+        reading("T IH1 R", "tˈɪə", "tˈɪɹ"),
+        reading("T EH1 R", "tˈeə", "tˈɛɹ"),
+    ),
+];
+
+const READ: [Reading; 2] = [
+    reading("R IY1 D", "ɹˈiːd", "ɹˈiːd"),
+    reading("R EH1 D", "ɹˈɛd", "ɹˈɛd"),
+];
+const LEAD: [Reading; 2] = [
+    reading("L IY1 D", "lˈiːd", "lˈiːd"),
+    reading("L EH1 D", "lˈɛd", "lˈɛd"),
+];
+const LIVE: [Reading; 2] = [
+    reading("L IH1 V", "lˈɪv", "lˈɪv"),
+    reading("L AY1 V", "lˈaɪv", "lˈaɪv"),
+];
+const LIVES: [Reading; 2] = [
+    reading("L IH1 V Z", "lˈɪvz", "lˈɪvz"),
+    reading("L AY1 V Z", "lˈaɪvz", "lˈaɪvz"),
+];
+const CLOSE: [Reading; 2] = [
+    reading("K L OW1 Z", "klˈəʊz", "klˈoʊz"),
+    reading("K L OW1 S", "klˈəʊs", "klˈoʊs"),
+];
+const WOUND: [Reading; 2] = [
+    reading("W UW1 N D", "wˈuːnd", "wˈuːnd"),
+    reading("W AW1 N D", "wˈaʊnd", "wˈaʊnd"),
+];
+
+/// 查找第`index`个片段前后最近的单词，遇到标点则认为没有上下文
+fn neighbours(tokens: &[&str], index: usize) -> (Option<String>, Option<String>) {
+    fn find<'a>(mut tokens: impl Iterator<Item = &'a &'a str>) -> Option<String> {
+        tokens
+            .find(|i| !i.trim().is_empty())
+            .filter(|i| i.starts_with(|c: char| c.is_alphanumeric() || c == '\''))
+            .map(|i| i.to_lowercase().replace('\u{2019}', "'"))
+    }
+
+    (find(tokens[..index].iter().rev()), find(tokens[index + 1..].iter()))
+}
+
+/// 根据相邻单词为同形异音词选择读音，`tokens`是按单词和非单词切分的片段。
+/// 不是同形异音词时返回`None`。
+pub(super) fn disambiguate(tokens: &[&str], index: usize) -> Option<&'static Reading> {
+    let word = tokens.get(index)?.to_lowercase();
+    let (prev, next) = neighbours(tokens, index);
+    let prev = prev.as_deref().unwrap_or_default();
+    let next = next.as_deref().unwrap_or_default();
+    let after_determiner = DETERMINERS.contains(&prev);
+    let after_verb_cue = VERB_CUES.contains(&prev) || SUBJECT_PRONOUNS.contains(&prev);
+
+    let reading = match word.as_str() {
+        "read" => {
+            let past = PARTICIPLE_CUES.contains(&prev)
+                || matches!(prev, "he" | "she" | "it")
+                || matches!(next, "yesterday" | "earlier" | "last" | "already");
+            &READ[past as usize]
+        }
+        "lead" => {
+            let metal = prev == "of"
+                || matches!(
+                    next,
+                    "pipe" | "pipes" | "paint" | "poisoning" | "pencil" | "pencils" | "weight"
+                        | "weights" | "bullet" | "bullets" | "shot" | "balloon" | "exposure"
+                );
+            &LEAD[metal as usize]
+        }
+        "live" => {
+            let adjective = after_determiner
+                || matches!(
+                    prev,
+                    "is" | "are" | "was" | "were" | "be" | "been" | "go" | "goes" | "went" | "going"
+                )
+                || matches!(
+                    next,
+                    "music" | "broadcast" | "show" | "stream" | "performance" | "audience"
+                        | "concert" | "recording" | "album" | "event" | "coverage" | "feed"
+                        | "wire" | "bait" | "action" | "tv" | "radio"
+                );
+            &LIVE[adjective as usize]
+        }
+        "lives" => {
+            let noun = after_determiner
+                || matches!(
+                    prev,
+                    "many" | "nine" | "two" | "save" | "saves" | "saved" | "saving" | "lost"
+                        | "lose" | "loses" | "losing" | "whose" | "of"
+                );
+            &LIVES[noun as usize]
+        }
+        "close" => {
+            let adjective = matches!(
+                prev,
+                "so" | "too" | "very" | "quite" | "pretty" | "really" | "is" | "are" | "was"
+                    | "were" | "be" | "been" | "get" | "got" | "getting" | "stay" | "come" | "came"
+                    | "a" | "an"
+            ) || matches!(
+                next,
+                "to" | "by" | "call" | "friend" | "friends" | "relative" | "relatives" | "enough"
+                    | "together" | "attention" | "contact" | "range" | "behind"
+            );
+            &CLOSE[adjective as usize]
+        }
+        "wound" => {
+            let verb = !after_determiner
+                && (SUBJECT_PRONOUNS.contains(&prev) || PARTICLES.contains(&next));
+            &WOUND[verb as usize]
+        }
+        _ => {
+            let (_, noun, verb) = STRESS_SHIFT.iter().find(|(i, ..)| *i == word)?;
+            let is_verb = !after_determiner
+                && match word.as_str() {
+                    "use" => !matches!(prev, "of" | "in" | "for" | "into" | "make" | "made"),
+                    "wind" | "tear" => after_verb_cue || PARTICLES.contains(&next),
+                    _ => after_verb_cue,
+                };
+            if is_verb { verb } else { noun }
+        }
+    };
+    Some(reading)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipa(text: &str, word: &str) -> Option<&'static str> {
+        let tokens = text.split(' ').collect::<Vec<_>>();
+        let index = tokens.iter().position(|i| *i == word)?;
+        disambiguate(&tokens, index).map(|i| i.gb)
+    }
+
+    #[test]
+    fn test_disambiguate() {
+        assert_eq!(Some("ɹˈiːd"), ipa("I like to read books", "read"));
+        assert_eq!(Some("ɹˈɛd"), ipa("She has read the book", "read"));
+        assert_eq!(Some("lˈɛd"), ipa("old lead pipes", "lead"));
+        assert_eq!(Some("lˈiːd"), ipa("they lead the team", "lead"));
+        assert_eq!(Some("lˈaɪv"), ipa("a live concert", "live"));
+        assert_eq!(Some("lˈɪv"), ipa("where we live now", "live"));
+        assert_eq!(Some("ɹˈɛkɔːd"), ipa("a world record", "record"));
+        assert_eq!(Some("ɹɪkˈɔːd"), ipa("we record music", "record"));
+        assert_eq!(Some("wˈɪnd"), ipa("the wind blows", "wind"));
+        assert_eq!(Some("wˈaɪnd"), ipa("wind up the clock", "wind"));
+        assert_eq!(Some("jˈuːs"), ipa("the use of force", "use"));
+        assert_eq!(None, ipa("hello world", "hello"));
+    }
+
+    #[test]
+    fn test_refuse_noun_arpa() {
+        // 名词refuse（垃圾）末尾是清音s
+        let tokens = ["the", " ", "refuse"];
+        assert_eq!(Some("R EH1 F Y UW2 S"), disambiguate(&tokens, 2).map(|i| i.arpa));
+    }
+
+    #[test]
+    fn test_punctuation_breaks_context() {
+        let tokens = ["the", ". ", "record"];
+        assert_eq!(Some(&STRESS_SHIFT[0].1), disambiguate(&tokens, 2));
+        let tokens = ["to", " ", "record"];
+        assert_eq!(Some(&STRESS_SHIFT[0].2), disambiguate(&tokens, 2));
+    }
+}
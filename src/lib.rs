@@ -1,85 +1,669 @@
+mod analysis;
+mod audiobook;
+mod backend;
+mod cache;
+mod dialogue;
+mod dsp;
 mod error;
 mod g2p;
+mod options;
+mod pause;
+mod ssml;
+mod stats;
 mod stream;
+mod subtitle;
 mod synthesizer;
+mod telemetry;
 mod tokenizer;
 mod tokenizer_v019;
 mod transcription;
+mod vocabulary;
 mod voice;
+mod voice_bank;
+mod voice_store;
 mod voice_v019;
 mod voices_v019;
+mod wav;
 
 use {
-    bincode::{config::standard, decode_from_slice},
     ort::{execution_providers::CUDAExecutionProvider, session::Session},
-    std::{collections::HashMap, path::Path, sync::Arc, time::Duration},
+    cache::CacheKey,
+    std::{num::NonZeroUsize, path::Path, sync::Arc},
+    synthesizer::SynthVoice,
     tokio::{fs::read, sync::Mutex},
+    voice_bank::{LoadedVoice, VoiceBank},
 };
-pub use {error::*, g2p::*, stream::*, tokenizer::*, tokenizer_v019::*, transcription::*, voice::*, voice_v019::*};
+pub use {
+    analysis::*, audiobook::*, cache::SynthCache, dialogue::*, error::*, g2p::*, options::*, pause::*, stats::*, stream::*, subtitle::*, telemetry::*, tokenizer::*, tokenizer_v019::*, transcription::*,
+    vocabulary::*, voice::*, voice_store::*, voice_v019::*,
+};
+
+/// 合成音频的采样率
+pub const SAMPLE_RATE: u32 = 24000;
 
+/// Kokoro语音合成引擎
+///
+/// 支持v0.19、v1.0和v1.1模型，加载时根据ONNX模型的输入输出名称和语音包的格式自动识别模型版本，
+/// 并据此选择音素表和词表。模型元数据中带有词表时优先使用，也可以通过[`KokoroTts::with_vocabulary`]替换。
+/// 语速通过[`SynthOptions`]设置，对所有模型版本都有效。
 pub struct KokoroTts {
-    model: Arc<Mutex<Session>>,
-    voices: Arc<HashMap<String, Vec<Vec<Vec<f32>>>>>,
+    model: Arc<backend::Model>,
+    model_version: ModelVersion,
+    /// 模型的标识，用作缓存键的一部分
+    model_id: u64,
+    vocabulary: Arc<Vocabulary>,
+    /// 词表的摘要，用作缓存键的一部分
+    vocabulary_id: u64,
+    voices: Arc<VoiceBank>,
+    cache: Option<Arc<SynthCache>>,
 }
 
 impl KokoroTts {
+    /// `model_digest`是模型文件的摘要，用作缓存键的一部分
+    fn with_session(model: Session, voices: VoiceBank, model_digest: u64) -> Result<Self, KokoroError> {
+        let model_version = synthesizer::detect_model_version(&model, voices.is_v019())?;
+        let vocabulary =
+            Vocabulary::from_model_metadata(&model)?.unwrap_or_else(|| Vocabulary::builtin(model_version));
+        let model_id = cache::model_fingerprint(&model, model_version, model_digest)?;
+        Ok(Self {
+            model: Arc::new(Mutex::new(model)),
+            model_version,
+            model_id,
+            vocabulary_id: cache::vocabulary_digest(&vocabulary),
+            vocabulary: Arc::new(vocabulary),
+            voices: Arc::new(voices),
+            cache: None,
+        })
+    }
+
     pub async fn new<P: AsRef<Path>>(model_path: P, voices_path: P) -> Result<Self, KokoroError> {
-        let voices = read(voices_path).await?;
-        let (voices, _) = decode_from_slice(&voices, standard())?;
+        let voices = VoiceBank::from_bytes(&read(voices_path).await?)?;
 
         let model = Session::builder()?
             .with_execution_providers([CUDAExecutionProvider::default().build()])?
-            .commit_from_file(model_path)?;
-        Ok(Self {
-            model: Arc::new(model.into()),
-            voices,
-        })
+            .commit_from_file(&model_path)?;
+        Self::with_session(model, voices, cache::model_file_digest(model_path).await?)
+    }
+
+    /// 按需加载语音
+    ///
+    /// 只为语音包文件建立索引，每个语音在第一次使用时才从文件中读取并解码。
+    /// `max_loaded`限制同时保留在内存中的语音数量，超出时淘汰最久未使用的语音，`None`表示不限制。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use std::num::NonZeroUsize;
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new_lazy("../kokoro-v1.1-zh.onnx", "../voices-v1.1-zh.bin", NonZeroUsize::new(4)).await else {
+    ///         return;
+    ///     };
+    ///     // 只有这个语音会被解码
+    ///     let _ = tts.synth("你好", Voice::Zf003).await;
+    /// }
+    /// ```
+    ///
+    pub async fn new_lazy<P: AsRef<Path>>(
+        model_path: P,
+        voices_path: P,
+        max_loaded: Option<NonZeroUsize>,
+    ) -> Result<Self, KokoroError> {
+        let voices = VoiceBank::index(voices_path, max_loaded).await?;
+
+        let model = Session::builder()?
+            .with_execution_providers([CUDAExecutionProvider::default().build()])?
+            .commit_from_file(&model_path)?;
+        Self::with_session(model, voices, cache::model_file_digest(model_path).await?)
     }
 
     pub async fn new_from_bytes<B>(model: B, voices: B) -> Result<Self, KokoroError>
     where
         B: AsRef<[u8]>,
     {
-        let (voices, _) = decode_from_slice(voices.as_ref(), standard())?;
+        let voices = VoiceBank::from_bytes(voices.as_ref())?;
+        let digest = cache::model_digest(model.as_ref());
 
         let model = Session::builder()?
             .with_execution_providers([CUDAExecutionProvider::default().build()])?
             .commit_from_memory(model.as_ref())?;
-        Ok(Self {
-            model: Arc::new(model.into()),
-            voices,
-        })
+        Self::with_session(model, voices, digest)
+    }
+
+    /// 自动识别出的模型版本
+    pub fn model_version(&self) -> ModelVersion {
+        self.model_version
+    }
+
+    /// 替换词表，用于自行转换或微调过的模型，之后开始的流式合成才会使用新的词表
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Vocabulary};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let Ok(vocabulary) = Vocabulary::load("../config.json").await else {
+    ///         return;
+    ///     };
+    ///     let tts = tts.with_vocabulary(vocabulary);
+    ///     assert!(!tts.vocabulary().is_empty());
+    /// }
+    /// ```
+    ///
+    pub fn with_vocabulary(self, vocabulary: Vocabulary) -> Self {
+        Self {
+            vocabulary_id: cache::vocabulary_digest(&vocabulary),
+            vocabulary: Arc::new(vocabulary),
+            ..self
+        }
     }
 
-    pub async fn synth<S>(&self, text: S, voice: Voice) -> Result<(Vec<f32>, Duration), KokoroError>
+    /// 正在使用的词表
+    pub fn vocabulary(&self) -> &Vocabulary {
+        &self.vocabulary
+    }
+
+    /// 缓存合成结果，`synth`、`synth_ssml`和之后开始的流式合成都会先查询缓存，参见[`SynthCache`]
+    pub fn with_cache(self, cache: SynthCache) -> Self {
+        Self {
+            cache: Some(Arc::new(cache)),
+            ..self
+        }
+    }
+
+    /// 正在使用的缓存
+    pub fn cache(&self) -> Option<&SynthCache> {
+        self.cache.as_deref()
+    }
+
+    /// 预先合成一组固定的提示音并写入缓存，已经在缓存中的会被跳过，返回实际合成的数量。没有设置缓存时什么都不做。
+    pub async fn warm_cache<I, S>(&self, prompts: I, voice: Voice, options: &SynthOptions) -> Result<usize, KokoroError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.warm_cache_with(prompts, voice, options).await
+    }
+
+    async fn warm_cache_with<I, S, V>(&self, prompts: I, voice: V, options: &SynthOptions) -> Result<usize, KokoroError>
     where
+        I: IntoIterator<Item = S>,
         S: AsRef<str>,
+        V: SynthVoice,
     {
-        let name = voice.get_name();
-        let pack = self
+        if self.cache.is_none() {
+            return Ok(0);
+        }
+        let mut synthesized = 0;
+        for prompt in prompts {
+            let (_, stats) = self.synth_with(prompt.as_ref(), voice.clone(), options).await?;
+            if !stats.cached {
+                synthesized += 1;
+            }
+        }
+        Ok(synthesized)
+    }
+
+    /// 缓存键，由模型、词表、文本、语音名称、风格向量和选项决定
+    fn cache_key(&self, ssml: bool, text: &str, voice: &str, loaded: &LoadedVoice, options: &SynthOptions) -> u64 {
+        CacheKey {
+            model: self.model_id,
+            vocabulary: self.vocabulary_id,
+            ssml,
+            text,
+            voice,
+            pack: loaded.digest(),
+            options,
+        }
+        .hash()
+    }
+
+    /// 在运行时添加语音，`styles`的形状与语音包文件中的相同，通常为`(510, 1, 256)`。同名的语音会被替换。
+    /// 新名称的语音通过[`KokoroTts::synth_by_name`]和[`KokoroTts::stream_by_name`]合成。
+    pub fn add_voice<S: Into<String>>(&self, name: S, styles: &[Vec<Vec<f32>>]) -> Result<(), KokoroError> {
+        let mut store = VoiceStore::default();
+        store.insert(name, styles)?;
+        self.voices.add(store);
+        Ok(())
+    }
+
+    /// 在运行时添加另一个语音包中的所有语音，同名的语音会被替换
+    pub fn add_voices(&self, store: VoiceStore) {
+        self.voices.add(store);
+    }
+
+    /// 移除语音，返回语音是否存在
+    pub fn remove_voice(&self, name: &str) -> bool {
+        self.voices.remove(name)
+    }
+
+    /// 列出语音包中所有可识别的语音及其元数据
+    pub fn voices(&self) -> Vec<VoiceInfo> {
+        let mut voices = self
             .voices
-            .get(name)
-            .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
-        synthesizer::synth(Arc::downgrade(&self.model), text, pack, voice).await
+            .names()
+            .iter()
+            .filter_map(|name| VoiceInfo::from_name(name, self.model_version))
+            .collect::<Vec<_>>();
+        voices.sort_by(|a, b| a.name.cmp(&b.name));
+        voices
+    }
+
+    /// 获取语音的元数据，语音包中没有该语音或语音与模型版本不兼容时返回`None`
+    pub fn voice_info(&self, voice: Voice) -> Option<VoiceInfo> {
+        if !self.voices.contains(voice.get_name()) || synthesizer::check_voice(voice, self.model_version).is_err() {
+            return None;
+        }
+        Some(VoiceInfo {
+            model_version: self.model_version,
+            ..voice.info()
+        })
+    }
+
+    /// 分析文本的读音，不进行合成
+    ///
+    /// 返回每个词的分词结果、音素来源、音素、编号以及被丢弃的字符，用于排查读音错误。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.1-zh.onnx", "../voices-v1.1-zh.bin").await else {
+    ///         return;
+    ///     };
+    ///     let Ok(analysis) = tts.analyze("重庆的长城", Voice::Zf003) else {
+    ///         return;
+    ///     };
+    ///     for i in analysis.words {
+    ///         println!("{} {:?} {:?} {} {:?}", i.word.text, i.word.tag, i.word.source, i.word.phonemes, i.token_ids);
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn analyze<S>(&self, text: S, voice: Voice) -> Result<Analysis, KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.analyze_with(text.as_ref(), voice)
+    }
+
+    fn analyze_with<V: SynthVoice>(&self, text: &str, voice: V) -> Result<Analysis, KokoroError> {
+        voice.check(self.model_version)?;
+        let (language, accent) = voice.language();
+        let g2p_options = G2POptions {
+            language,
+            accent,
+            ..Default::default()
+        };
+        analysis::analyze(text, &g2p_options, self.model_version == ModelVersion::V11, &self.vocabulary)
+    }
+
+    pub async fn synth<S>(&self, text: S, voice: Voice) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_with_options(text, voice, &Default::default()).await
+    }
+
+    /// 按选项合成语音，语速等选项无效时返回错误
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, SynthOptions, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.1-zh.onnx", "../voices-v1.1-zh.bin").await else {
+    ///         return;
+    ///     };
+    ///     let options = SynthOptions {
+    ///         speed: 1.15,
+    ///         ..Default::default()
+    ///     };
+    ///     let _ = tts.synth_with_options("你好", Voice::Zf003, &options).await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_with_options<S>(
+        &self,
+        text: S,
+        voice: Voice,
+        options: &SynthOptions,
+    ) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_with(text.as_ref(), voice, options).await
+    }
+
+    /// 按名称合成语音，可以使用通过[`KokoroTts::add_voice`]或[`KokoroTts::add_voices`]添加的语音。
+    /// 朗读的语言和口音由名称的前缀决定（如`af_`、`zf_`，参见[`VoiceInfo::from_name`]），无法识别时按美式英语朗读。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, SynthOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let styles = vec![vec![vec![0.; 256]]; 510];
+    ///     let _ = tts.add_voice("af_custom", &styles);
+    ///     let _ = tts.synth_by_name("Hello world.", "af_custom", &SynthOptions::default()).await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_by_name<S>(
+        &self,
+        text: S,
+        name: &str,
+        options: &SynthOptions,
+    ) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_with(text.as_ref(), Arc::<str>::from(name), options).await
+    }
+
+    async fn synth_with<V: SynthVoice>(
+        &self,
+        text: &str,
+        voice: V,
+        options: &SynthOptions,
+    ) -> Result<(Vec<f32>, SynthStats), KokoroError> {
+        options.validate()?;
+        voice.check(self.model_version)?;
+        let loaded = self.voices.get(voice.name()).await?;
+        let pack = loaded.pack()?;
+        let key = || self.cache_key(false, text, voice.name(), &loaded, options);
+        let synth = synthesizer::synth(
+            Arc::downgrade(&self.model),
+            self.model_version,
+            &self.vocabulary,
+            text,
+            pack,
+            voice.clone(),
+            options,
+        );
+        cache::cached(self.cache.as_deref(), key, synth).await
+    }
+
+    /// 合成语音并生成与音频对齐的字幕，每条字幕单独合成，时间包含选项插入的停顿。结果不会写入缓存。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{CueSplit, KokoroTts, SynthOptions, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let text = "Hello, world. This is a subtitle test.";
+    ///     if let Ok((_audio, _stats, subtitles)) =
+    ///         tts.synth_with_subtitles(text, Voice::AfHeart, &SynthOptions::default(), CueSplit::Sentence).await
+    ///     {
+    ///         println!("{}", subtitles.to_srt());
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub async fn synth_with_subtitles<S>(
+        &self,
+        text: S,
+        voice: Voice,
+        options: &SynthOptions,
+        split: CueSplit,
+    ) -> Result<(Vec<f32>, SynthStats, Subtitles), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        options.validate()?;
+        synthesizer::check_voice(voice, self.model_version)?;
+        let loaded = self.voices.get(voice.get_name()).await?;
+        let text = text.as_ref();
+        let (audio, stats, timeline) = synthesizer::synth_timed(
+            Arc::downgrade(&self.model),
+            self.model_version,
+            &self.vocabulary,
+            synthesizer::Input::Cues(text, split),
+            loaded.pack()?,
+            voice,
+            options,
+        )
+        .await?;
+        Ok((audio, stats, subtitle::build_subtitles(text, split, &timeline)))
+    }
+
+    /// 合成多人对话，返回混合后的音频和每一句的时间
+    ///
+    /// 所有句子通过同一个流式合成会话发送，每句之前切换语音，合成和拼接可以同时进行。
+    /// 相邻两句之间插入[`DialogueOptions::gap`]或该句指定的停顿。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{DialogueOptions, KokoroTts, Voice, parse_script};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let script = "Teacher: What is the capital of France?\n[1s]\nbf_emma: It is Paris.";
+    ///     let Ok(lines) = parse_script(script, &[("Teacher", Voice::AmMichael)]) else {
+    ///         return;
+    ///     };
+    ///     if let Ok((_audio, _stats, timings)) = tts.synth_dialogue(&lines, &DialogueOptions::default()).await {
+    ///         for (line, timing) in lines.iter().zip(timings) {
+    ///             println!("{:.2?}-{:.2?} {}: {}", timing.start, timing.end, line.speaker, line.text);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub async fn synth_dialogue(
+        &self,
+        lines: &[DialogueLine],
+        options: &DialogueOptions,
+    ) -> Result<(Vec<f32>, SynthStats, Vec<LineTiming>), KokoroError> {
+        options.synth.validate()?;
+        for line in lines {
+            synthesizer::check_voice(line.voice, self.model_version)?;
+        }
+        let Some(first) = lines.first() else {
+            return Ok(Default::default());
+        };
+        let (sink, stream) = self.stream_with_options(first.voice, options.synth);
+        dialogue::synth_dialogue(lines, options.gap, sink, stream).await
+    }
+
+    /// 把长文档渲染为有声书
+    ///
+    /// 每章写入输出目录中的`001.wav`、`002.wav`等文件，同时生成`index.cue`和`ffmetadata.txt`章节索引。
+    /// 段落逐个通过[`KokoroTts::synth_with_options`]合成并追加到文件，内存中只保留一个段落的音频，设置了缓存时也会使用缓存。
+    /// 进度保存在`audiobook.json`中，中断之后用相同的参数再次调用会跳过已完成的章节和段落；
+    /// 章节的文本、语音或选项改变后，该章会重新渲染。
+    /// WAV文件的长度字段只有32位，单章超过约12.4小时时返回[`KokoroError::AudioTooLong`]。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{AudiobookOptions, DocumentFormat, KokoroTts, Voice, parse_document};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let chapters = parse_document("Chapter 1\n\nIt was a dark night.\n\nChapter 2\n\nThe end.", DocumentFormat::PlainText);
+    ///     let options = AudiobookOptions::default();
+    ///     let index = tts
+    ///         .render_audiobook(&chapters, Voice::AfHeart, &options, "../audiobook", |p| {
+    ///             println!("chapter {}/{}: {}/{}", p.chapter + 1, p.chapters, p.paragraph, p.paragraphs);
+    ///         })
+    ///         .await;
+    ///     if let Ok(index) = index {
+    ///         println!("{}", index.to_cue());
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub async fn render_audiobook<P, F>(
+        &self,
+        chapters: &[Chapter],
+        voice: Voice,
+        options: &AudiobookOptions,
+        dir: P,
+        progress: F,
+    ) -> Result<AudiobookIndex, KokoroError>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&RenderProgress),
+    {
+        options.synth.validate()?;
+        synthesizer::check_voice(voice, self.model_version)?;
+        let loaded = self.voices.get(voice.get_name()).await?;
+        // 模型、语音和合成选项相同的章节才能继续上次的进度
+        let key = self.cache_key(false, "", voice.get_name(), &loaded, &options.synth);
+        let synth = async |text: &str| self.synth_with_options(text, voice, &options.synth).await;
+        audiobook::render(chapters, options, dir.as_ref(), key, synth, progress).await
+    }
+
+    /// 合成SSML
+    ///
+    /// 支持`<speak>`、`<break>`、`<prosody>`、`<say-as>`、`<phoneme>`、`<sub>`、`<voice>`和`<lang>`，
+    /// 不支持的标签会输出警告，其中的文本按普通文本朗读。`voice`是`<voice>`之外使用的语音。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let ssml = r#"<speak>Hello<break time="300ms"/><prosody rate="slow">world</prosody></speak>"#;
+    ///     let _ = tts.synth_ssml(ssml, Voice::AfHeart).await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_ssml<S>(&self, ssml: S, voice: Voice) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_ssml_with_options(ssml, voice, &Default::default()).await
+    }
+
+    /// 按选项合成SSML，`<prosody>`在`options`的基础上调整语速、音量和音高
+    pub async fn synth_ssml_with_options<S>(
+        &self,
+        ssml: S,
+        voice: Voice,
+        options: &SynthOptions,
+    ) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        let ssml = ssml.as_ref();
+        let synth = synthesizer::synth_ssml(
+            Arc::downgrade(&self.model),
+            self.model_version,
+            &self.vocabulary,
+            &self.voices,
+            ssml,
+            voice,
+            options,
+        );
+        let Some(cache) = self.cache.as_deref() else {
+            return synth.await;
+        };
+        // 外层语音的风格向量也是缓存键的一部分
+        let loaded = self.voices.get(voice.get_name()).await?;
+        let key = || self.cache_key(true, ssml, voice.get_name(), &loaded, options);
+        cache::cached(Some(cache), key, synth).await
     }
 
     pub fn stream<S>(&self, voice: Voice) -> (SynthSink<S>, SynthStream)
     where
         S: AsRef<str> + Send + 'static,
     {
-        let voices = Arc::downgrade(&self.voices);
+        self.stream_with_options(voice, Default::default())
+    }
+
+    /// 按选项开始流式合成，超出范围的语速会被限制在有效范围内，之后可以通过`SynthSink::set_options`修改选项
+    pub fn stream_with_options<S>(&self, voice: Voice, options: SynthOptions) -> (SynthSink<S>, SynthStream)
+    where
+        S: AsRef<str> + Send + 'static,
+    {
+        self.stream_with(voice, options)
+    }
+
+    /// 按名称开始流式合成，可以使用运行时添加的语音，参见[`KokoroTts::synth_by_name`]
+    pub fn stream_by_name<S, N>(&self, name: N, options: SynthOptions) -> (SynthSink<S, Arc<str>>, SynthStream)
+    where
+        S: AsRef<str> + Send + 'static,
+        N: Into<Arc<str>>,
+    {
+        self.stream_with(name.into(), options)
+    }
+
+    /// 流式合成会话只持有模型和语音的弱引用，`KokoroTts`释放后的请求返回`KokoroError::ModelReleased`
+    fn stream_with<S, V>(&self, voice: V, options: SynthOptions) -> (SynthSink<S, V>, SynthStream)
+    where
+        S: AsRef<str> + Send + 'static,
+        V: SynthVoice,
+    {
         let model = Arc::downgrade(&self.model);
+        let voices = Arc::downgrade(&self.voices);
+        let model_version = self.model_version;
+        let model_id = self.model_id;
+        let vocabulary = self.vocabulary.clone();
+        let vocabulary_id = self.vocabulary_id;
+        let cache = self.cache.clone();
 
-        start_synth_session(voice, move |text, voice| {
-            let voices = voices.clone();
-            let model = model.clone();
+        start_synth_session(voice, options.clamped(), move |request: Request<S, V>| {
+            let tts = model.upgrade().zip(voices.upgrade()).map(|(model, voices)| Self {
+                model,
+                model_version,
+                model_id,
+                vocabulary: vocabulary.clone(),
+                vocabulary_id,
+                voices,
+                cache: cache.clone(),
+            });
             async move {
-                let name = voice.get_name();
-                let voices = voices.upgrade().ok_or(KokoroError::ModelReleased)?;
-                let pack = voices
-                    .get(name)
-                    .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
-                synthesizer::synth(model, text, pack, voice).await
+                let tts = tts.ok_or(KokoroError::ModelReleased)?;
+                let Request {
+                    voice,
+                    options,
+                    text,
+                    ssml,
+                } = request;
+                if !ssml {
+                    return tts.synth_with(text.as_ref(), voice, &options).await;
+                }
+                // SSML只能通过`SynthSink<S, Voice>`发送
+                let voice = voice
+                    .ssml_voice()
+                    .ok_or_else(|| KokoroError::VoiceVersionInvalid(format!("{} does not support SSML", voice.name())))?;
+                tts.synth_ssml_with_options(text, voice, &options).await
             }
         })
     }
@@ -87,34 +671,173 @@ impl KokoroTts {
 
 /// Kokoro TTS for v0.19 models
 /// v0.19 uses a different voice format: raw tensor (11, 511, 256)
+/// Voices are addressed by their names in the official voices.bin, see `VoiceV019`.
+/// `KokoroTts` also detects and loads v0.19 models, addressing the voices by their `Voice` names;
+/// this type only wraps it so that the voices can be addressed as `VoiceV019`, including the default `af` voice.
 pub struct KokoroTtsV019 {
-    model: Arc<Mutex<Session>>,
-    voices: Arc<voices_v019::VoicesV019>,
+    tts: KokoroTts,
 }
 
 impl KokoroTtsV019 {
     pub async fn new<P: AsRef<Path>>(model_path: P, voices_path: P) -> Result<Self, KokoroError> {
-        let voices = voices_v019::load_voices_v019(voices_path).await?;
+        let tts = KokoroTts::new(model_path, voices_path).await?;
+        if tts.model_version != ModelVersion::V019 {
+            return Err(KokoroError::ModelUnsupported(format!(
+                "expected a V019 model and voices, found {:?}",
+                tts.model_version
+            )));
+        }
+        Ok(Self { tts })
+    }
 
-        let model = Session::builder()?
-            .with_execution_providers([CUDAExecutionProvider::default().build()])?
-            .commit_from_file(model_path)?;
-        Ok(Self {
-            model: Arc::new(model.into()),
-            voices: Arc::new(voices),
-        })
+    /// 替换词表
+    pub fn with_vocabulary(self, vocabulary: Vocabulary) -> Self {
+        Self {
+            tts: self.tts.with_vocabulary(vocabulary),
+        }
     }
 
-    pub async fn synth<S>(&self, text: S, voice: VoiceV019) -> Result<(Vec<f32>, Duration), KokoroError>
+    /// 缓存合成结果，参见[`KokoroTts::with_cache`]
+    pub fn with_cache(self, cache: SynthCache) -> Self {
+        Self {
+            tts: self.tts.with_cache(cache),
+        }
+    }
+
+    /// 正在使用的缓存
+    pub fn cache(&self) -> Option<&SynthCache> {
+        self.tts.cache()
+    }
+
+    /// 预先合成提示音并写入缓存，参见[`KokoroTts::warm_cache`]
+    pub async fn warm_cache<I, S>(&self, prompts: I, voice: VoiceV019, options: &SynthOptions) -> Result<usize, KokoroError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tts.warm_cache_with(prompts, voice, options).await
+    }
+
+    /// 分析文本的读音，不进行合成，参见[`KokoroTts::analyze`]
+    pub fn analyze<S>(&self, text: S, voice: VoiceV019) -> Result<Analysis, KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.tts.analyze_with(text.as_ref(), voice)
+    }
+
+    pub async fn synth<S>(&self, text: S, voice: VoiceV019) -> Result<(Vec<f32>, SynthStats), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_with_options(text, voice, &Default::default()).await
+    }
+
+    /// 按选项合成语音，语速等选项无效时返回错误。超出语音包长度的文本会在单词之间切成多段合成，再交叉淡化拼接。
+    pub async fn synth_with_options<S>(
+        &self,
+        text: S,
+        voice: VoiceV019,
+        options: &SynthOptions,
+    ) -> Result<(Vec<f32>, SynthStats), KokoroError>
     where
         S: AsRef<str>,
     {
-        let voice_idx = voice.get_index();
-        if voice_idx >= self.voices.len() {
-            return Err(KokoroError::VoiceNotFound(format!("Voice index {} out of range", voice_idx)));
+        self.tts.synth_with(text.as_ref(), voice, options).await
+    }
+    /// 开始流式合成
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use kokoro_tts::{KokoroTtsV019, VoiceV019};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTtsV019::new("../kokoro-v0_19.onnx", "../voices-v0_19.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, mut stream) = tts.stream(VoiceV019::AfBella);
+    ///     let _ = sink.synth("Hello, world.").await;
+    ///     sink.set_voice(VoiceV019::BmLewis);
+    ///     let _ = sink.synth("Good morning.").await;
+    ///     drop(sink);
+    ///     while let Some(result) = stream.next().await {
+    ///         if let Ok((audio, _stats)) = result {
+    ///             assert!(!audio.is_empty());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn stream<S>(&self, voice: VoiceV019) -> (SynthSink<S, VoiceV019>, SynthStream)
+    where
+        S: AsRef<str> + Send + 'static,
+    {
+        self.stream_with_options(voice, Default::default())
+    }
+
+    /// 按选项开始流式合成，超出范围的语速会被限制在有效范围内
+    pub fn stream_with_options<S>(
+        &self,
+        voice: VoiceV019,
+        options: SynthOptions,
+    ) -> (SynthSink<S, VoiceV019>, SynthStream)
+    where
+        S: AsRef<str> + Send + 'static,
+    {
+        self.tts.stream_with(voice, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        backend::{Backend, ModelInput},
+        futures::{StreamExt, future::BoxFuture},
+    };
+
+    /// 每个音素编号输出一个静音采样的假模型
+    struct Silence;
+
+    impl Backend for Silence {
+        fn infer<'a>(&'a mut self, input: ModelInput<'a>) -> BoxFuture<'a, Result<Vec<f32>, KokoroError>> {
+            let audio = vec![0.; input.tokens.len()];
+            Box::pin(async move { Ok(audio) })
+        }
+    }
+
+    fn tts() -> KokoroTts {
+        let vocabulary = Vocabulary::builtin(ModelVersion::V10);
+        KokoroTts {
+            model: Arc::new(Mutex::new(Silence)),
+            model_version: ModelVersion::V10,
+            model_id: 0,
+            vocabulary_id: cache::vocabulary_digest(&vocabulary),
+            vocabulary: Arc::new(vocabulary),
+            voices: Arc::new(VoiceBank::new(VoiceStore::default())),
+            cache: None,
         }
+    }
+
+    #[tokio::test]
+    async fn test_custom_voice() -> Result<(), KokoroError> {
+        let tts = tts();
+        tts.add_voice("af_custom", &vec![vec![vec![0.; 256]]; 510])?;
+        let options = SynthOptions::default();
+        let (audio, _) = tts.synth_by_name("Hello", "af_custom", &options).await?;
+        assert!(!audio.is_empty());
+
+        let (mut sink, mut stream) = tts.stream_by_name("af_custom", options);
+        sink.synth("Hello").await?;
+        assert!(!stream.next().await.unwrap()?.0.is_empty());
+
+        assert!(tts.remove_voice("af_custom"));
+        let result = tts.synth_by_name("Hello", "af_custom", &options).await;
+        assert!(matches!(result, Err(KokoroError::VoiceNotFound(_))));
 
-        let pack = &self.voices[voice_idx];
-        synthesizer::synth_v019(Arc::downgrade(&self.model), text, pack, voice).await
+        Ok(())
     }
 }
@@ -0,0 +1,162 @@
+use {
+    crate::{KokoroError, Pauses, VariantSelection},
+    std::time::Duration,
+};
+
+/// 响度归一化的目标
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Loudness {
+    /// 峰值电平，单位dBFS
+    Peak(f32),
+    /// 均方根电平，单位dBFS
+    Rms(f32),
+    /// EBU R128积分响度，单位LUFS，广播常用-23，流媒体常用-16
+    Lufs(f32),
+}
+
+impl Loudness {
+    fn target(&self) -> f32 {
+        match self {
+            Self::Peak(i) | Self::Rms(i) | Self::Lufs(i) => *i,
+        }
+    }
+}
+
+/// 语音合成选项
+///
+/// 与语音本身无关的合成参数，对所有模型版本都有效。
+/// 推理之后依次去掉静音、拼接片段并插入停顿、偏移音高、归一化响度并附加增益，流式合成的每个请求都会单独处理。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{Loudness, SynthOptions};
+///
+/// let options = SynthOptions {
+///     speed: 1.15,
+///     pitch_semitones: -2.,
+///     loudness: Some(Loudness::Lufs(-16.)),
+///     ..Default::default()
+/// };
+/// assert!(options.validate().is_ok());
+/// assert!(SynthOptions { speed: 3.0, ..options }.validate().is_err());
+/// ```
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SynthOptions {
+    /// 语速，`1.0`为正常语速，有效范围为[`SynthOptions::MIN_SPEED`]到[`SynthOptions::MAX_SPEED`]
+    pub speed: f32,
+    /// 英语单词有多个候选读音时的选择方式
+    pub variant: VariantSelection,
+    /// 音高偏移的半音数，保持共振峰和时长不变，有效范围为正负[`SynthOptions::MAX_PITCH_SEMITONES`]
+    pub pitch_semitones: f32,
+    /// 响度归一化的目标，`None`表示不做归一化
+    pub loudness: Option<Loudness>,
+    /// 在响度归一化之后附加的增益，单位dB，有效范围为正负[`SynthOptions::MAX_GAIN_DB`]
+    pub gain_db: f32,
+    /// 去掉开头和结尾的静音，值为能量门限，单位dBFS，通常取-40左右
+    pub trim_silence: Option<f32>,
+    /// 在标点处切分文本分别合成，并按标点类别插入停顿。
+    /// 与`trim_silence`一起使用时，每个片段先去掉首尾静音，停顿的时长才会准确。
+    pub pauses: Option<Pauses>,
+    /// 片段接缝处交叉淡化的时长，也用于整段音频首尾的淡入淡出，最长为[`SynthOptions::MAX_CROSSFADE`]。
+    /// 为0时不做淡化，改为在零交叉点处对齐。
+    pub crossfade: Duration,
+    /// 严格模式：音素中有词表之外的符号时返回`KokoroError::UnknownPhonemes`，而不是跳过这些符号继续合成
+    pub strict: bool,
+}
+
+impl Default for SynthOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.,
+            variant: Default::default(),
+            pitch_semitones: 0.,
+            loudness: None,
+            gain_db: 0.,
+            trim_silence: None,
+            pauses: None,
+            crossfade: Duration::from_millis(10),
+            strict: false,
+        }
+    }
+}
+
+impl SynthOptions {
+    pub const MIN_SPEED: f32 = 0.5;
+    pub const MAX_SPEED: f32 = 2.;
+    pub const MAX_PITCH_SEMITONES: f32 = 12.;
+    pub const MAX_GAIN_DB: f32 = 40.;
+    pub const MAX_CROSSFADE: Duration = Duration::from_millis(100);
+
+    /// 检查选项是否有效
+    ///
+    /// 语速不在有效范围内时返回`KokoroError::SpeedOutOfRange`，其他选项无效时返回`KokoroError::OptionInvalid`。
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        if !(Self::MIN_SPEED..=Self::MAX_SPEED).contains(&self.speed) {
+            return Err(KokoroError::SpeedOutOfRange(self.speed));
+        }
+        if !(-Self::MAX_PITCH_SEMITONES..=Self::MAX_PITCH_SEMITONES).contains(&self.pitch_semitones) {
+            return Err(KokoroError::OptionInvalid(format!(
+                "pitch shift of {} semitones is out of range",
+                self.pitch_semitones
+            )));
+        }
+        if !(-Self::MAX_GAIN_DB..=Self::MAX_GAIN_DB).contains(&self.gain_db) {
+            return Err(KokoroError::OptionInvalid(format!("gain of {} dB is out of range", self.gain_db)));
+        }
+        if self.crossfade > Self::MAX_CROSSFADE {
+            return Err(KokoroError::OptionInvalid(format!(
+                "crossfade of {:?} is longer than {:?}",
+                self.crossfade,
+                Self::MAX_CROSSFADE
+            )));
+        }
+        if let Some(threshold) = self.trim_silence
+            && !(-100. ..=0.).contains(&threshold)
+        {
+            return Err(KokoroError::OptionInvalid(format!(
+                "silence threshold of {} dBFS must be between -100 and 0",
+                threshold
+            )));
+        }
+        if let Some(loudness) = self.loudness
+            && !(-70. ..=0.).contains(&loudness.target())
+        {
+            return Err(KokoroError::OptionInvalid(format!(
+                "loudness target {:?} must be between -70 and 0",
+                loudness
+            )));
+        }
+        Ok(())
+    }
+
+    /// 把语速、音高、增益、门限和交叉淡化时长限制在有效范围内，`NaN`视为默认值
+    pub fn clamped(self) -> Self {
+        fn clamp(value: f32, min: f32, max: f32, default: f32) -> f32 {
+            if value.is_nan() { default } else { value.clamp(min, max) }
+        }
+
+        Self {
+            speed: clamp(self.speed, Self::MIN_SPEED, Self::MAX_SPEED, 1.),
+            pitch_semitones: clamp(
+                self.pitch_semitones,
+                -Self::MAX_PITCH_SEMITONES,
+                Self::MAX_PITCH_SEMITONES,
+                0.,
+            ),
+            gain_db: clamp(self.gain_db, -Self::MAX_GAIN_DB, Self::MAX_GAIN_DB, 0.),
+            trim_silence: self.trim_silence.map(|i| clamp(i, -100., 0., -40.)),
+            crossfade: self.crossfade.min(Self::MAX_CROSSFADE),
+            loudness: self.loudness.map(|i| {
+                let target = clamp(i.target(), -70., 0., -23.);
+                match i {
+                    Loudness::Peak(_) => Loudness::Peak(target),
+                    Loudness::Rms(_) => Loudness::Rms(target),
+                    Loudness::Lufs(_) => Loudness::Lufs(target),
+                }
+            }),
+            ..self
+        }
+    }
+}
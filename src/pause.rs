@@ -0,0 +1,137 @@
+use std::{ops::Range, time::Duration};
+
+/// 按标点类别插入的停顿时长
+///
+/// # 示例
+///
+/// ```rust
+/// use std::time::Duration;
+/// use kokoro_tts::{Pauses, SynthOptions};
+///
+/// let options = SynthOptions {
+///     trim_silence: Some(-40.),
+///     pauses: Some(Pauses {
+///         clause: Duration::from_millis(150),
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// };
+/// assert!(options.validate().is_ok());
+/// ```
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pauses {
+    /// 逗号、分号、冒号和顿号之后
+    pub clause: Duration,
+    /// 句号、问号、感叹号、省略号和换行之后
+    pub sentence: Duration,
+    /// 段落之间，即空行之后
+    pub paragraph: Duration,
+}
+
+impl Default for Pauses {
+    fn default() -> Self {
+        Self {
+            clause: Duration::from_millis(200),
+            sentence: Duration::from_millis(400),
+            paragraph: Duration::from_millis(800),
+        }
+    }
+}
+
+impl Pauses {
+    pub(crate) fn get(&self, boundary: Boundary) -> Duration {
+        match boundary {
+            Boundary::None => Duration::ZERO,
+            Boundary::Clause => self.clause,
+            Boundary::Sentence => self.sentence,
+            Boundary::Paragraph => self.paragraph,
+        }
+    }
+}
+
+/// 文本片段结尾的标点类别，按停顿由短到长排列
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Boundary {
+    None,
+    Clause,
+    Sentence,
+    Paragraph,
+}
+
+fn boundary(c: char, rest: &str) -> Boundary {
+    // 半角标点后面必须是空白或文本结尾，避免切开小数、千分位和缩写中的点
+    let spaced = rest.chars().next().is_none_or(char::is_whitespace);
+    match c {
+        '。' | '！' | '？' | '…' => Boundary::Sentence,
+        '，' | '；' | '：' | '、' => Boundary::Clause,
+        '.' | '!' | '?' if spaced => Boundary::Sentence,
+        ',' | ';' | ':' if spaced => Boundary::Clause,
+        '\n' if rest.trim_start_matches([' ', '\t', '\r']).starts_with('\n') => Boundary::Paragraph,
+        '\n' => Boundary::Sentence,
+        _ => Boundary::None,
+    }
+}
+
+/// 片段中是否有需要朗读的内容
+pub(crate) fn has_speech(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+}
+
+/// 在标点处切分文本，返回每个片段的字节范围及其结尾的标点类别
+///
+/// 标点保留在片段末尾，以便模型产生正确的语调；没有可朗读内容的片段并入前一个片段，停顿取两者中较长的。
+pub(crate) fn split_pauses(text: &str) -> Vec<(Range<usize>, Boundary)> {
+    let mut segments: Vec<(Range<usize>, Boundary)> = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        let b = boundary(c, &text[end..]);
+        if b == Boundary::None {
+            continue;
+        }
+        match segments.last_mut() {
+            Some((range, last)) if !has_speech(&text[start..end]) => {
+                range.end = end;
+                *last = b.max(*last);
+            }
+            _ if !has_speech(&text[start..end]) => (),
+            _ => segments.push((start..end, b)),
+        }
+        start = end;
+    }
+    if has_speech(&text[start..]) {
+        segments.push((start..text.len(), Boundary::None));
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str) -> Vec<(&str, Boundary)> {
+        split_pauses(text)
+            .into_iter()
+            .map(|(range, b)| (&text[range], b))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_pauses() {
+        assert_eq!(
+            vec![
+                ("Hello,", Boundary::Clause),
+                (" world.", Boundary::Sentence),
+                (" It costs 3.14 dollars?!\n\n", Boundary::Paragraph),
+                ("你好，", Boundary::Clause),
+                ("世界。", Boundary::Sentence),
+                ("再见", Boundary::None),
+            ],
+            split("Hello, world. It costs 3.14 dollars?!\n\n你好，世界。再见")
+        );
+        assert_eq!(vec![("a\n", Boundary::Sentence), ("b", Boundary::None)], split("a\nb"));
+        assert!(split("……").is_empty());
+    }
+}
@@ -0,0 +1,639 @@
+//! SSML子集的解析，把标记转换为带有语音、语言和合成选项的片段。
+//!
+//! 支持`<speak>`、`<break>`、`<prosody>`、`<say-as>`、`<phoneme>`、`<sub>`、`<voice>`和`<lang>`，
+//! 其他标签会被忽略并输出警告，其中的文本按普通文本朗读。
+
+use {
+    crate::{Accent, Language, SynthOptions, Voice, pause::has_speech},
+    log::warn,
+    std::{collections::HashMap, time::Duration},
+};
+
+/// 片段的内容
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Content {
+    /// 需要经过g2p转换的文本
+    Text(String),
+    /// 直接使用的国际音标
+    Phonemes(String),
+    /// 静音
+    Break(Duration),
+}
+
+/// 解析SSML得到的一个片段
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Segment {
+    pub(crate) content: Content,
+    pub(crate) voice: Voice,
+    /// 由`<lang>`或`xml:lang`指定的语言，`None`表示使用语音自身的语言
+    pub(crate) language: Option<(Language, Accent)>,
+    pub(crate) options: SynthOptions,
+}
+
+#[derive(Copy, Clone)]
+struct State {
+    voice: Voice,
+    language: Option<(Language, Accent)>,
+    options: SynthOptions,
+}
+
+/// 需要收集全部内容后再处理的元素
+enum Capture {
+    SayAs { interpret_as: String, format: Option<String> },
+    Phoneme(Option<String>),
+    Sub(String),
+}
+
+struct Parser {
+    segments: Vec<Segment>,
+    /// 打开的元素及其之前的状态，元素结束时恢复
+    stack: Vec<(String, State)>,
+    state: State,
+    capture: Option<(String, Capture, String)>,
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                e => match e.strip_prefix("#x").or_else(|| e.strip_prefix("#X")) {
+                    Some(hex) => char::from_u32(u32::from_str_radix(hex, 16).ok()?)?,
+                    None => char::from_u32(e.strip_prefix('#')?.parse().ok()?)?,
+                },
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                result.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 解析标签内部，返回(名称, 属性, 是否为结束标签, 是否自闭合)
+fn parse_tag(tag: &str) -> (String, HashMap<String, String>, bool, bool) {
+    let (tag, closing) = match tag.strip_prefix('/') {
+        Some(tag) => (tag, true),
+        None => (tag, false),
+    };
+    let (tag, self_closing) = match tag.strip_suffix('/') {
+        Some(tag) => (tag, true),
+        None => (tag, false),
+    };
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+
+    let mut attrs = HashMap::new();
+    let mut rest = tag[name_end..].trim_start();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_ascii_lowercase();
+        rest = rest[eq + 1..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            // 没有引号的属性值，读到空白为止
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            attrs.insert(key, decode_entities(&rest[..end]));
+            rest = rest[end..].trim_start();
+            continue;
+        };
+        let end = rest[1..].find(quote).map_or(rest.len(), |i| i + 1);
+        attrs.insert(key, decode_entities(&rest[1..end]));
+        rest = rest.get(end + 1..).unwrap_or_default().trim_start();
+    }
+
+    (name, attrs, closing, self_closing)
+}
+
+/// 解析`500ms`、`2s`这样的时长
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, scale) = if let Some(ms) = value.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = value.strip_suffix('s') {
+        (s, 1.)
+    } else {
+        (value, 0.001)
+    };
+    let seconds = number.trim().parse::<f32>().ok()? * scale;
+    (seconds.is_finite() && seconds >= 0.).then(|| Duration::from_secs_f32(seconds.min(60.)))
+}
+
+/// 解析`+10%`、`120%`这样的相对值，返回倍数
+fn parse_percent(value: &str) -> Option<f32> {
+    let number = value.strip_suffix('%')?;
+    let percent = number.parse::<f32>().ok()?;
+    let multiplier = if number.starts_with(['+', '-']) {
+        1. + percent / 100.
+    } else {
+        percent / 100.
+    };
+    (multiplier > 0.).then_some(multiplier)
+}
+
+fn parse_rate(value: &str) -> Option<f32> {
+    match value {
+        "x-slow" => Some(0.5),
+        "slow" => Some(0.75),
+        "medium" | "default" => Some(1.),
+        "fast" => Some(1.25),
+        "x-fast" => Some(1.5),
+        v => parse_percent(v).or_else(|| v.parse().ok().filter(|i: &f32| *i > 0.)),
+    }
+}
+
+/// 音量，返回相对的分贝数
+fn parse_volume(value: &str) -> Option<f32> {
+    match value {
+        "silent" => Some(-SynthOptions::MAX_GAIN_DB),
+        "x-soft" => Some(-12.),
+        "soft" => Some(-6.),
+        "medium" | "default" => Some(0.),
+        "loud" => Some(6.),
+        "x-loud" => Some(12.),
+        v => match v.strip_suffix("dB").or_else(|| v.strip_suffix("db")) {
+            Some(db) => db.parse().ok(),
+            None => parse_percent(v).map(|i| 20. * i.log10()),
+        },
+    }
+}
+
+/// 音高，返回相对的半音数
+fn parse_pitch(value: &str) -> Option<f32> {
+    match value {
+        "x-low" => Some(-6.),
+        "low" => Some(-3.),
+        "medium" | "default" => Some(0.),
+        "high" => Some(3.),
+        "x-high" => Some(6.),
+        v => match v.strip_suffix("st") {
+            Some(st) => st.parse().ok(),
+            None => parse_percent(v).map(|i| 12. * i.log2()),
+        },
+    }
+}
+
+fn parse_language(code: &str) -> Option<(Language, Accent)> {
+    let code = code.to_ascii_lowercase().replace('_', "-");
+    let (language, region) = code.split_once('-').unwrap_or((&code, ""));
+    Some(match language {
+        "en" if matches!(region, "gb" | "uk" | "ie" | "au" | "nz") => (Language::English, Accent::British),
+        "en" => (Language::English, Accent::American),
+        "es" => (Language::Spanish, Accent::Standard),
+        "fr" => (Language::French, Accent::Standard),
+        "it" => (Language::Italian, Accent::Standard),
+        "pt" => (Language::Portuguese, Accent::Brazilian),
+        "hi" => (Language::Hindi, Accent::Standard),
+        "ja" => (Language::Japanese, Accent::Standard),
+        "zh" | "cmn" => (Language::Chinese, Accent::Mandarin),
+        _ => return None,
+    })
+}
+
+const ZH_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October",
+    "November", "December",
+];
+
+/// 100以内的中文数字
+fn zh_number(n: u32) -> String {
+    let (tens, ones) = (n / 10 % 10, n % 10);
+    let mut result = String::new();
+    if tens > 1 {
+        result.push(ZH_DIGITS[tens as usize]);
+    }
+    if tens > 0 {
+        result.push('十');
+    }
+    if ones > 0 || tens == 0 {
+        result.push(ZH_DIGITS[ones as usize]);
+    }
+    result
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// 按`format`（如`ymd`、`mdy`、`dmy`）解析日期并转换为可朗读的文本
+fn say_date(text: &str, format: Option<&str>, chinese: bool) -> Option<String> {
+    let parts = text
+        .split(['-', '/', '.'])
+        .map(|i| i.trim().parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let format = format.unwrap_or(if text.trim().len() > 4 && text.trim()[..4].bytes().all(|b| b.is_ascii_digit()) {
+        "ymd"
+    } else {
+        "mdy"
+    });
+    if parts.len() != format.len() {
+        return None;
+    }
+    let (mut year, mut month, mut day) = (None, None, None);
+    for (field, value) in format.chars().zip(parts) {
+        match field {
+            'y' => year = Some(value),
+            'm' if (1..=12).contains(&value) => month = Some(value),
+            'd' if (1..=31).contains(&value) => day = Some(value),
+            _ => return None,
+        }
+    }
+
+    if chinese {
+        let mut result = String::new();
+        if let Some(year) = year {
+            result.extend(year.to_string().bytes().map(|b| ZH_DIGITS[(b - b'0') as usize]));
+            result.push('年');
+        }
+        if let Some(month) = month {
+            result += &zh_number(month);
+            result.push('月');
+        }
+        if let Some(day) = day {
+            result += &zh_number(day);
+            result.push('日');
+        }
+        return Some(result);
+    }
+    let mut result = month.map_or_else(String::new, |i| MONTHS[i as usize - 1].to_owned());
+    if let Some(day) = day {
+        result = format!("{} {}", result, ordinal(day)).trim().to_owned();
+    }
+    if let Some(year) = year {
+        if result.is_empty() {
+            result = year.to_string();
+        } else {
+            result = format!("{}, {}", result, year);
+        }
+    }
+    Some(result)
+}
+
+/// 电话号码逐位朗读，原来的分隔符处稍作停顿
+fn say_telephone(text: &str, chinese: bool) -> String {
+    text.split(|c: char| !c.is_ascii_digit() && c != '+')
+        .filter(|i| !i.is_empty())
+        .map(|group| {
+            let digits = group.chars().filter_map(|c| match c {
+                '+' if chinese => Some("加".to_owned()),
+                '+' => Some("plus".to_owned()),
+                // 电话号码中的“一”读作“幺”
+                '1' if chinese => Some("幺".to_owned()),
+                c if chinese => c.to_digit(10).map(|i| ZH_DIGITS[i as usize].to_string()),
+                c => Some(c.to_string()),
+            });
+            digits.collect::<Vec<_>>().join(if chinese { "" } else { " " })
+        })
+        .collect::<Vec<_>>()
+        .join(if chinese { "，" } else { ", " })
+}
+
+impl Parser {
+    fn new(voice: Voice, options: SynthOptions) -> Self {
+        Self {
+            segments: Vec::new(),
+            stack: Vec::new(),
+            state: State {
+                voice,
+                language: None,
+                options,
+            },
+            capture: None,
+        }
+    }
+
+    fn is_chinese(&self) -> bool {
+        let (language, _) = self.state.language.unwrap_or_else(|| {
+            let info = self.state.voice.info();
+            (info.language, info.accent)
+        });
+        language == Language::Chinese
+    }
+
+    fn push(&mut self, content: Content) {
+        let State {
+            voice,
+            language,
+            options,
+        } = self.state;
+        if let (Content::Text(text), Some(last)) = (&content, self.segments.last_mut())
+            && let Content::Text(last_text) = &mut last.content
+            && (last.voice, last.language, last.options) == (voice, language, options)
+        {
+            last_text.push_str(text);
+            return;
+        }
+        self.segments.push(Segment {
+            content,
+            voice,
+            language,
+            options,
+        });
+    }
+
+    fn text(&mut self, text: &str) {
+        match &mut self.capture {
+            Some((_, _, buffer)) => buffer.push_str(text),
+            None => self.push(Content::Text(text.to_owned())),
+        }
+    }
+
+    fn finish_capture(&mut self) {
+        let Some((_, capture, text)) = self.capture.take() else {
+            return;
+        };
+        match capture {
+            Capture::Sub(alias) => self.text(&alias),
+            Capture::Phoneme(Some(ph)) => self.push(Content::Phonemes(ph)),
+            Capture::Phoneme(None) => self.text(&text),
+            Capture::SayAs { interpret_as, format } => {
+                let chinese = self.is_chinese();
+                let said = match interpret_as.as_str() {
+                    "characters" | "spell-out" | "verbatim" => Some(
+                        text.chars()
+                            .filter(|c| !c.is_whitespace())
+                            .flat_map(char::to_uppercase)
+                            .map(String::from)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ),
+                    "cardinal" | "number" => Some(text.chars().filter(|c| !matches!(c, ',' | '_' | ' ')).collect()),
+                    "date" => say_date(&text, format.as_deref(), chinese),
+                    "telephone" => Some(say_telephone(&text, chinese)),
+                    i => {
+                        warn!("Unsupported say-as interpret-as=\"{}\", read as plain text.", i);
+                        None
+                    }
+                };
+                let said = said.unwrap_or_else(|| {
+                    if interpret_as == "date" {
+                        warn!("Unrecognized date \"{}\", read as plain text.", text);
+                    }
+                    text
+                });
+                // 前后留出空格，避免与相邻的单词连在一起
+                self.text(&format!(" {} ", said));
+            }
+        }
+    }
+
+    fn start(&mut self, name: &str, attrs: &HashMap<String, String>, self_closing: bool) {
+        if self.capture.is_some() {
+            warn!("SSML tag <{}> inside a content element is ignored.", name);
+            return;
+        }
+        let previous = self.state;
+        if let Some(language) = attrs.get("xml:lang") {
+            match parse_language(language) {
+                Some(language) => self.state.language = Some(language),
+                None => warn!("Unsupported SSML language \"{}\", ignored.", language),
+            }
+        }
+        let capture = match name {
+            "speak" | "lang" => None,
+            "break" => {
+                let strength = attrs.get("strength").map(String::as_str);
+                let pause = match attrs.get("time").and_then(|i| parse_duration(i)) {
+                    Some(pause) => pause,
+                    None => Duration::from_millis(match strength {
+                        Some("none") => 0,
+                        Some("x-weak") => 100,
+                        Some("weak") => 200,
+                        Some("strong") => 700,
+                        Some("x-strong") => 1000,
+                        _ => 400,
+                    }),
+                };
+                self.push(Content::Break(pause));
+                // <break>没有内容，不需要等待结束标签
+                self.state = previous;
+                return;
+            }
+            "prosody" => {
+                let options = &mut self.state.options;
+                for (key, value) in attrs {
+                    let value = value.trim();
+                    let applied = match key.as_str() {
+                        "rate" => parse_rate(value).map(|i| options.speed *= i),
+                        "volume" => parse_volume(value).map(|i| options.gain_db += i),
+                        "pitch" => parse_pitch(value).map(|i| options.pitch_semitones += i),
+                        _ => Some(()),
+                    };
+                    if applied.is_none() {
+                        warn!("Unsupported prosody {}=\"{}\", ignored.", key, value);
+                    }
+                }
+                *options = options.clamped();
+                None
+            }
+            "voice" => {
+                match attrs.get("name").map(|i| (i, Voice::from_name(i))) {
+                    Some((_, Some(voice))) => self.state.voice = voice,
+                    Some((name, None)) => warn!("Unknown SSML voice \"{}\", keeping the current voice.", name),
+                    None => warn!("SSML <voice> without a name, keeping the current voice."),
+                }
+                None
+            }
+            "say-as" => Some(Capture::SayAs {
+                interpret_as: attrs.get("interpret-as").cloned().unwrap_or_default(),
+                format: attrs.get("format").cloned(),
+            }),
+            "phoneme" => {
+                let alphabet = attrs.get("alphabet").map_or("ipa", String::as_str);
+                if alphabet != "ipa" {
+                    warn!("Unsupported phoneme alphabet \"{}\", read as plain text.", alphabet);
+                }
+                Some(Capture::Phoneme(attrs.get("ph").filter(|_| alphabet == "ipa").cloned()))
+            }
+            "sub" => Some(Capture::Sub(attrs.get("alias").cloned().unwrap_or_default())),
+            _ => {
+                warn!("Unsupported SSML tag <{}>, its content is read as plain text.", name);
+                None
+            }
+        };
+
+        match capture {
+            Some(capture) if !self_closing => self.capture = Some((name.to_owned(), capture, String::new())),
+            Some(capture) => {
+                self.capture = Some((name.to_owned(), capture, String::new()));
+                self.finish_capture();
+            }
+            None if !self_closing => self.stack.push((name.to_owned(), previous)),
+            None => self.state = previous,
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if let Some((tag, ..)) = &self.capture {
+            if tag == name {
+                self.finish_capture();
+            } else {
+                warn!("SSML end tag </{}> inside a content element is ignored.", name);
+            }
+            return;
+        }
+        let Some(index) = self.stack.iter().rposition(|(tag, _)| tag == name) else {
+            warn!("Unmatched SSML end tag </{}>, ignored.", name);
+            return;
+        };
+        self.state = self.stack[index].1;
+        self.stack.truncate(index);
+    }
+}
+
+/// 解析SSML，`voice`和`options`是最外层的语音和合成选项
+///
+/// 没有`<speak>`根元素的文本同样可以解析。格式错误的部分按普通文本处理。
+pub(crate) fn parse_ssml(ssml: &str, voice: Voice, options: SynthOptions) -> Vec<Segment> {
+    let mut parser = Parser::new(voice, options);
+    let mut rest = ssml;
+    while let Some(start) = rest.find('<') {
+        parser.text(&decode_entities(&rest[..start]));
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |i| &comment[i + 3..]);
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            parser.text(&cdata[..end]);
+            rest = cdata.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+        // 属性值中可能出现'>'，跳过引号内的部分
+        let mut quote = None;
+        let end = rest.char_indices().skip(1).find(|&(_, c)| match quote {
+            Some(q) if c == q => {
+                quote = None;
+                false
+            }
+            Some(_) => false,
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                false
+            }
+            None => c == '>',
+        });
+        let Some((end, _)) = end else {
+            warn!("Unterminated SSML tag, read as plain text.");
+            parser.text(rest);
+            rest = "";
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with(['?', '!']) {
+            // XML声明和文档类型
+            continue;
+        }
+        let (name, attrs, closing, self_closing) = parse_tag(tag);
+        if closing {
+            parser.end(&name);
+        } else {
+            parser.start(&name, &attrs, self_closing);
+        }
+    }
+    parser.text(&decode_entities(rest));
+    parser.finish_capture();
+
+    parser
+        .segments
+        .into_iter()
+        .filter(|i| match &i.content {
+            Content::Text(text) => has_speech(text),
+            Content::Phonemes(ph) => !ph.trim().is_empty(),
+            Content::Break(_) => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(ssml: &str) -> Vec<Content> {
+        parse_ssml(ssml, Voice::AfHeart, Default::default())
+            .into_iter()
+            .map(|i| i.content)
+            .collect()
+    }
+
+    fn text(text: &str) -> Content {
+        Content::Text(text.to_owned())
+    }
+
+    #[test]
+    fn test_parse_ssml() {
+        assert_eq!(
+            vec![
+                text("Hello "),
+                Content::Break(Duration::from_millis(500)),
+                text(" world & "),
+                Content::Phonemes("təmˈɑːtəʊ".to_owned()),
+                text(" World Wide Web"),
+            ],
+            contents(
+                r#"<?xml version="1.0"?><speak>Hello <break time="500ms"/> world &amp; <phoneme alphabet="ipa" ph="təmˈɑːtəʊ">tomato</phoneme> <sub alias="World Wide Web">WWW</sub></speak>"#
+            )
+        );
+        assert_eq!(vec![text(" A B C ")], contents(r#"<say-as interpret-as="characters">abc</say-as>"#));
+        assert_eq!(
+            vec![text(" March 5th, 2024 ")],
+            contents(r#"<say-as interpret-as="date">2024-03-05</say-as>"#)
+        );
+        assert_eq!(
+            vec![text(" 5 5 5, 1 2 3 4 ")],
+            contents(r#"<say-as interpret-as="telephone">555-1234</say-as>"#)
+        );
+        // 不支持的标签按普通文本处理
+        assert_eq!(vec![text("plain text")], contents("<emphasis>plain</emphasis> text"));
+    }
+
+    #[test]
+    fn test_parse_ssml_state() {
+        let segments = parse_ssml(
+            r#"<speak>a<prosody rate="fast" volume="+6dB" pitch="-2st">b</prosody><voice name="zf_xiaoxiao"><lang xml:lang="en-GB">c</lang>d</voice>e</speak>"#,
+            Voice::AfHeart,
+            Default::default(),
+        );
+        assert_eq!(5, segments.len());
+        assert_eq!(1.25, segments[1].options.speed);
+        assert_eq!(6., segments[1].options.gain_db);
+        assert_eq!(-2., segments[1].options.pitch_semitones);
+        assert_eq!(Voice::ZfXiaoxiao, segments[2].voice);
+        assert_eq!(Some((Language::English, Accent::British)), segments[2].language);
+        assert_eq!((Voice::ZfXiaoxiao, None), (segments[3].voice, segments[3].language));
+        assert_eq!((Voice::AfHeart, SynthOptions::default()), (segments[4].voice, segments[4].options));
+
+        let segments = parse_ssml(
+            r#"<say-as interpret-as="date">2024-03-05</say-as><say-as interpret-as="telephone">138-1234</say-as>"#,
+            Voice::ZfXiaoxiao,
+            Default::default(),
+        );
+        assert_eq!(vec![text(" 二零二四年三月五日  幺三八，幺二三四 ")], segments.into_iter().map(|i| i.content).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,116 @@
+use {
+    crate::SAMPLE_RATE,
+    std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        ops::AddAssign,
+        time::Duration,
+    },
+};
+
+/// 一次合成的耗时统计
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{KokoroTts, Voice};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+///         return;
+///     };
+///     if let Ok((_audio, stats)) = tts.synth("Hello, world!", Voice::AfHeart).await {
+///         println!("{}", stats);
+///         assert!(stats.chunks > 0);
+///     }
+/// }
+/// ```
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SynthStats {
+    /// 模型推理的总耗时，包括等待模型锁之后的所有分段
+    pub inference: Duration,
+    /// 文本转换为音素的耗时，直接合成音素时为零
+    pub g2p: Duration,
+    /// 音素转换为编号的耗时
+    pub tokenization: Duration,
+    /// 合成音频的时长
+    pub audio: Duration,
+    /// 模型推理的次数，过长的输入会被切成多段
+    pub chunks: usize,
+    /// 音频是否来自[`SynthCache`](crate::SynthCache)，此时各项耗时都为零
+    pub cached: bool,
+}
+
+impl SynthStats {
+    /// 采样数对应的音频时长
+    pub fn audio_duration(samples: usize) -> Duration {
+        Duration::from_secs_f64(samples as f64 / SAMPLE_RATE as f64)
+    }
+
+    /// 处理的总耗时：g2p、转换编号和推理
+    pub fn processing(&self) -> Duration {
+        self.inference + self.g2p + self.tokenization
+    }
+
+    /// 实时率，即处理耗时与音频时长之比，小于1表示比实时更快。没有音频时为0。
+    pub fn rtf(&self) -> f32 {
+        if self.audio.is_zero() {
+            return 0.;
+        }
+        self.processing().as_secs_f32() / self.audio.as_secs_f32()
+    }
+}
+
+impl AddAssign for SynthStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.inference += rhs.inference;
+        self.g2p += rhs.g2p;
+        self.tokenization += rhs.tokenization;
+        self.audio += rhs.audio;
+        self.chunks += rhs.chunks;
+        self.cached |= rhs.cached;
+    }
+}
+
+impl Display for SynthStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "inference {:.2?}, g2p {:.2?}, tokenization {:.2?}, audio {:.2?}, RTF {:.3}, {} chunk(s)",
+            self.inference,
+            self.g2p,
+            self.tokenization,
+            self.audio,
+            self.rtf(),
+            self.chunks
+        )?;
+        if self.cached {
+            write!(f, ", cached")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut stats = SynthStats {
+            inference: Duration::from_millis(400),
+            g2p: Duration::from_millis(90),
+            tokenization: Duration::from_millis(10),
+            audio: SynthStats::audio_duration(SAMPLE_RATE as usize),
+            chunks: 1,
+            cached: false,
+        };
+        assert_eq!(Duration::from_secs(1), stats.audio);
+        assert!((stats.rtf() - 0.5).abs() < 1e-6);
+        stats += stats;
+        assert_eq!(2, stats.chunks);
+        assert_eq!(Duration::from_secs(1), stats.processing());
+        assert_eq!(0., SynthStats::default().rtf());
+    }
+}
@@ -1,28 +1,28 @@
 use {
-    crate::{KokoroError, Voice},
+    crate::{KokoroError, SynthOptions, SynthStats, Voice, telemetry},
     futures::{Sink, SinkExt, Stream},
     pin_project::pin_project,
     std::{
         pin::Pin,
         task::{Context, Poll},
-        time::Duration,
     },
     tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
 };
 
-struct Request<S> {
-    voice: Voice,
-    text: S,
+pub(crate) struct Request<S, V = Voice> {
+    pub(crate) voice: V,
+    pub(crate) options: SynthOptions,
+    pub(crate) text: S,
+    /// `text`是否为SSML
+    pub(crate) ssml: bool,
 }
 
-struct Response {
-    data: Vec<f32>,
-    took: Duration,
-}
+type Response = Result<(Vec<f32>, SynthStats), KokoroError>;
 
 /// 语音合成流
 ///
-/// 该结构体用于通过流式合成来处理更长的文本。它实现了`Stream` trait，可以用于异步迭代合成后的音频数据。
+/// 该结构体用于通过流式合成来处理更长的文本。它实现了`Stream` trait，可以用于异步迭代合成后的音频数据，每一项附带该请求的[`SynthStats`]。
+/// 每个请求按发送的顺序对应一项，合成失败的请求返回错误，之后的请求照常合成。
 #[pin_project]
 pub struct SynthStream {
     #[pin]
@@ -30,28 +30,25 @@ pub struct SynthStream {
 }
 
 impl Stream for SynthStream {
-    type Item = (Vec<f32>, Duration);
+    type Item = Response;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.project().rx)
-            .poll_recv(cx)
-            .map(|i| match i {
-                None => None,
-                Some(Response { data, took }) => Some((data, took)),
-            })
+        Pin::new(&mut self.project().rx).poll_recv(cx)
     }
 }
 
 /// 语音合成发送端
 ///
 /// 该结构体用于发送语音合成请求。它实现了`Sink` trait，可以用于异步发送合成请求。
+/// `V`是语音的类型，v0.19模型的[`KokoroTtsV019`](crate::KokoroTtsV019)使用[`VoiceV019`](crate::VoiceV019)。
 #[pin_project]
-pub struct SynthSink<S> {
-    tx: UnboundedSender<Request<S>>,
-    voice: Voice,
+pub struct SynthSink<S, V = Voice> {
+    tx: UnboundedSender<Request<S, V>>,
+    voice: V,
+    options: SynthOptions,
 }
 
-impl<S> SynthSink<S> {
+impl<S, V: Clone> SynthSink<S, V> {
     /// 设置语音名称
     ///
     /// 该方法用于设置要合成的语音名称。
@@ -70,17 +67,47 @@ impl<S> SynthSink<S> {
     ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
     ///         return;
     ///     };
-    ///     // speed: 1.0
-    ///     let (mut sink, _) = tts.stream::<&str>(Voice::ZfXiaoxiao(1.0));
-    ///     // speed: 1.8
-    ///     sink.set_voice(Voice::ZmYunxi(1.8));
+    ///     let (mut sink, _) = tts.stream::<&str>(Voice::ZfXiaoxiao);
+    ///     sink.set_voice(Voice::ZmYunxi);
     /// }
     /// ```
     ///
-    pub fn set_voice(&mut self, voice: Voice) {
+    pub fn set_voice(&mut self, voice: V) {
         self.voice = voice
     }
 
+    /// 设置之后的合成请求使用的选项，选项无效时返回错误并保留原来的选项
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, SynthOptions, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, _) = tts.stream::<&str>(Voice::ZfXiaoxiao);
+    ///     let options = SynthOptions {
+    ///         speed: 1.8,
+    ///         ..Default::default()
+    ///     };
+    ///     assert!(sink.set_options(options).is_ok());
+    /// }
+    /// ```
+    ///
+    pub fn set_options(&mut self, options: SynthOptions) -> Result<(), KokoroError> {
+        options.validate()?;
+        self.options = options;
+        Ok(())
+    }
+
+    /// 只修改语速，其余选项不变
+    pub fn set_speed(&mut self, speed: f32) -> Result<(), KokoroError> {
+        self.set_options(SynthOptions { speed, ..self.options })
+    }
+
     /// 发送合成请求
     ///
     /// 该方法用于发送语音合成请求。
@@ -103,27 +130,69 @@ impl<S> SynthSink<S> {
     ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.1-zh.onnx", "../voices-v1.1-zh.bin").await else {
     ///         return;
     ///     };
-    ///     let (mut sink, _) =tts.stream(Voice::Zf003(2));
+    ///     let (mut sink, _) =tts.stream(Voice::Zf003);
     ///     let _ = sink.synth("hello world.").await;
     /// }
     /// ```
     ///
     pub async fn synth(&mut self, text: S) -> Result<(), KokoroError> {
-        self.send((self.voice, text)).await
+        self.send((self.voice.clone(), text)).await
     }
 }
 
-impl<S> Sink<(Voice, S)> for SynthSink<S> {
+impl<S> SynthSink<S> {
+    /// 发送SSML合成请求，`<voice>`和`<prosody>`只在元素内部生效，不会改变之后的请求
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.1-zh.onnx", "../voices-v1.1-zh.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, _) = tts.stream(Voice::Zf003);
+    ///     let _ = sink.synth_ssml(r#"<speak>电话是<say-as interpret-as="telephone">138-1234</say-as></speak>"#).await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_ssml(&mut self, ssml: S) -> Result<(), KokoroError> {
+        self.enqueue(Request {
+            voice: self.voice,
+            options: self.options,
+            text: ssml,
+            ssml: true,
+        })
+    }
+}
+
+impl<S, V> SynthSink<S, V> {
+    fn enqueue(&self, request: Request<S, V>) -> Result<(), KokoroError> {
+        self.tx
+            .send(request)
+            .map_err(|e| KokoroError::Send(e.to_string()))?;
+        telemetry::add_queue_depth(1.);
+        Ok(())
+    }
+}
+
+impl<S, V> Sink<(V, S)> for SynthSink<S, V> {
     type Error = KokoroError;
 
     fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, (voice, text): (Voice, S)) -> Result<(), Self::Error> {
-        self.tx
-            .send(Request { voice, text })
-            .map_err(|e| KokoroError::Send(e.to_string()))
+    fn start_send(self: Pin<&mut Self>, (voice, text): (V, S)) -> Result<(), Self::Error> {
+        let options = self.options;
+        self.enqueue(Request {
+            voice,
+            options,
+            text,
+            ssml: false,
+        })
     }
 
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -135,26 +204,56 @@ impl<S> Sink<(Voice, S)> for SynthSink<S> {
     }
 }
 
-pub(super) fn start_synth_session<'a, F, R, S>(
-    voice: Voice,
+pub(super) fn start_synth_session<F, R, S, V>(
+    voice: V,
+    options: SynthOptions,
     synth_request_callback: F,
-) -> (SynthSink<S>, SynthStream)
+) -> (SynthSink<S, V>, SynthStream)
 where
-    F: Fn(S, Voice) -> R + Send + 'static,
-    R: Future<Output = Result<(Vec<f32>, Duration), KokoroError>> + Send,
+    F: Fn(Request<S, V>) -> R + Send + 'static,
+    R: Future<Output = Result<(Vec<f32>, SynthStats), KokoroError>> + Send,
     S: AsRef<str> + Send + 'static,
+    V: Send + 'static,
 {
-    let (tx, mut rx) = unbounded_channel::<Request<S>>();
+    let (tx, mut rx) = unbounded_channel::<Request<S, V>>();
     let (tx2, rx2) = unbounded_channel();
     tokio::spawn(async move {
         while let Some(req) = rx.recv().await {
-            let (data, took) = synth_request_callback(req.text, req.voice).await?;
-            tx2.send(Response { data, took })
-                .map_err(|e| KokoroError::Send(e.to_string()))?;
+            let result = synth_request_callback(req).await;
+            telemetry::add_queue_depth(-1.);
+            if tx2.send(result).is_err() {
+                // SynthStream已经释放，队列中剩余的请求不会再被合成
+                telemetry::add_queue_depth(-(rx.len() as f64));
+                return;
+            }
         }
-
-        Ok::<_, KokoroError>(())
     });
 
-    (SynthSink { tx, voice }, SynthStream { rx: rx2 })
+    (SynthSink { tx, voice, options }, SynthStream { rx: rx2 })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, futures::StreamExt};
+
+    #[tokio::test]
+    async fn test_error_keeps_session() -> Result<(), KokoroError> {
+        let (mut sink, mut stream) = start_synth_session(Voice::AfHeart, Default::default(), |request: Request<&str>| async move {
+            match request.text {
+                "" => Err(KokoroError::OptionInvalid("empty".to_owned())),
+                text => Ok((vec![0.; text.len()], SynthStats::default())),
+            }
+        });
+        sink.synth("Hello").await?;
+        sink.synth("").await?;
+        sink.synth("Bye").await?;
+        drop(sink);
+
+        assert_eq!(5, stream.next().await.unwrap()?.0.len());
+        assert!(matches!(stream.next().await, Some(Err(KokoroError::OptionInvalid(_)))));
+        // 失败的请求之后会话继续合成
+        assert_eq!(3, stream.next().await.unwrap()?.0.len());
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,257 @@
+use {
+    crate::{
+        SynthStats,
+        pause::{Boundary, has_speech, split_pauses},
+    },
+    std::{fmt::Write, mem::take, num::NonZeroUsize, ops::Range, time::Duration},
+};
+
+/// 字幕的切分方式
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CueSplit {
+    /// 每句一条字幕
+    #[default]
+    Sentence,
+    /// 每条字幕最多包含的字符数，过长的句子先在逗号等标点处、再在空白或汉字之间切开。
+    /// 不会把两句合并到同一条字幕中，无法切开的单词可能超出限制。
+    MaxChars(NonZeroUsize),
+}
+
+/// 一条字幕
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cue {
+    pub start: Duration,
+    pub end: Duration,
+    /// 合并了连续空白的原文
+    pub text: String,
+}
+
+/// 与合成的音频对齐的字幕，参见`KokoroTts::synth_with_subtitles`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Subtitles {
+    pub cues: Vec<Cue>,
+}
+
+impl Subtitles {
+    /// 导出为SRT
+    pub fn to_srt(&self) -> String {
+        let mut srt = String::new();
+        for (n, cue) in self.cues.iter().enumerate() {
+            let _ = write!(
+                srt,
+                "{}\n{} --> {}\n{}\n\n",
+                n + 1,
+                timestamp(cue.start, ','),
+                timestamp(cue.end, ','),
+                cue.text
+            );
+        }
+        srt
+    }
+
+    /// 导出为WebVTT
+    pub fn to_webvtt(&self) -> String {
+        let mut vtt = "WEBVTT\n\n".to_owned();
+        for cue in &self.cues {
+            let _ = write!(
+                vtt,
+                "{} --> {}\n{}\n\n",
+                timestamp(cue.start, '.'),
+                timestamp(cue.end, '.'),
+                cue.text
+            );
+        }
+        vtt
+    }
+}
+
+/// `HH:MM:SS,mmm`，WebVTT使用`.`分隔毫秒
+fn timestamp(time: Duration, separator: char) -> String {
+    let ms = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// 字幕中显示的文本：合并连续空白，去掉首尾空白
+fn cue_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn cue_len(text: &str) -> usize {
+    cue_text(text).chars().count()
+}
+
+/// 汉字、假名、谚文等不以空格分词的文字，相邻的字之间可以断开
+pub(crate) fn is_cjk(c: char) -> bool {
+    c >= '\u{2e80}'
+}
+
+/// 把超出长度的片段在空白或汉字之间切开，除最后一段外结尾都没有停顿
+fn split_long(text: &str, range: Range<usize>, boundary: Boundary, max: usize) -> Vec<(Range<usize>, Boundary)> {
+    if cue_len(&text[range.clone()]) <= max {
+        return vec![(range, boundary)];
+    }
+
+    let mut breaks = Vec::new();
+    let mut prev: Option<char> = None;
+    for (i, c) in text[range.clone()].char_indices() {
+        let i = range.start + i;
+        if let Some(p) = prev
+            && !c.is_whitespace()
+            && (p.is_whitespace() || is_cjk(p) || is_cjk(c))
+        {
+            breaks.push(i);
+        }
+        prev = Some(c);
+    }
+    breaks.push(range.end);
+
+    let mut pieces: Vec<Range<usize>> = Vec::new();
+    let mut start = range.start;
+    let mut last = start;
+    for b in breaks {
+        if cue_len(&text[start..b]) > max && last > start {
+            pieces.push(start..last);
+            start = last;
+        }
+        last = b;
+    }
+    pieces.push(start..range.end);
+
+    // 只有标点的片段并入前一段
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for piece in pieces {
+        match merged.last_mut() {
+            Some(last) if !has_speech(&text[piece.clone()]) => last.end = piece.end,
+            _ => merged.push(piece),
+        }
+    }
+    let n = merged.len();
+    merged
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| (r, if i + 1 == n { boundary } else { Boundary::None }))
+        .collect()
+}
+
+/// 把文本切分为字幕，每条字幕由一个或多个片段组成，片段的结尾带有停顿类别，与`split_pauses`的结果一致
+pub(crate) fn split_cues(text: &str, split: CueSplit) -> Vec<Vec<(Range<usize>, Boundary)>> {
+    let mut cues = Vec::new();
+    let mut cue: Vec<(Range<usize>, Boundary)> = Vec::new();
+    for (range, boundary) in split_pauses(text) {
+        let pieces = match split {
+            CueSplit::Sentence => vec![(range, boundary)],
+            CueSplit::MaxChars(max) => split_long(text, range, boundary, max.get()),
+        };
+        for (range, boundary) in pieces {
+            if let CueSplit::MaxChars(max) = split
+                && let Some((first, _)) = cue.first()
+                && cue_len(&text[first.start..range.end]) > max.get()
+            {
+                cues.push(take(&mut cue));
+            }
+            cue.push((range, boundary));
+            if boundary >= Boundary::Sentence {
+                cues.push(take(&mut cue));
+            }
+        }
+    }
+    if !cue.is_empty() {
+        cues.push(cue);
+    }
+
+    cues
+}
+
+/// 根据每个合成片段在文本中的字节范围和在音频中的采样范围生成字幕
+pub(crate) fn build_subtitles(text: &str, split: CueSplit, timeline: &[(Range<usize>, Range<usize>)]) -> Subtitles {
+    let cues = split_cues(text, split)
+        .into_iter()
+        .filter_map(|pieces| {
+            let range = pieces.first()?.0.start..pieces.last()?.0.end;
+            let samples = timeline
+                .iter()
+                .filter(|(t, _)| range.contains(&t.start))
+                .map(|(_, s)| s);
+            let start = samples.clone().map(|s| s.start).min()?;
+            let end = samples.map(|s| s.end).max()?;
+            Some(Cue {
+                start: SynthStats::audio_duration(start),
+                end: SynthStats::audio_duration(end),
+                text: cue_text(&text[range]),
+            })
+        })
+        .collect();
+
+    Subtitles { cues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str, split: CueSplit) -> Vec<String> {
+        split_cues(text, split)
+            .into_iter()
+            .map(|i| cue_text(&text[i[0].0.start..i.last().unwrap().0.end]))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_cues() {
+        let text = "Hello, world. This is a long sentence, with a clause; and more words here!\n\n你好，我们是一群追逐梦想的人。";
+        assert_eq!(
+            vec![
+                "Hello, world.",
+                "This is a long sentence, with a clause; and more words here!",
+                "你好，我们是一群追逐梦想的人。"
+            ],
+            split(text, CueSplit::Sentence)
+        );
+        let max = CueSplit::MaxChars(NonZeroUsize::new(20).unwrap());
+        let cues = split(text, max);
+        assert_eq!(
+            vec![
+                "Hello, world.",
+                "This is a long",
+                "sentence,",
+                "with a clause;",
+                "and more words here!",
+                "你好，我们是一群追逐梦想的人。"
+            ],
+            cues
+        );
+        assert!(cues.iter().all(|i| i.chars().count() <= 20));
+        // 只有标点的片段不会单独成为字幕
+        let max = CueSplit::MaxChars(NonZeroUsize::new(4).unwrap());
+        assert_eq!(vec!["你好世界。", "再见。"], split("你好世界。再见。", max));
+    }
+
+    #[test]
+    fn test_build_subtitles() {
+        let text = "Hello, world. Goodbye.";
+        // 两个片段之间插入了1秒的停顿
+        let timeline = [(0..6, 0..12000), (6..13, 12000..24000), (13..22, 48000..60000)];
+        let subtitles = build_subtitles(text, CueSplit::Sentence, &timeline);
+        assert_eq!(2, subtitles.cues.len());
+        assert_eq!(Duration::ZERO, subtitles.cues[0].start);
+        assert_eq!(Duration::from_secs(1), subtitles.cues[0].end);
+        assert_eq!(Duration::from_secs(2), subtitles.cues[1].start);
+        assert_eq!(
+            "1\n00:00:00,000 --> 00:00:01,000\nHello, world.\n\n2\n00:00:02,000 --> 00:00:02,500\nGoodbye.\n\n",
+            subtitles.to_srt()
+        );
+        assert!(
+            subtitles
+                .to_webvtt()
+                .starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.000\nHello, world.\n\n")
+        );
+        assert_eq!("01:01:01.001", timestamp(Duration::from_millis(3_661_001), '.'));
+    }
+}
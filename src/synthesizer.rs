@@ -1,177 +1,819 @@
 use {
-    crate::{KokoroError, Voice, VoiceV019, g2p, get_token_ids, get_token_ids_v019},
-    ndarray::Array,
-    ort::{
-        inputs,
-        session::{RunOptions, Session},
-        value::TensorRef,
+    crate::{
+        Accent, G2POptions, KokoroError, Language, ModelVersion, SynthOptions, SynthStats, Vocabulary, Voice, VoiceInfo,
+        VoicePack, VoiceV019, SAMPLE_RATE,
+        dsp::{fade_in, fade_out, join, post_process, time_stretch, trim_silence},
+        pause::{Boundary, split_pauses},
+        subtitle::{CueSplit, split_cues},
+        ssml::{Content, Segment, parse_ssml},
+        g2p::source_word,
+        g2p_with_options,
+        backend::{Model, ModelInput},
+        telemetry::{self, instrument, observe, span},
+        voice_bank::VoiceBank,
     },
+    ort::session::Session,
     std::{
-        cmp::min,
-        sync::Weak,
+        ops::Range,
+        sync::{Arc, Weak},
         time::{Duration, SystemTime},
     },
-    tokio::sync::Mutex,
+    log::warn,
 };
 
-async fn synth_v10<'a, P, S>(
-    model: Weak<Mutex<Session>>,
-    phonemes: S,
-    pack: P,
-    speed: f32,
-) -> Result<(Vec<f32>, Duration), KokoroError>
-where
-    P: AsRef<Vec<Vec<Vec<f32>>>>,
-    S: AsRef<str>,
-{
-    let model = model.upgrade().ok_or(KokoroError::ModelReleased)?;
-    let phonemes = get_token_ids(phonemes.as_ref(), false);
-    let phonemes = Array::from_shape_vec((1, phonemes.len()), phonemes)?;
-    let ref_s = pack.as_ref()[phonemes.len() - 1]
-        .first()
-        .map(|i| i.clone())
-        .unwrap_or_default();
-
-    let style = Array::from_shape_vec((1, ref_s.len()), ref_s)?;
-    let speed = Array::from_vec(vec![speed]);
-    let options = RunOptions::new()?;
-    let mut model = model.lock().await;
+/// 加锁推理一次，返回音频和推理耗时，等待锁的时间不计入推理耗时
+async fn infer(model: &Model, input: ModelInput<'_>) -> Result<(Vec<f32>, Duration), KokoroError> {
     let t = SystemTime::now();
-    let kokoro_output = model
-        .run_async(
-            inputs![
-                "tokens" => TensorRef::from_array_view(&phonemes)?,
-                "style" => TensorRef::from_array_view(&style)?,
-                "speed" => TensorRef::from_array_view(&speed)?,
-            ],
-            &options,
-        )?
-        .await?;
-    let elapsed = t.elapsed()?;
-    let (_, audio) = kokoro_output["audio"].try_extract_tensor::<f32>()?;
+    let mut model = instrument(span!(DEBUG, "lock_wait"), model.lock()).await;
+    telemetry::record_lock_wait(t.elapsed()?);
+    let t = SystemTime::now();
+    let audio = instrument(span!(DEBUG, "inference", tokens = input.tokens.len()), model.infer(input)).await?;
+    Ok((audio, t.elapsed()?))
+}
 
-    Ok((audio.to_owned(), elapsed))
+/// 根据ONNX模型的输入输出名称和语音包的格式识别模型版本
+pub(super) fn detect_model_version(model: &Session, voices_v019: bool) -> Result<ModelVersion, KokoroError> {
+    let has_input = |name: &str| model.inputs.iter().any(|i| i.name == name);
+    let has_output = |name: &str| model.outputs.iter().any(|i| i.name == name);
+    if has_input("input_ids") && has_output("waveform") {
+        if voices_v019 {
+            return Err(KokoroError::ModelUnsupported(
+                "v0.19 voices cannot be used with a v1.1 model".to_owned(),
+            ));
+        }
+        Ok(ModelVersion::V11)
+    } else if has_input("tokens") && has_output("audio") {
+        // v0.19和v1.0模型的输入输出相同，只能通过语音包的格式区分
+        Ok(if voices_v019 { ModelVersion::V019 } else { ModelVersion::V10 })
+    } else {
+        Err(KokoroError::ModelUnsupported(format!(
+            "unrecognized model inputs {:?} and outputs {:?}",
+            model.inputs.iter().map(|i| &i.name).collect::<Vec<_>>(),
+            model.outputs.iter().map(|i| &i.name).collect::<Vec<_>>(),
+        )))
+    }
 }
 
-async fn synth_v11<P, S>(
-    model: Weak<Mutex<Session>>,
-    phonemes: S,
-    pack: P,
-    speed: i32,
-) -> Result<(Vec<f32>, Duration), KokoroError>
-where
-    P: AsRef<Vec<Vec<Vec<f32>>>>,
-    S: AsRef<str>,
-{
-    let model = model.upgrade().ok_or(KokoroError::ModelReleased)?;
-    let mut phonemes = get_token_ids(phonemes.as_ref(), true);
+/// 检查语音能否用于指定版本的模型
+pub(super) fn check_voice(voice: Voice, model_version: ModelVersion) -> Result<(), KokoroError> {
+    match (voice.info().model_version, model_version) {
+        (v, m) if v == m => Ok(()),
+        // v0.19的语音都是v1.0中的同名语音
+        (ModelVersion::V10, ModelVersion::V019) => Ok(()),
+        (v, m) => Err(KokoroError::VoiceVersionInvalid(format!(
+            "{} is a {:?} voice but the loaded model is {:?}",
+            voice.get_name(),
+            v,
+            m
+        ))),
+    }
+}
 
-    let mut ret = Vec::new();
-    let mut elapsed = Duration::ZERO;
-    while let p = phonemes.drain(..min(pack.as_ref().len(), phonemes.len()))
-        && p.len() != 0
-    {
-        let phonemes = Array::from_shape_vec((1, p.len()), p.collect())?;
-        let ref_s = pack.as_ref()[phonemes.len() - 1]
-            .first()
-            .map(|i| i.clone())
-            .unwrap_or(vec![0.; 256]);
-
-        let style = Array::from_shape_vec((1, ref_s.len()), ref_s)?;
-        let speed = Array::from_vec(vec![speed]);
-        let options = RunOptions::new()?;
-        let mut model = model.lock().await;
-        let t = SystemTime::now();
-        let kokoro_output = model
-            .run_async(
-                inputs![
-                    "input_ids" => TensorRef::from_array_view(&phonemes)?,
-                    "style" => TensorRef::from_array_view(&style)?,
-                    "speed" => TensorRef::from_array_view(&speed)?,
-                ],
-                &options,
-            )?
-            .await?;
-        elapsed = t.elapsed()?;
-        let (_, audio) = kokoro_output["waveform"].try_extract_tensor::<f32>()?;
-        let (_, _duration) = kokoro_output["duration"].try_extract_tensor::<i64>()?;
-        // let _ = dbg!(duration.len());
-        ret.extend_from_slice(audio);
+/// 合成时用到的语音属性，[`Voice`]、v0.19模型的[`VoiceV019`]和按名称指定的语音都通过它走同一条合成路径
+pub(crate) trait SynthVoice: Clone + Send + Sync + 'static {
+    /// 语音包中的名称
+    fn name(&self) -> &str;
+
+    /// 朗读使用的语言和口音
+    fn language(&self) -> (Language, Accent);
+
+    /// 检查语音能否用于指定版本的模型
+    fn check(&self, model_version: ModelVersion) -> Result<(), KokoroError>;
+
+    /// SSML请求使用的语音，只有[`Voice`]支持SSML
+    fn ssml_voice(&self) -> Option<Voice>;
+}
+
+impl SynthVoice for Voice {
+    fn name(&self) -> &str {
+        self.get_name()
+    }
+
+    fn language(&self) -> (Language, Accent) {
+        let info = self.info();
+        (info.language, info.accent)
+    }
+
+    fn check(&self, model_version: ModelVersion) -> Result<(), KokoroError> {
+        check_voice(*self, model_version)
     }
 
-    Ok((ret, elapsed))
+    fn ssml_voice(&self) -> Option<Voice> {
+        Some(*self)
+    }
 }
 
-pub(super) async fn synth<'a, P, S>(
-    model: Weak<Mutex<Session>>,
-    text: S,
-    pack: P,
-    voice: Voice,
-) -> Result<(Vec<f32>, Duration), KokoroError>
-where
-    P: AsRef<Vec<Vec<Vec<f32>>>>,
-    S: AsRef<str>,
-{
-    let phonemes = g2p(text.as_ref(), voice.is_v11_supported())?;
+impl SynthVoice for VoiceV019 {
+    fn name(&self) -> &str {
+        self.get_name()
+    }
+
+    fn language(&self) -> (Language, Accent) {
+        (Language::English, self.accent())
+    }
+
+    fn check(&self, model_version: ModelVersion) -> Result<(), KokoroError> {
+        if model_version != ModelVersion::V019 {
+            return Err(KokoroError::VoiceVersionInvalid(format!(
+                "{} is a V019 voice but the loaded model is {:?}",
+                self.get_name(),
+                model_version
+            )));
+        }
+        Ok(())
+    }
+
+    fn ssml_voice(&self) -> Option<Voice> {
+        None
+    }
+}
+
+/// 按名称指定的语音，可以是通过[`KokoroTts::add_voice`]添加的语音。
+/// 语言和口音由名称的前缀决定（参见[`VoiceInfo::from_name`]），无法识别时按美式英语朗读。
+impl SynthVoice for Arc<str> {
+    fn name(&self) -> &str {
+        self
+    }
+
+    fn language(&self) -> (Language, Accent) {
+        VoiceInfo::from_name(self, ModelVersion::V10).map_or((Language::English, Accent::American), |i| {
+            (i.language, i.accent)
+        })
+    }
+
+    fn check(&self, _model_version: ModelVersion) -> Result<(), KokoroError> {
+        // 语音包中没有版本信息，语音是否存在在查找时检查
+        Ok(())
+    }
+
+    fn ssml_voice(&self) -> Option<Voice> {
+        Voice::from_name(self)
+    }
+}
+
+/// 时长对应的采样数
+pub(super) fn samples(duration: Duration) -> usize {
+    (duration.as_secs_f32() * SAMPLE_RATE as f32).round() as usize
+}
+
+/// 待合成的内容
+#[derive(Copy, Clone)]
+pub(super) enum Input<'a> {
+    /// 需要经过g2p转换的文本
+    Text(&'a str),
+    /// 已经转换好的音素
+    Phonemes(&'a str),
+    /// 按字幕切分后逐条合成的文本
+    Cues(&'a str, CueSplit),
+}
+
+impl Input<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Text(i) | Self::Phonemes(i) | Self::Cues(i, _) => i,
+        }
+    }
+}
+
+/// 每个合成片段在输入中的字节范围和在输出音频中的采样范围
+pub(super) type Timeline = Vec<(Range<usize>, Range<usize>)>;
+
+async fn synth_segment(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
+    input: Input<'_>,
+    pack: VoicePack<'_>,
+    g2p_options: &G2POptions,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats), KokoroError> {
+    let t = SystemTime::now();
+    let phonemes = match input {
+        Input::Text(text) | Input::Cues(text, _) => span!(DEBUG, "g2p", chars = text.len())
+            .in_scope(|| g2p_with_options(text, g2p_options, model_version == ModelVersion::V11))?,
+        Input::Phonemes(phonemes) => phonemes.to_owned(),
+    };
+    let g2p = match input {
+        Input::Text(_) | Input::Cues(..) => t.elapsed()?,
+        Input::Phonemes(_) => Duration::ZERO,
+    };
+    let t = SystemTime::now();
+    let (tokens, mut unknown) = span!(DEBUG, "tokenize").in_scope(|| vocabulary.encode(&phonemes));
+    let tokenization = t.elapsed()?;
+    if options.strict && !unknown.is_empty() {
+        if let Input::Text(text) | Input::Cues(text, _) = input {
+            for i in unknown.iter_mut() {
+                if let Some(word) = source_word(text, g2p_options, model_version == ModelVersion::V11, i.symbol) {
+                    i.word = word;
+                }
+            }
+        }
+        return Err(KokoroError::UnknownPhonemes(unknown));
+    }
+    for i in unknown {
+        warn!("Unknown phone {} in {}, skipped.", i.symbol, i.word);
+    }
     // #[cfg(debug_assertions)]
     // println!("{}", phonemes);
-    match voice {
-        v if v.is_v11_supported() => synth_v11(model, phonemes, pack, v.get_speed_v11()?).await,
-        v if v.is_v10_supported() => synth_v10(model, phonemes, pack, v.get_speed_v10()?).await,
-        v => Err(KokoroError::VoiceVersionInvalid(v.get_name().to_owned())),
-    }
+    let crossfade = samples(options.crossfade);
+    let space = vocabulary.get(' ');
+    let (audio, stats) = match model_version {
+        ModelVersion::V11 => {
+            // v1.1模型的语速是整数，先用最接近的整数合成，剩余的比例通过时间伸缩补足
+            let speed = options.speed.round().max(1.);
+            let (audio, stats) = synth_chunks(model, model_version, tokens, pack, speed, space, crossfade).await?;
+            (time_stretch(&audio, options.speed / speed), stats)
+        }
+        ModelVersion::V10 | ModelVersion::V019 => {
+            synth_chunks(model, model_version, tokens, pack, options.speed, space, crossfade).await?
+        }
+    };
+
+    Ok((
+        audio,
+        SynthStats {
+            g2p,
+            tokenization,
+            ..stats
+        },
+    ))
 }
 
-/// Synthesize speech using v0.19 model
-/// v0.19 voice pack shape: (511, 256) instead of (510, 1, 256)
-pub(super) async fn synth_v019<'a, P, S>(
-    model: Weak<Mutex<Session>>,
+pub(super) async fn synth<S, V>(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
     text: S,
-    pack: P,
-    voice: VoiceV019,
-) -> Result<(Vec<f32>, Duration), KokoroError>
+    pack: VoicePack<'_>,
+    voice: V,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats), KokoroError>
 where
-    P: AsRef<Vec<Vec<f32>>>,  // Note: 2D not 3D! (511, 256)
     S: AsRef<str>,
+    V: SynthVoice,
 {
+    let (audio, stats, _) =
+        synth_timed(model, model_version, vocabulary, Input::Text(text.as_ref()), pack, voice, options).await?;
+    Ok((audio, stats))
+}
+
+/// 与[`synth`]相同，同时返回每个片段的位置，用于生成字幕
+pub(super) async fn synth_timed<V: SynthVoice>(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
+    input: Input<'_>,
+    pack: VoicePack<'_>,
+    voice: V,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats, Timeline), KokoroError> {
+    let span = span!(INFO, "synth", version = ?model_version, voice = voice.name(), chars = input.as_str().len());
+    let ((audio, timeline), stats) = observe(span, async {
+        voice.check(model_version)?;
+        let (language, accent) = voice.language();
+        let g2p_options = G2POptions {
+            language,
+            accent,
+            variant: options.variant,
+        };
+        let (audio, stats, timeline) =
+            synth_text_timed(model, model_version, vocabulary, input, pack, &g2p_options, options).await?;
+        Ok(((audio, timeline), stats))
+    })
+    .await?;
+
+    Ok((audio, stats, timeline))
+}
+
+/// 按选项切分文本、逐段合成并拼接，最后做推理之后的处理
+pub(super) async fn synth_text(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
+    input: Input<'_>,
+    pack: VoicePack<'_>,
+    g2p_options: &G2POptions,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats), KokoroError> {
+    let (audio, stats, _) = synth_text_timed(model, model_version, vocabulary, input, pack, g2p_options, options).await?;
+    Ok((audio, stats))
+}
+
+async fn synth_text_timed(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
+    input: Input<'_>,
+    pack: VoicePack<'_>,
+    g2p_options: &G2POptions,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats, Timeline), KokoroError> {
+    options.validate()?;
+    let segments: Vec<_> = match input {
+        Input::Text(text) if options.pauses.is_some() => split_pauses(text)
+            .into_iter()
+            .map(|(range, boundary)| (range.clone(), Input::Text(&text[range]), boundary))
+            .collect(),
+        // 每条字幕单独合成，没有停顿时不再按标点细分
+        Input::Cues(text, split) => split_cues(text, split)
+            .into_iter()
+            .flat_map(|pieces| match options.pauses {
+                Some(_) => pieces,
+                None => vec![(pieces[0].0.start..pieces[pieces.len() - 1].0.end, Boundary::None)],
+            })
+            .map(|(range, boundary)| (range.clone(), Input::Text(&text[range]), boundary))
+            .collect(),
+        input => vec![(0..input.as_str().len(), input, Boundary::None)],
+    };
+
+    let crossfade = samples(options.crossfade);
+    let mut audio = Vec::new();
+    let mut stats = SynthStats::default();
+    let mut timeline = Vec::with_capacity(segments.len());
+    for (range, input, boundary) in segments {
+        let (segment, took) = synth_segment(model.clone(), model_version, vocabulary, input, pack, g2p_options, options).await?;
+        stats += took;
+        let segment = match options.trim_silence {
+            Some(threshold) => trim_silence(&segment, threshold),
+            None => &segment,
+        };
+        join(&mut audio, segment, crossfade);
+        timeline.push((range, audio.len().saturating_sub(segment.len())..audio.len()));
+        if let Some(pauses) = options.pauses
+            && boundary != Boundary::None
+        {
+            // 停顿前先淡出，之后的片段与静音交叉淡化即为淡入
+            fade_out(&mut audio, crossfade);
+            audio.resize(audio.len() + samples(pauses.get(boundary)), 0.);
+        }
+    }
+    // 首尾也做淡化，流式合成的相邻请求直接拼接时不会在接缝处产生咔嗒声
+    fade_in(&mut audio, crossfade);
+    fade_out(&mut audio, crossfade);
+
+    let audio = span!(DEBUG, "post_process").in_scope(|| post_process(audio, options));
+    stats.audio = SynthStats::audio_duration(audio.len());
+
+    Ok((audio, stats, timeline))
+}
+
+/// 合成SSML，每个片段使用各自的语音、语言和选项，响度归一化在拼接之后对整段音频进行
+pub(super) async fn synth_ssml(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    vocabulary: &Vocabulary,
+    voices: &VoiceBank,
+    ssml: &str,
+    voice: Voice,
+    options: &SynthOptions,
+) -> Result<(Vec<f32>, SynthStats), KokoroError> {
+    let span = span!(INFO, "synth_ssml", version = ?model_version, voice = voice.get_name(), chars = ssml.len());
+    observe(span, async {
+        options.validate()?;
+        let crossfade = samples(options.crossfade);
+        let outer = SynthOptions {
+            loudness: None,
+            ..*options
+        };
+        let mut audio = Vec::new();
+        let mut stats = SynthStats::default();
+        for Segment {
+            content,
+            voice,
+            language,
+            options,
+        } in parse_ssml(ssml, voice, outer)
+        {
+            let input = match &content {
+                Content::Text(text) => Input::Text(text),
+                Content::Phonemes(phonemes) => Input::Phonemes(phonemes),
+                Content::Break(pause) => {
+                    fade_out(&mut audio, crossfade);
+                    audio.resize(audio.len() + samples(*pause), 0.);
+                    continue;
+                }
+            };
+            check_voice(voice, model_version)?;
+            let info = voice.info();
+            let (language, accent) = language.unwrap_or((info.language, info.accent));
+            let g2p_options = G2POptions {
+                language,
+                accent,
+                variant: options.variant,
+            };
+            let loaded = voices.get(voice.get_name()).await?;
+            let (segment, took) =
+                synth_text(model.clone(), model_version, vocabulary, input, loaded.pack()?, &g2p_options, &options).await?;
+            stats += took;
+            join(&mut audio, &segment, crossfade);
+        }
+
+        let normalize = SynthOptions {
+            loudness: options.loudness,
+            ..Default::default()
+        };
+        let audio = span!(DEBUG, "post_process").in_scope(|| post_process(audio, &normalize));
+        stats.audio = SynthStats::audio_duration(audio.len());
+
+        Ok((audio, stats))
+    })
+    .await
+}
+
+/// Split tokens without the boundary pads into chunks of at most `max` tokens.
+/// Cuts at the last `space` token inside the limit so words are not split, the space itself is dropped.
+fn split_tokens(tokens: &[i64], max: usize, space: Option<i64>) -> Vec<&[i64]> {
+    let mut chunks = Vec::new();
+    let mut rest = tokens;
+    while rest.len() > max {
+        match space.and_then(|s| rest[..=max].iter().rposition(|&t| t == s)).filter(|&i| i > 0) {
+            Some(i) => {
+                chunks.push(&rest[..i]);
+                rest = &rest[i + 1..];
+            }
+            None => {
+                chunks.push(&rest[..max]);
+                rest = &rest[max..];
+            }
+        }
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+/// 模型的上下文最多512个编号，去掉首尾的填充后每段最多510个
+const MAX_CHUNK_TOKENS: usize = 510;
+
+/// 合成带首尾填充的音素编号
+///
+/// 超出模型上下文的序列在`space`处切成多段合成，再交叉淡化拼接。
+/// 所有分段使用按整段长度选出的同一个风格向量，避免音色在接缝处跳变；v0.19的语音包有511行，v1.0和v1.1的有510行。
+async fn synth_chunks(
+    model: Weak<Model>,
+    model_version: ModelVersion,
+    phonemes: Vec<i64>,
+    pack: VoicePack<'_>,
+    speed: f32,
+    space: Option<i64>,
+    crossfade: usize,
+) -> Result<(Vec<f32>, SynthStats), KokoroError> {
     let model = model.upgrade().ok_or(KokoroError::ModelReleased)?;
 
-    // v0.19 uses g2p with v10 format (English)
-    let phonemes = g2p(text.as_ref(), false)?;
+    let zeros = vec![0.; 256];
+    let style = (phonemes.len() - 1).min(pack.len().saturating_sub(1));
+    let ref_s = pack.style(style).unwrap_or(&zeros);
 
-    // Use v0.19 tokenizer with 177 tokens
-    let phonemes = get_token_ids_v019(&phonemes);
+    let mut ret = Vec::new();
+    let mut stats = SynthStats::default();
+    for chunk in split_tokens(&phonemes[1..phonemes.len() - 1], MAX_CHUNK_TOKENS, space) {
+        let mut tokens = Vec::with_capacity(chunk.len() + 2);
+        tokens.push(0);
+        tokens.extend_from_slice(chunk);
+        tokens.push(0);
+        let input = ModelInput {
+            version: model_version,
+            tokens: &tokens,
+            style: ref_s,
+            speed,
+        };
+        let (audio, took) = infer(&model, input).await?;
+        stats.inference += took;
+        stats.chunks += 1;
+        join(&mut ret, &audio, crossfade);
+    }
 
-    let phonemes_len = phonemes.len();
-    let phonemes = Array::from_shape_vec((1, phonemes_len), phonemes)?;
+    Ok((ret, stats))
+}
 
-    // Get style vector from voice pack
-    // Pack shape is (511, 256) - use phonemes_len-1 as index, clamped to valid range
-    let style_idx = (phonemes_len - 1).min(pack.as_ref().len() - 1);
-    let ref_s = pack.as_ref().get(style_idx)
-        .map(|i| i.clone())
-        .unwrap_or_else(|| vec![0.0; 256]);
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{VoiceStore, backend::Backend},
+        futures::future::BoxFuture,
+        std::sync::{Arc, Mutex as StdMutex},
+        tokio::sync::Mutex,
+    };
 
-    let style = Array::from_shape_vec((1, ref_s.len()), ref_s)?;
-    let speed = Array::from_vec(vec![voice.get_speed()]);
+    /// 假模型每个音素编号输出的采样数（语速为1时）
+    const SAMPLES_PER_TOKEN: usize = 100;
 
-    let options = RunOptions::new()?;
-    let mut model = model.lock().await;
-    let t = SystemTime::now();
+    #[derive(Clone, Debug, PartialEq)]
+    struct Call {
+        version: ModelVersion,
+        tokens: Vec<i64>,
+        /// 风格向量的第一个值，等于选中的行号
+        style: f32,
+        speed: f32,
+    }
+
+    /// 确定性的假模型：记录每次调用，每个编号输出`SAMPLES_PER_TOKEN / speed`个值为编号千分之一的采样
+    struct FakeBackend(Arc<StdMutex<Vec<Call>>>);
+
+    impl Backend for FakeBackend {
+        fn infer<'a>(&'a mut self, input: ModelInput<'a>) -> BoxFuture<'a, Result<Vec<f32>, KokoroError>> {
+            self.0.lock().unwrap().push(Call {
+                version: input.version,
+                tokens: input.tokens.to_vec(),
+                style: input.style[0],
+                speed: input.speed,
+            });
+            let n = (SAMPLES_PER_TOKEN as f32 / input.speed).round() as usize;
+            let audio = input
+                .tokens
+                .iter()
+                .flat_map(|&t| std::iter::repeat_n(t as f32 / 1000., n))
+                .collect();
+            Box::pin(async move { Ok(audio) })
+        }
+    }
 
-    let kokoro_output = model
-        .run_async(
-            inputs![
-                "tokens" => TensorRef::from_array_view(&phonemes)?,
-                "style" => TensorRef::from_array_view(&style)?,
-                "speed" => TensorRef::from_array_view(&speed)?,
-            ],
+    fn fake_model() -> (Arc<Model>, Arc<StdMutex<Vec<Call>>>) {
+        let calls = Arc::new(StdMutex::new(Vec::new()));
+        let model: Arc<Model> = Arc::new(Mutex::new(FakeBackend(calls.clone())));
+        (model, calls)
+    }
+
+    /// 每一行风格向量的值都等于行号，便于检查选中的是哪一行
+    fn voices(rows: usize) -> VoiceStore {
+        let mut store = VoiceStore::with_capacity(1, rows * 4);
+        store.push("test".to_owned(), rows, 4, (0..rows).flat_map(|r| [r as f32; 4]));
+        store
+    }
+
+    #[tokio::test]
+    async fn test_style_and_speed_v10() -> Result<(), KokoroError> {
+        let (model, calls) = fake_model();
+        let voices = voices(510);
+        let tokens = vec![0, 50, 83, 54, 0];
+        let (audio, _) = synth_chunks(
+            Arc::downgrade(&model),
+            ModelVersion::V10,
+            tokens.clone(),
+            voices.get("test").unwrap(),
+            1.25,
+            None,
+            0,
+        )
+        .await?;
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(
+            vec![Call {
+                version: ModelVersion::V10,
+                tokens,
+                style: 4.,
+                speed: 1.25,
+            }],
+            *calls
+        );
+        assert_eq!(5 * 80, audio.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chunking() -> Result<(), KokoroError> {
+        const SPACE: i64 = 16;
+        // 400个两个音素的单词，共1199个音素
+        let words = vec![[43, 44]; 400];
+        let mut tokens = vec![0];
+        tokens.extend(words.join(&SPACE));
+        tokens.push(0);
+        let crossfade = 10;
+        for (version, rows) in [(ModelVersion::V019, 511), (ModelVersion::V10, 510), (ModelVersion::V11, 510)] {
+            let (model, calls) = fake_model();
+            let voices = voices(rows);
+            let (audio, stats) = synth_chunks(
+                Arc::downgrade(&model),
+                version,
+                tokens.clone(),
+                voices.get("test").unwrap(),
+                1.,
+                Some(SPACE),
+                crossfade,
+            )
+            .await?;
+
+            let calls = calls.lock().unwrap();
+            assert_eq!(3, calls.len());
+            assert_eq!(calls.len(), stats.chunks);
+            for call in calls.iter() {
+                assert_eq!(version, call.version);
+                assert!(call.tokens.len() <= 512);
+                assert_eq!((Some(&0), Some(&0)), (call.tokens.first(), call.tokens.last()));
+                // 在空格处切分，分段的开头和结尾都不是空格
+                assert_ne!(SPACE, call.tokens[1]);
+                assert_ne!(SPACE, call.tokens[call.tokens.len() - 2]);
+                // 所有分段使用语音包的最后一行风格向量
+                assert_eq!((rows - 1) as f32, call.style);
+            }
+            let total = calls.iter().map(|i| i.tokens.len() - 2).sum::<usize>();
+            assert_eq!(tokens.len() - 2 - (calls.len() - 1), total);
+            // 相邻的分段交叉淡化拼接
+            let samples = calls.iter().map(|i| i.tokens.len() * SAMPLES_PER_TOKEN).sum::<usize>();
+            assert_eq!(samples - crossfade * (calls.len() - 1), audio.len());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_long_text_v10() -> Result<(), KokoroError> {
+        let (model, calls) = fake_model();
+        let voices = voices(510);
+        let vocabulary = Vocabulary::builtin(ModelVersion::V10);
+        let phonemes = "həlˈoʊ wˈɜːld ".repeat(60);
+        let (audio, stats) = synth_segment(
+            Arc::downgrade(&model),
+            ModelVersion::V10,
+            &vocabulary,
+            Input::Phonemes(&phonemes),
+            voices.get("test").unwrap(),
+            &Default::default(),
+            &Default::default(),
+        )
+        .await?;
+
+        // 超过510个音素时分段合成，而不是用空的风格向量合成整段
+        let calls = calls.lock().unwrap();
+        assert_eq!(2, stats.chunks);
+        assert!(calls.iter().all(|i| i.tokens.len() <= 512 && i.style == 509.));
+        assert!(!audio.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_speed_v11() -> Result<(), KokoroError> {
+        let (model, calls) = fake_model();
+        let voices = voices(510);
+        let vocabulary = Vocabulary::builtin(ModelVersion::V11);
+        let options = SynthOptions {
+            speed: 1.5,
+            ..Default::default()
+        };
+        let phonemes = "ni2 xau3 ".repeat(100);
+        let (audio, stats) = synth_segment(
+            Arc::downgrade(&model),
+            ModelVersion::V11,
+            &vocabulary,
+            Input::Phonemes(&phonemes),
+            voices.get("test").unwrap(),
+            &Default::default(),
+            &options,
+        )
+        .await?;
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(2, calls.len());
+        assert_eq!(2, stats.chunks);
+        // 直接合成音素时没有g2p耗时
+        assert_eq!(Duration::ZERO, stats.g2p);
+        // 模型使用取整后的语速，剩余的比例通过时间伸缩补足
+        assert!(calls.iter().all(|i| i.speed == 2. && i.style == 509.));
+        let model_samples = calls.iter().map(|i| i.tokens.len() * SAMPLES_PER_TOKEN / 2).sum::<usize>();
+        let expected = model_samples as f32 * 2. / 1.5;
+        assert!((audio.len() as f32 - expected).abs() < expected * 0.01);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_strict_english() -> Result<(), KokoroError> {
+        let voices = voices(511);
+        let options = SynthOptions {
+            strict: true,
+            ..Default::default()
+        };
+        for version in [ModelVersion::V10, ModelVersion::V019] {
+            for accent in [Accent::American, Accent::British] {
+                let (model, calls) = fake_model();
+                let g2p_options = G2POptions {
+                    language: Language::English,
+                    accent,
+                    ..Default::default()
+                };
+                // 普通英语文本的音素都在词表中，严格模式不应报错
+                synth_segment(
+                    Arc::downgrade(&model),
+                    version,
+                    &Vocabulary::builtin(version),
+                    Input::Text("What is this? Whichever way, Mr. Smith had read the children a story."),
+                    voices.get("test").unwrap(),
+                    &g2p_options,
+                    &options,
+                )
+                .await?;
+                assert_eq!(1, calls.lock().unwrap().len());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stats() -> Result<(), KokoroError> {
+        let (model, _) = fake_model();
+        let voices = voices(510);
+        let vocabulary = Vocabulary::builtin(ModelVersion::V10);
+        let (audio, stats) = synth_text(
+            Arc::downgrade(&model),
+            ModelVersion::V10,
+            &vocabulary,
+            Input::Phonemes("həlˈoʊ wˈɜːld"),
+            voices.get("test").unwrap(),
+            &Default::default(),
+            &Default::default(),
+        )
+        .await?;
+
+        assert_eq!(1, stats.chunks);
+        assert_eq!(SynthStats::audio_duration(audio.len()), stats.audio);
+        assert!(stats.rtf() >= 0.);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cue_timeline() -> Result<(), KokoroError> {
+        let (model, calls) = fake_model();
+        let voices = voices(510);
+        let vocabulary = Vocabulary::builtin(ModelVersion::V10);
+        let text = "Hello, world. Goodbye.";
+        let options = SynthOptions {
+            pauses: Some(Default::default()),
+            ..Default::default()
+        };
+        let (audio, _, timeline) = synth_timed(
+            Arc::downgrade(&model),
+            ModelVersion::V10,
+            &vocabulary,
+            Input::Cues(text, CueSplit::Sentence),
+            voices.get("test").unwrap(),
+            Voice::AfHeart,
             &options,
-        )?
+        )
         .await?;
 
-    let elapsed = t.elapsed()?;
-    let (_, audio) = kokoro_output["audio"].try_extract_tensor::<f32>()?;
+        assert_eq!(3, calls.lock().unwrap().len());
+        let ranges = timeline.iter().map(|(t, _)| &text[t.clone()]).collect::<Vec<_>>();
+        assert_eq!(vec!["Hello,", " world.", " Goodbye."], ranges);
+        // 片段之间的间隔是停顿减去与静音交叉淡化的部分
+        let crossfade = samples(options.crossfade);
+        assert_eq!(samples(Duration::from_millis(200)) - crossfade, timeline[1].1.start - timeline[0].1.end);
+        assert_eq!(samples(Duration::from_millis(400)) - crossfade, timeline[2].1.start - timeline[1].1.end);
+        assert_eq!(audio.len(), timeline[2].1.end + samples(Duration::from_millis(400)));
 
-    Ok((audio.to_owned(), elapsed))
+        // 没有停顿时每条字幕只合成一次
+        let (model, calls) = fake_model();
+        let (_, _, timeline) = synth_timed(
+            Arc::downgrade(&model),
+            ModelVersion::V10,
+            &vocabulary,
+            Input::Cues(text, CueSplit::Sentence),
+            voices.get("test").unwrap(),
+            Voice::AfHeart,
+            &Default::default(),
+        )
+        .await?;
+        assert_eq!(2, calls.lock().unwrap().len());
+        assert_eq!(vec![0..13, 13..22], timeline.into_iter().map(|(t, _)| t).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_model_released() {
+        let (model, _) = fake_model();
+        let weak = Arc::downgrade(&model);
+        drop(model);
+        let voices = voices(510);
+        let result = synth_chunks(weak, ModelVersion::V10, vec![0, 0], voices.get("test").unwrap(), 1., None, 0).await;
+        assert!(matches!(result, Err(KokoroError::ModelReleased)));
+    }
+
+    #[test]
+    fn test_split_tokens() {
+        const SPACE: i64 = 16;
+        let tokens = [1, 2, SPACE, 3, 4, 5, SPACE, 6];
+        assert_eq!(vec![&[1, 2][..], &[3, 4, 5], &[6]], split_tokens(&tokens, 4, Some(SPACE)));
+        assert_eq!(vec![&[1, 2, SPACE][..], &[3, 4, 5], &[SPACE, 6]], split_tokens(&tokens, 3, None));
+        assert_eq!(vec![&tokens[..]], split_tokens(&tokens, 8, Some(SPACE)));
+        assert!(split_tokens(&[], 4, Some(SPACE)).is_empty());
+    }
+
+    #[test]
+    fn test_synth_voice() {
+        assert_eq!((Language::English, Accent::British), VoiceV019::BmLewis.language());
+        assert_eq!((Language::English, Accent::American), VoiceV019::Af.language());
+        assert!(VoiceV019::Af.check(ModelVersion::V019).is_ok());
+        assert!(VoiceV019::Af.check(ModelVersion::V10).is_err());
+        assert!(VoiceV019::AfBella.ssml_voice().is_none());
+        // v0.19的语音包中也能按v1.0的语音名称查找
+        assert!(Voice::AfBella.check(ModelVersion::V019).is_ok());
+        assert_eq!(Some(Voice::AfBella), Voice::AfBella.ssml_voice());
+    }
 }
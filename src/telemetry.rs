@@ -0,0 +1,183 @@
+//! 合成流程的可观测性
+//!
+//! 启用`tracing`特性后，每次请求会创建`synth` span，其下有`g2p`、`tokenize`、`lock_wait`、`inference`和`post_process`；
+//! 启用`metrics`特性后，通过`metrics`门面记录下面列出的指标。两个特性都未启用时这里的函数都是空操作。
+
+use {
+    crate::{KokoroError, SynthStats},
+    std::{future::Future, time::Duration},
+};
+
+/// 合成请求数（计数器）
+pub const METRIC_REQUESTS: &str = "kokoro_synth_requests_total";
+/// 失败的合成请求数（计数器）
+pub const METRIC_ERRORS: &str = "kokoro_synth_errors_total";
+/// 每次请求的实时率（直方图）
+pub const METRIC_RTF: &str = "kokoro_synth_rtf";
+/// 每次请求的推理耗时，单位为秒（直方图）
+pub const METRIC_INFERENCE: &str = "kokoro_inference_seconds";
+/// 每次请求的g2p耗时，单位为秒（直方图）
+pub const METRIC_G2P: &str = "kokoro_g2p_seconds";
+/// 每次请求的音素编号转换耗时，单位为秒（直方图）
+pub const METRIC_TOKENIZATION: &str = "kokoro_tokenization_seconds";
+/// 每次推理等待模型锁的时间，单位为秒（直方图）
+pub const METRIC_LOCK_WAIT: &str = "kokoro_lock_wait_seconds";
+/// 命中[`SynthCache`](crate::SynthCache)的请求数（计数器）
+pub const METRIC_CACHE_HITS: &str = "kokoro_cache_hits_total";
+/// 未命中[`SynthCache`](crate::SynthCache)的请求数（计数器）
+pub const METRIC_CACHE_MISSES: &str = "kokoro_cache_misses_total";
+/// 所有`SynthSink`中已发送但还没合成完的请求数（仪表）
+pub const METRIC_QUEUE_DEPTH: &str = "kokoro_stream_queue_depth";
+
+/// 向已安装的记录器登记所有指标的单位和说明，应在安装记录器之后调用
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+
+    describe_counter!(METRIC_REQUESTS, "Synthesis requests");
+    describe_counter!(METRIC_ERRORS, "Synthesis requests that returned an error");
+    describe_histogram!(METRIC_RTF, "Real-time factor of each request, processing time over audio duration");
+    describe_histogram!(METRIC_INFERENCE, Unit::Seconds, "Model inference time of each request");
+    describe_histogram!(METRIC_G2P, Unit::Seconds, "Text to phoneme conversion time of each request");
+    describe_histogram!(METRIC_TOKENIZATION, Unit::Seconds, "Phoneme to token conversion time of each request");
+    describe_histogram!(METRIC_LOCK_WAIT, Unit::Seconds, "Time spent waiting for the model lock before each inference");
+    describe_counter!(METRIC_CACHE_HITS, "Requests answered from the synthesis cache");
+    describe_counter!(METRIC_CACHE_MISSES, "Requests that missed the synthesis cache");
+    describe_gauge!(METRIC_QUEUE_DEPTH, "Stream requests sent but not yet synthesized");
+}
+
+/// 创建一个span，未启用`tracing`特性时得到空的[`Span`]，字段不会被求值
+macro_rules! span {
+    ($level:ident, $name:literal $(, $($fields:tt)+)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::span!(tracing::Level::$level, $name $(, $($fields)+)?);
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::telemetry::Span;
+        span
+    }};
+}
+pub(crate) use span;
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::Span;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    pub(crate) fn in_scope<F: FnOnce() -> T, T>(&self, f: F) -> T {
+        f()
+    }
+}
+
+/// 在span中执行异步任务
+#[cfg(feature = "tracing")]
+pub(crate) async fn instrument<F: Future>(span: Span, future: F) -> F::Output {
+    tracing::Instrument::instrument(future, span).await
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn instrument<F: Future>(_span: Span, future: F) -> F::Output {
+    future.await
+}
+
+/// 在span中执行一次合成请求，并记录请求数、错误数和耗时
+pub(crate) async fn observe<F, T>(span: Span, request: F) -> Result<(T, SynthStats), KokoroError>
+where
+    F: Future<Output = Result<(T, SynthStats), KokoroError>>,
+{
+    let result = instrument(span, request).await;
+    #[cfg(feature = "metrics")]
+    {
+        use metrics::{counter, histogram};
+
+        counter!(METRIC_REQUESTS).increment(1);
+        match &result {
+            Ok((_, stats)) => {
+                histogram!(METRIC_RTF).record(stats.rtf());
+                histogram!(METRIC_INFERENCE).record(stats.inference);
+                histogram!(METRIC_G2P).record(stats.g2p);
+                histogram!(METRIC_TOKENIZATION).record(stats.tokenization);
+            }
+            Err(_) => counter!(METRIC_ERRORS).increment(1),
+        }
+    }
+
+    result
+}
+
+/// 记录一次等待模型锁的时间
+pub(crate) fn record_lock_wait(_wait: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(METRIC_LOCK_WAIT).record(_wait);
+}
+
+/// 记录一次缓存查询
+pub(crate) fn record_cache(_hit: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(if _hit { METRIC_CACHE_HITS } else { METRIC_CACHE_MISSES }).increment(1);
+}
+
+/// 调整流式合成队列中的请求数
+pub(crate) fn add_queue_depth(_delta: f64) {
+    #[cfg(feature = "metrics")]
+    metrics::gauge!(METRIC_QUEUE_DEPTH).increment(_delta);
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use {
+        super::*,
+        futures::executor::block_on,
+        metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit, with_local_recorder},
+        std::sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    };
+
+    /// 只记录请求数和错误数的记录器
+    #[derive(Default)]
+    struct Counters {
+        requests: Arc<AtomicU64>,
+        errors: Arc<AtomicU64>,
+    }
+
+    impl Recorder for Counters {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            match key.name() {
+                METRIC_REQUESTS => Counter::from_arc(self.requests.clone()),
+                METRIC_ERRORS => Counter::from_arc(self.errors.clone()),
+                _ => Counter::noop(),
+            }
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_observe() {
+        let recorder = Counters::default();
+        with_local_recorder(&recorder, || {
+            let ok = block_on(observe(span!(INFO, "test"), async { Ok(((), SynthStats::default())) }));
+            assert!(ok.is_ok());
+            let err = block_on(observe(span!(INFO, "test"), async {
+                Err::<((), SynthStats), _>(KokoroError::ModelReleased)
+            }));
+            assert!(err.is_err());
+        });
+        assert_eq!(2, recorder.requests.load(Ordering::Relaxed));
+        assert_eq!(1, recorder.errors.load(Ordering::Relaxed));
+    }
+}
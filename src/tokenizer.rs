@@ -1,10 +1,11 @@
 use {
+    crate::Vocabulary,
     log::warn,
     std::{collections::HashMap, sync::LazyLock},
 };
 
-const VOCAB_V10: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
-    let mut map = HashMap::new();
+pub(crate) static VOCAB_V10: LazyLock<Vocabulary> = LazyLock::new(|| {
+    let mut map = HashMap::<char, i64>::new();
 
     map.insert(';', 1);
     map.insert(':', 2);
@@ -120,11 +121,11 @@ const VOCAB_V10: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
     map.insert('↗', 172);
     map.insert('↘', 173);
     map.insert('ᵻ', 177);
-    map
+    map.into()
 });
 
-const VOCAB_V11: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
-    let mut map = HashMap::new();
+pub(crate) static VOCAB_V11: LazyLock<Vocabulary> = LazyLock::new(|| {
+    let mut map = HashMap::<char, i64>::new();
 
     map.insert(';', 1);
     map.insert(':', 2);
@@ -297,29 +298,57 @@ const VOCAB_V11: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
     map.insert('元', 175);
     map.insert('云', 176);
     map.insert('ᵻ', 177);
-    map
+    map.into()
 });
 
-pub fn get_token_ids(phonemes: &str, v11: bool) -> Vec<i64> {
-    let mut tokens = Vec::with_capacity(phonemes.len() + 2);
-    tokens.push(0);
+/// 词表中没有的音素符号
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownPhoneme {
+    pub symbol: char,
+    /// 符号在音素串中的位置，按字符计数
+    pub position: usize,
+    /// 符号所在的单词。由分词器直接报告时是音素串中以空白分隔的单词，合成时会换成原文中的单词。
+    pub word: String,
+}
 
-    for i in phonemes.chars() {
-        let v = if v11 {
-            VOCAB_V11.get(&i).map(|i| *i)
-        } else {
-            VOCAB_V10.get(&i).map(|i| *i)
-        };
-        match v {
-            Some(t) => {
-                tokens.push(t as _);
-            }
-            _ => {
-                warn!("Unknown phone {}, skipped.", i);
-            }
+impl UnknownPhoneme {
+    /// 记录音素串中第`position`个字符（字节偏移为`offset`）为未知符号
+    pub(crate) fn new(phonemes: &str, symbol: char, position: usize, offset: usize) -> Self {
+        let start = phonemes[..offset]
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| i + phonemes[i..].chars().next().map_or(1, char::len_utf8));
+        let end = phonemes[offset..]
+            .find(char::is_whitespace)
+            .map_or(phonemes.len(), |i| offset + i);
+        Self {
+            symbol,
+            position,
+            word: phonemes[start..end].to_owned(),
         }
     }
+}
 
-    tokens.push(0);
+pub fn get_token_ids(phonemes: &str, v11: bool) -> Vec<i64> {
+    let (tokens, unknown) = get_token_ids_checked(phonemes, v11);
+    for i in unknown {
+        warn!("Unknown phone {} in {}, skipped.", i.symbol, i.word);
+    }
     tokens
 }
+
+/// 将音素转换为词表中的编号，同时返回所有被跳过的未知符号
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::get_token_ids_checked;
+///
+/// let (tokens, unknown) = get_token_ids_checked("həlˈoʊ w❓ɹld", false);
+/// assert_eq!(1, unknown.len());
+/// assert_eq!(('❓', 8, "w❓ɹld"), (unknown[0].symbol, unknown[0].position, unknown[0].word.as_str()));
+/// assert_eq!(tokens.len(), 13);
+/// ```
+///
+pub fn get_token_ids_checked(phonemes: &str, v11: bool) -> (Vec<i64>, Vec<UnknownPhoneme>) {
+    if v11 { VOCAB_V11.encode(phonemes) } else { VOCAB_V10.encode(phonemes) }
+}
@@ -1,65 +1,32 @@
-use log::warn;
-use std::{collections::HashMap, sync::LazyLock};
-
-/// Load v0.19 vocabulary from tokens.txt
-/// Format: "character token_id" per line
-fn load_vocab_from_tokens(tokens_txt: &str) -> HashMap<char, u8> {
-    let mut map = HashMap::new();
-
-    for line in tokens_txt.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            // First part is the character (may be multi-byte UTF-8)
-            // Second part is the token ID
-            if let Ok(token_id) = parts[1].parse::<u8>() {
-                // Get the first character (handles multi-byte properly)
-                if let Some(ch) = parts[0].chars().next() {
-                    map.insert(ch, token_id);
-                }
-            }
-        }
-    }
-
-    map
-}
+use crate::{UnknownPhoneme, Vocabulary};
+use log::{debug, warn};
+use std::sync::LazyLock;
 
 // Embed the tokens.txt file at compile time
 const TOKENS_V019: &str = include_str!("../dict/v0_19/tokens.txt");
 
-static VOCAB_V019: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
-    let vocab = load_vocab_from_tokens(TOKENS_V019);
-    eprintln!("Loaded v0.19 vocabulary: {} tokens", vocab.len());
+/// v0.19 vocabulary loaded from tokens.txt
+/// Format: "character token_id" per line, the space token is the line "  16"
+pub(crate) static VOCAB_V019: LazyLock<Vocabulary> = LazyLock::new(|| {
+    let vocab = Vocabulary::from_tokens_txt(TOKENS_V019).expect("embedded tokens.txt is valid");
+    debug!("Loaded v0.19 vocabulary: {} tokens", vocab.len());
     vocab
 });
 
 /// Convert phoneme string to token IDs for v0.19
 pub fn get_token_ids_v019(phonemes: &str) -> Vec<i64> {
-    let mut tokens = Vec::with_capacity(phonemes.len() + 2);
-    tokens.push(0); // BOS (beginning of sequence) token
-
-    for ch in phonemes.chars() {
-        match VOCAB_V019.get(&ch) {
-            Some(&token_id) => {
-                tokens.push(token_id as i64);
-            }
-            None => {
-                // Only warn for non-space characters
-                if ch != ' ' && ch != '\n' && ch != '\t' {
-                    warn!("[v0.19] Unknown phone '{}' (U+{:04X}), skipped.", ch, ch as u32);
-                }
-            }
-        }
+    let (tokens, unknown) = get_token_ids_v019_checked(phonemes);
+    for i in unknown {
+        warn!("[v0.19] Unknown phone '{}' (U+{:04X}) in {}, skipped.", i.symbol, i.symbol as u32, i.word);
     }
-
-    tokens.push(0); // EOS (end of sequence) token
     tokens
 }
 
+/// Convert phoneme string to token IDs for v0.19, also returning the skipped symbols
+pub fn get_token_ids_v019_checked(phonemes: &str) -> (Vec<i64>, Vec<UnknownPhoneme>) {
+    VOCAB_V019.encode(phonemes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,13 +37,14 @@ mod tests {
         assert!(vocab.len() > 150, "Should have 170+ tokens, got {}", vocab.len());
 
         // Check some known tokens from tokens.txt
-        assert_eq!(vocab.get(&'$'), Some(&0));   // $ 0
-        assert_eq!(vocab.get(&';'), Some(&1));   // ; 1
-        assert_eq!(vocab.get(&'ɝ'), Some(&88));  // ɝ 88 - This was missing in v1.0!
-        assert_eq!(vocab.get(&'ɚ'), Some(&85));  // ɚ 85
-        assert_eq!(vocab.get(&'ˈ'), Some(&156)); // ˈ 156 - Primary stress
-        assert_eq!(vocab.get(&'a'), Some(&43));  // a 43
-        assert_eq!(vocab.get(&'z'), Some(&68));  // z 68
+        assert_eq!(vocab.get('$'), Some(0));   // $ 0
+        assert_eq!(vocab.get(';'), Some(1));   // ; 1
+        assert_eq!(vocab.get(' '), Some(16));  // space 16 - word boundary
+        assert_eq!(vocab.get('ɝ'), Some(88));  // ɝ 88 - This was missing in v1.0!
+        assert_eq!(vocab.get('ɚ'), Some(85));  // ɚ 85
+        assert_eq!(vocab.get('ˈ'), Some(156)); // ˈ 156 - Primary stress
+        assert_eq!(vocab.get('a'), Some(43));  // a 43
+        assert_eq!(vocab.get('z'), Some(68));  // z 68
     }
 
     #[test]
@@ -86,4 +54,11 @@ mod tests {
         assert_eq!(tokens[tokens.len()-1], 0); // EOS
         assert!(tokens.len() > 2); // Should have content
     }
+
+    #[test]
+    fn test_unknown_phonemes() {
+        let (tokens, unknown) = get_token_ids_v019_checked("hə❓ lˈoʊ");
+        assert_eq!(vec![UnknownPhoneme { symbol: '❓', position: 2, word: "hə❓".to_owned() }], unknown);
+        assert_eq!(tokens, get_token_ids_v019("hə❓ lˈoʊ"));
+    }
 }
@@ -21,59 +21,157 @@
 #define API
 #endif
 
+#define BITNUM_FLAG_ALT    14 // bit number of FLAG_ALT_TRANS - 1
+#define DOLLAR_LIST        0x03
+#define DOLLAR_NOPREFIX    0x02
+#define DOLLAR_UNPR        0x01
+#define FLAG_ALLCAPS       0x400   // dictionary_flags[1]: only if the word is all capitals
+#define FLAG_ALLOW_TEXTMODE 0x02  // allow dictionary to translate to text rather than phonemes
+#define FLAG_ATEND         0x20000 // dictionary_flags[1]: use this pronunciation if at end of clause
+#define FLAG_ATSTART       0x40000 // dictionary_flags[1]: use this pronunciation if at start of clause
+#define FLAG_CAPITAL       0x200   // dictionary_flags[1]: only if the word has an initial capital
+#define FLAG_FIRST_UPPER   0x2     // word starts with a capital letter
+#define FLAG_FOUND         0x80000000 // word was found in the dictionary list
 #define FLAG_FOUND_ATTRIBUTES 0x40000000 // word was found in the dictionary list (has attributes)
+#define FLAG_HYPHEN        0x80    // a hyphen follows this word
+#define FLAG_HYPHEN_AFTER  0x4000  // a hyphen precedes the next word
+#define FLAG_NEEDS_DOT     0x2000000 // only if the word is followed by a dot
+#define FLAG_NO_PREFIX     0x20000000 // passed to TranslateRules() to ignore prefix rules
 #define FLAG_NO_TRACE      0x10000000 // passed to TranslateRules() to suppress dictionary lookup printout
+#define FLAG_NOUN          0x20    // dictionary_flags[1]: pronunciation if a noun is expected
+#define FLAG_ONLY          0x4000  // dictionary_flags[1]: no match if any suffix has been removed
+#define FLAG_ONLY_S        0x8000  // dictionary_flags[1]: only an 's' suffix is allowed
+#define FLAG_PAST          0x40    // dictionary_flags[1]: pronunciation if past tense is expected
+#define FLAG_PREFIX_REMOVED 0x800000
+#define FLAG_SENTENCE      0x2000  // dictionary_flags[1]: only if the clause is a sentence
+#define FLAG_SKIPWORDS     0x80    // the dictionary entry matches more than one word
+#define FLAG_STEM          0x10000 // dictionary_flags[1]: must have a suffix
+#define FLAG_STRESS_END    0x200   // full stress if at end of clause
+#define FLAG_SUFFIX_REMOVED 0x2000
+#define FLAG_SUFFIX_VOWEL  0x08000000 // remember an ending vowel which was removed
+#define FLAG_SUFX          0x04    // end_flags: a suffix has been removed
+#define FLAG_SUFX_E_ADDED  0x10    // end_flags: an 'e' has been added to the stem
+#define FLAG_SUFX_S        0x08    // end_flags: the suffix is 's' or 'es'
+#define FLAG_TEXTMODE      0x20000000 // word translates to replacement text, not phonemes
 #define FLAG_UNPRON_TEST   0x80000000 // do unpronounability test on the beginning of the word
+#define FLAG_VERB          0x10    // dictionary_flags[1]: pronunciation if a verb is expected
+#define i_APPEND_IFNEXTVOWEL 0x05
+#define i_APPEND_PHONEME  0x04
+#define i_CHANGE_PHONEME  0x01
+#define i_INSERT_PHONEME  0x03
 #define i_IPA_NAME       0x0d
+#define i_JUMP_FALSE     0x6800
+#define i_NOT            0x0003
+#define i_REPLACE_NEXT_PHONEME 0x02
 #define INSTN_CONTINUE       0x0002
 #define INSTN_RETURN         0x0001
+#define isAfterStress      9
+#define isBreak            5
+#define isFinalVowel       11
+#define isFirstVowel       13
+#define isNotVowel         10
+#define isSecondVowel      14
+#define isTranslationGiven 16
+#define isVoiced           12
+#define isWordEnd          8
+#define isWordStart        6
+#define L(c1, c2) (c1 << 8) + c2 // combine two characters into an integer for translator name
+#define LETTERGP_C      2
 #define LETTERGP_VOWEL2 7
+#define LOPT_IT_LENGTHEN     0 // 1 = remove lengthen indicator from unstressed syllables, 2 = also lengthen stressed penultimate syllables
+#define LOPT_REDUCE          1 // bit 0: reduce vowels even if the phonemes are given in the dictionary
+#define LOPT_UNPRONOUNCABLE  2 // 1 = don't check for unpronouncable words, 2 = use the *_rules to check
 #define N_HASH_DICT     1024
 #define N_LETTER_GROUPS   95 // maximum is 127-32
+#define N_LOPTS              3
 #define N_PHON_OUT  500  // realloc increment
 #define N_PHONEME_DATA_PARAM 16
 #define N_PHONEME_LIST 1000 // enough for source[N_TR_SOURCE] full of text, else it will truncate
 #define N_PHONEME_TAB      256     // max phonemes in a phoneme table
+#define N_REMOVE_ACCENT  0x25e
 #define N_RULE_GROUP2    120 // max num of two-letter rule chains
 #define N_TR_SOURCE      800 // the source text of a single clause (UTF8 bytes)
 #define N_WORD_BYTES     160 // max bytes for the UTF8 characters in a word
 #define N_WORD_PHONEMES  200 // max phonemes in a word
 #define pd_ADDWAV 4
+#define phFLAGBIT_LONG        21 // vowel is long, increases the syllable weight
 #define phFLAGBIT_NONSYLLABIC 20 // don't count this vowel as a syllable when finding the stress position
 #define phFLAGBIT_UNSTRESSED 1
+#define phFLAGBIT_VOICED     4
+#define phLIQUID  3
+#define phLONG         (1U << phFLAGBIT_LONG)
+#define phNASAL   8
 #define phNONSYLLABIC  (1U << phFLAGBIT_NONSYLLABIC)
+#define phPAUSE   0
 #define phSTRESS  1
 #define phUNSTRESSED   (1U << phFLAGBIT_UNSTRESSED)
+#define phVOICED       (1U << phFLAGBIT_VOICED)
 #define phVOWEL   2
+#define phonEND_WORD    15
 #define phonGLOTTALSTOP 19
 #define phonLENGTHEN    12
 #define phonPAUSE       9
 #define phonPAUSE_LONG  24
+#define phonPAUSE_NOLINK 11
 #define phonPAUSE_SHORT 10
 #define phonPAUSE_VSHORT 23
+#define phonSCHWA       13
 #define phonSTRESS_2    4
 #define phonSTRESS_3    5
 #define phonSTRESS_D    3
 #define phonSTRESS_P    6
 #define phonSTRESS_P2   7    // priority stress within a word
+#define phonSTRESS_PREV 8
 #define phonSTRESS_TONIC 26
 #define phonSTRESS_U    2
 #define phonSWITCH      21
 #define phonSYLLABIC    20
 #define REPLACED_E       'E' // 'e' replaced by silent e
+#define RULE_CAPITAL      19 // !
 #define RULE_CONDITION   5 // followed by condition number (byte)
+#define RULE_DEC_SCORE    60 // <
+#define RULE_DEL_FWD      13 // #
+#define RULE_DIGIT        15 // D digit
+#define RULE_DOLLAR       28 // $ commands
 #define RULE_DOUBLE       11 // %
+#define RULE_ENDING       14 // word is a suffix, followed by 3 bytes of flags
 #define RULE_GROUP_END   7
 #define RULE_GROUP_START 6
+#define RULE_IFVERB       26 // V
+#define RULE_INC_SCORE    12 // +
 #define RULE_LETTERGP     17 // A B C H F G Y   letter group number
+#define RULE_LETTERGP2    18 // L + letter group number
 #define RULE_LINENUM     9 // next 2 bytes give a line number, for debugging purposes
 #define RULE_NO_SUFFIX    24 // N
+#define RULE_NONALPHA     16 // Z non-alpha
 #define RULE_NOTVOWEL     25 // K
+#define RULE_NOVOWELS     29 // X no vowels up to word boundary
 #define RULE_PH_COMMON   4 // At start of rule. Its phoneme string is used by subsequent rules
 #define RULE_PHONEMES    3
 #define RULE_POST        2
 #define RULE_PRE         1
+#define RULE_PRE_ATSTART 8 // as RULE_PRE but also match with 'start of word'
+#define RULE_REPLACEMENTS 20 // section for character replacements
+#define RULE_SKIPCHARS    23 // J
+#define RULE_SPACE        32 // ascii space
+#define RULE_STRESSED     10 // &
+#define RULE_SYLLABLE     21 // @
+#define S_2_SYL_2          0x1000
+#define S_2_TO_HEAVY       0x40
+#define S_FINAL_DIM        0x04
+#define S_FINAL_DIM_ONLY   0x06
+#define S_FINAL_LONG       0x80000
+#define S_FINAL_NO_2       0x10
+#define S_FINAL_SPANISH    0x200
+#define S_FIRST_PRIMARY    0x80
+#define S_INITIAL_2        0x2000
+#define S_MID_DIM          0x10000
+#define S_NO_AUTO_2        0x20
+#define S_NO_DIM           0x02
+#define S_PRIORITY_STRESS  0x20000
+#define SFLAG_DICTIONARY       0x10 // the pronunciation of this word was listed in the xx_list file
 #define SFLAG_EMBEDDED         0x02 // there are embedded commands before this phoneme
+#define SFLAG_LENGTHEN         0x08 // lengthen symbol : included after this phoneme
 #define SFLAG_SYLLABLE         0x04 // vowel or syllabic consonant
 #define STRESS_IS_DIMINISHED    0       // diminished, unstressed within a word
 #define STRESS_IS_NOT_STRESSED  2       // default, not stressed
@@ -81,11 +179,23 @@
 #define STRESS_IS_PRIORITY      5       // replaces primary markers
 #define STRESS_IS_SECONDARY     3       // secondary stress
 #define STRESS_IS_UNSTRESSED    1       // unstressed, weak
+#define SUFX_A           0x40000 // remember that the suffix starts with a vowel
+#define SUFX_B           0x20000 // break, this is the end of the word
+#define SUFX_D           0x1000  // a double letter may be removed from the stem
+#define SUFX_E           0x0100  // e may have been added
+#define SUFX_I           0x0200  // y may have been changed to i
+#define SUFX_M           0x80000 // bit 19, allow multiple suffixes
+#define SUFX_P           0x0400  // prefix
+#define SUFX_Q           0x4000  // don't retranslate
+#define SUFX_T           0x10000 // don't affect the stress
+#define SUFX_UNPRON      0x8000  // used to return $unpron flag from *_rules
+#define SUFX_V           0x0800  // suffix means use the verb form pronunciation
 
 typedef struct {
 	int points;
 	const char *phonemes;
 	int end_type;
+	char *del_fwd;
 } MatchRecord;
 
 typedef struct {
@@ -130,6 +240,16 @@ typedef struct {
 	unsigned short start;
 } WORD_TAB;
 
+typedef struct {
+	int stress_rule; // 1=first syllable, 2=penultimate,  3=last
+	int stress_flags;
+	int unstressed_wd1; // stress for $u word of 1 syllable
+	int unstressed_wd2; // stress for $u word of >1 syllable
+	int param[N_LOPTS];
+	int max_initial_consonants;
+	int suffix_add_e; // replace a suffix (which has the SUFX_E flag) with this character
+} LANGUAGE_OPTIONS;
+
 static const char *data_dictrules;     // language_1   translation rules file
 static int dict_condition;    // conditional apply some pronunciation rules and dict.lookups
 static const char *dict_hashtab[N_HASH_DICT];   // hash table to index dictionary lookup file
@@ -147,6 +267,8 @@ static const unsigned short ipa1[96] = {
 	0x70,  0x71,  0x72,  0x73,  0x74,  0x75,  0x76,  0x77,  0x78,  0x79,  0x7a,  0x7b,  0x7c,  0x7d,  0x303, 0x7f
 };
 static unsigned char letter_bits[256];
+static int letter_bits_offset = 0; // non-zero for Indic scripts, eg. 0x900 for Devanagari
+static const char *groups3[128];   // translation rule lists, index by offset letter
 static int n_groups2;              // number of groups2[] entries used
 static int n_ph_list2;
 static int n_phoneme_list = 0;
@@ -1710,6 +1832,41 @@ static const char transpose_map_latin[] = {
 	 0,  0,  0,  0,  0,  0,  0,  0,  0,  0, 55,  0, 56,  0, 57,  0, // 0x170
 };
 static char word_phonemes[N_WORD_PHONEMES]; // a word translated into phoneme codes
+static const unsigned char remove_accent[] = {
+	'a', 'a', 'a', 'a', 'a', 'a', 'a', 'c', 'e', 'e', 'e', 'e', 'i', 'i', 'i', 'i', // 0xc0
+	'd', 'n', 'o', 'o', 'o', 'o', 'o',   0, 'o', 'u', 'u', 'u', 'u', 'y', 't', 's', // 0xd0
+	'a', 'a', 'a', 'a', 'a', 'a', 'a', 'c', 'e', 'e', 'e', 'e', 'i', 'i', 'i', 'i', // 0xe0
+	'd', 'n', 'o', 'o', 'o', 'o', 'o',   0, 'o', 'u', 'u', 'u', 'u', 'y', 't', 'y', // 0xf0
+	'a', 'a', 'a', 'a', 'a', 'a', 'c', 'c', 'c', 'c', 'c', 'c', 'c', 'c', 'd', 'd', // 0x100
+	'd', 'd', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'e', 'g', 'g', 'g', 'g', // 0x110
+	'g', 'g', 'g', 'g', 'h', 'h', 'h', 'h', 'i', 'i', 'i', 'i', 'i', 'i', 'i', 'i', // 0x120
+	'i', 'i',   0,   0, 'j', 'j', 'k', 'k',   0, 'l', 'l', 'l', 'l', 'l', 'l',   0, // 0x130
+	  0, 'l', 'l', 'n', 'n', 'n', 'n', 'n', 'n',   0, 'n', 'n', 'o', 'o', 'o', 'o', // 0x140
+	'o', 'o', 'o', 'o', 'r', 'r', 'r', 'r', 'r', 'r', 's', 's', 's', 's', 's', 's', // 0x150
+	's', 's', 't', 't', 't', 't', 't', 't', 'u', 'u', 'u', 'u', 'u', 'u', 'u', 'u', // 0x160
+	'u', 'u', 'u', 'u', 'w', 'w', 'y', 'y', 'y', 'z', 'z', 'z', 'z', 'z', 'z', 's', // 0x170
+	  0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x180
+	  0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x190
+	'o', 'o',   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, 'u', // 0x1a0
+	'u',   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x1b0
+	  0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, 'a', 'a', 'i', // 0x1c0
+	'i', 'o', 'o', 'u', 'u', 'u', 'u', 'u', 'u', 'u', 'u', 'u', 'u',   0, 'a', 'a', // 0x1d0
+	'a', 'a',   0,   0,   0,   0, 'g', 'g', 'k', 'k', 'o', 'o', 'o', 'o',   0,   0, // 0x1e0
+	'j',   0,   0,   0, 'g', 'g',   0,   0, 'n', 'n', 'a', 'a',   0,   0,   0,   0, // 0x1f0
+	'a', 'a', 'a', 'a', 'e', 'e', 'e', 'e', 'i', 'i', 'i', 'i', 'o', 'o', 'o', 'o', // 0x200
+	'r', 'r', 'r', 'r', 'u', 'u', 'u', 'u', 's', 's', 't', 't',   0,   0, 'h', 'h', // 0x210
+	  0,   0,   0,   0,   0,   0, 'a', 'a', 'e', 'e', 'o', 'o', 'o', 'o', 'o', 'o', // 0x220
+	'o', 'o', 'y', 'y',   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x230
+	  0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x240
+	  0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x250
+};
+static char word_replacement[N_WORD_BYTES]; // replacement text for a word with the $text attribute
+static const char *letterGroups[N_LETTER_GROUPS]; // .L## letter groups, lists of utf-8 strings
+static LANGUAGE_OPTIONS langopts;
+static int translator_name; // eg. L('e','n')
+static int word_vowel_count;    // number of vowels so far
+static int word_stressed_count; // number of vowels so far which could be stressed
+static int expect_verb;
 
 static void strncpy0(char *to, const char *from, int size) {
 	// strcpy with limit, ensures a zero terminator
@@ -1726,6 +1883,30 @@ static void SetLetterBits(int group, const char *string) {
 		letter_bits[c] |= bits;
 }
 
+static void SetLetterBitsRange(int group, int first, int last) {
+	int bits;
+	int ix;
+
+	bits = (1L << group);
+	for (ix = first; ix <= last; ix++)
+		letter_bits[ix] |= bits;
+}
+
+
+static void SetLetterVowel(int c) {
+	letter_bits[c] = (letter_bits[c] & 0x40) | 0x81; // keep value for group 6 (front vowels e,i,y)
+}
+
+static void ResetLetterBits(int groups) {
+	// Clear all the specified groups
+	unsigned int ix;
+	unsigned int mask;
+
+	mask = ~groups;
+	for (ix = 0; ix < sizeof(letter_bits); ix++)
+		letter_bits[ix] &= mask;
+}
+
 static int utf8_out(unsigned int c, char *buf) {
 	// write a UTF-16 character into a buffer as UTF-8
 	// returns the number of bytes written
@@ -1870,6 +2051,22 @@ static int utf8_nbytes(const char *buf) {
 	return 4;
 }
 
+
+static int IsDigit09(unsigned int c) {
+	if ((c >= '0') && (c <= '9'))
+		return 1;
+	return 0;
+}
+
+static int utf8_in_back(int *c, const char *buf) {
+	// Reads the unicode character which ends at buf, moving backwards over any continuation bytes
+	// Returns the number of UTF8 bytes used.
+
+	while ((*buf & 0xc0) == 0x80)
+		buf--;
+	return utf8_in2(c, buf);
+}
+
 static int TransposeAlphabet(char *text) {
 	// transpose cyrillic alphabet (for example) into ascii (single byte) character codes
 	// return: number of bytes, bit 6: 1=used compression
@@ -1879,6 +2076,7 @@ static int TransposeAlphabet(char *text) {
 	int max;
 	char *p = text;
 	char *p2;
+	bool all_alpha = true;
 	int bufix;
 	char buf[N_WORD_BYTES+1];
 	min = 0x60;
@@ -1891,34 +2089,43 @@ static int TransposeAlphabet(char *text) {
 				// get the code from the transpose map
 				if (transpose_map_latin[c - min] > 0)
 					buf[bufix++] = transpose_map_latin[c - min];
-				else break;
-			} else break;
+				else {
+					all_alpha = false;
+					break;
+				}
+			} else {
+				all_alpha = false;
+				break;
+			}
 		}
 	} while ((c != 0) && (bufix < N_WORD_BYTES));
 	buf[bufix] = 0;
 
-	// compress to 6 bits per character
-	int ix;
-	int acc = 0;
-	int bits = 0;
-
-	p = buf;
-	p2 = buf;
-	while ((c = *p++) != 0) {
-		acc = (acc << 6) + (c & 0x3f);
-		bits += 6;
-
-		if (bits >= 8) {
-			bits -= 8;
-			*p2++ = (char) (acc >> bits);
+	if (all_alpha) {
+		// compress to 6 bits per character
+		int ix;
+		int acc = 0;
+		int bits = 0;
+
+		p = buf;
+		p2 = buf;
+		while ((c = *p++) != 0) {
+			acc = (acc << 6) + (c & 0x3f);
+			bits += 6;
+
+			if (bits >= 8) {
+				bits -= 8;
+				*p2++ = (char) (acc >> bits);
+			}
 		}
+		if (bits > 0)
+			*p2++ = (char) (acc << (8-bits));
+		*p2 = 0;
+		ix = (int) (p2 - buf);
+		memcpy(text, buf, ix);
+		return ix | 0x40; // bit 6 indicates compressed characters
 	}
-	if (bits > 0)
-		*p2++ = (char) (acc << (8-bits));
-	*p2 = 0;
-	ix = (int) (p2 - buf);
-	memcpy(text, buf, ix);
-	return ix | 0x40; // bit 6 indicates compressed characters
+	return (int) strlen(text);
 }
 
 static int HashDictionary(const char *string) {
@@ -1935,13 +2142,18 @@ static int HashDictionary(const char *string) {
 	return (hash+chars) & 0x3ff; // a 10 bit hash code
 }
 
-static const char *LookupDict2(const char *word, const char *word2, char *phonetic, unsigned int *flags) {
+
+static const char *LookupDict2(const char *word, const char *word2, char *phonetic, unsigned int *flags, int end_flags) {
 	const char *p;
 	const char *next;
 	int hash;
 	int phoneme_len;
 	int wlen;
+	unsigned char flag;
 	unsigned int dictionary_flags;
+	unsigned int dictionary_flags2;
+	int condition_failed = 0;
+	int n_chars;
 	int no_phonemes;
 	const char *word_end;
 	char word_buf[N_WORD_BYTES+1];
@@ -1966,6 +2178,7 @@ static const char *LookupDict2(const char *word, const char *word2, char *phonet
 		word_end = word2;
 
 		dictionary_flags = 0;
+		dictionary_flags2 = 0;
 		no_phonemes = p[1] & 0x80;
 
 		p += ((p[1] & 0x3f) + 2);
@@ -1978,27 +2191,133 @@ static const char *LookupDict2(const char *word, const char *word2, char *phonet
 			strcpy(phonetic, p);
 			p += (phoneme_len + 1);
 		}
+
+		while (p < next) {
+			// examine the flags which follow the phoneme string
+			flag = *p++ & 0xff;
+			if (flag >= 100) {
+				// conditional rule
+				if (flag >= 132) {
+					// fail if this condition is set
+					if ((dict_condition & (1 << (flag-132))) != 0)
+						condition_failed = 1;
+				} else {
+					// allow only if this condition is set
+					if ((dict_condition & (1 << (flag-100))) == 0)
+						condition_failed = 1;
+				}
+			} else if (flag > 80) {
+				// flags 81 to 90  match more than one word
+				// This comes after the other flags
+				n_chars = (int) (next - p);
+				if (strncmp(word2, p, n_chars) != 0)
+					condition_failed = 1;
+				if (condition_failed) {
+					p = next;
+					break;
+				}
+				dictionary_flags |= FLAG_SKIPWORDS;
+				p = next;
+				word_end = word2 + n_chars;
+			} else if (flag > 64) {
+				// stressed syllable information, put in bits 0-3
+				dictionary_flags = (dictionary_flags & ~0xf) | (flag & 0xf);
+				if ((flag & 0xc) == 0xc)
+					dictionary_flags |= FLAG_STRESS_END;
+			} else if (flag >= 32)
+				dictionary_flags2 |= (1L << (flag-32));
+			else
+				dictionary_flags |= (1L << flag);
+		}
+
+		if (condition_failed) {
+			condition_failed = 0;
+			continue;
+		}
+
+		if ((end_flags & FLAG_SUFX) == 0) {
+			// no suffix has been removed
+			if (dictionary_flags2 & FLAG_STEM)
+				continue; // this word must have a suffix
+		}
+
+		if ((end_flags & SUFX_P) && (dictionary_flags2 & (FLAG_ONLY | FLAG_ONLY_S)))
+			continue; // $only or $onlys, don't match if a prefix has been removed
+
+		if (end_flags & FLAG_SUFX) {
+			// a suffix was removed from the word
+			if (dictionary_flags2 & FLAG_ONLY)
+				continue; // no match if any suffix
+
+			if ((dictionary_flags2 & FLAG_ONLY_S) && ((end_flags & FLAG_SUFX_S) == 0)) {
+				// only a 's' suffix allowed, but the suffix wasn't 's'
+				continue;
+			}
+		}
+
+		if (dictionary_flags2 & (FLAG_CAPITAL | FLAG_ALLCAPS))
+			continue; // the text is converted to lower case before translation
+
+		if (dictionary_flags & FLAG_NEEDS_DOT)
+			continue; // words are translated without any following punctuation
+
+		if ((dictionary_flags2 & FLAG_VERB) && !expect_verb)
+			continue; // don't use the 'verb' pronunciation unless we are expecting a verb
+
+		if (dictionary_flags2 & (FLAG_PAST | FLAG_NOUN))
+			continue; // no context for the 'past' or 'noun' pronunciations
+
 		if (flags != NULL) {
 			flags[0] = dictionary_flags | FLAG_FOUND_ATTRIBUTES;
+			flags[1] = dictionary_flags2;
 		}
 
 		if (phoneme_len == 0) {
 			return 0; // no phoneme translation found here, only flags. So use rules
 		}
+
+		if (flags != NULL)
+			flags[0] |= FLAG_FOUND; // this flag indicates word was found in dictionary
 		return word_end;
 	}
 	return 0;
 }
 
-static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags);
+static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags, int end_flags);
+static int TranslateWord(char *word_start, WORD_TAB *wtab, char *word_out);
 
 static int Lookup(const char *word, char *ph_out) {
 	unsigned int flags[2] = { 0 };
-	if (LookupDictList(&word, ph_out, flags) != 0)
+	const char *word1 = word;
+	char text[80];
+	int found;
+
+	found = LookupDictList(&word1, ph_out, flags, FLAG_ALLOW_TEXTMODE);
+	if (flags[0] & FLAG_TEXTMODE) {
+		// the word is replaced by other text, translate that instead
+		text[0] = 0;
+		text[1] = ' ';
+		text[2] = ' ';
+		strncpy0(text+3, word1, sizeof(text)-3);
+		flags[0] = TranslateWord(text+3, NULL, NULL);
+		strcpy(ph_out, word_phonemes);
+		return flags[0];
+	}
+	if (found != 0)
 		return flags[0];
 	return 0;
 }
 
+static unsigned int *LookupFlags(const char *word) {
+	char buf[100];
+	static unsigned int flags[2];
+	const char *word1 = word;
+
+	flags[0] = flags[1] = 0;
+	LookupDictList(&word1, buf, flags, 0);
+	return flags;
+}
+
 static int LetterGroupNo(const char *rule) {
 	int groupNo = *rule;
 	groupNo = groupNo - 'A'; // subtracting 'A' makes letter_group equal to number in .Lxx definition
@@ -2007,16 +2326,94 @@ static int LetterGroupNo(const char *rule) {
 	return groupNo;
 }
 
+
 static int IsLetter(int letter, int group) {
+	int letter2;
+
 	if (group > 7)
 		return 0;
+	if (letter_bits_offset > 0) {
+		if (((letter2 = (letter - letter_bits_offset)) > 0) && (letter2 < 0x100))
+			letter = letter2;
+		else
+			return 0;
+	} else if ((letter >= 0xc0) && (letter < N_REMOVE_ACCENT))
+		return letter_bits[remove_accent[letter-0xc0]] & (1L << group);
+
 	if ((letter >= 0) && (letter < 0x100))
 		return letter_bits[letter] & (1L << group);
 
 	return 0;
 }
 
-static void MatchRule(char *word[], int group_length, const char *rule, MatchRecord *match_out, int word_flags) {
+static int IsLetterGroup(const char *word, int group, int pre) {
+	// Match the word against a list of utf-8 strings from the .L## letter groups
+	// returns length of matching letter group or -1
+	// pre: match backwards from word (for a pre-rule), otherwise forwards
+
+	const char *p; // group counter
+	const char *w; // word counter
+	int len = 0;
+	int i;
+
+	p = letterGroups[group];
+	if (p == NULL)
+		return -1;
+
+	while (*p != RULE_GROUP_END) {
+		if (pre) {
+			len = (int) strlen(p);
+			w = word;
+			for (i = 0; i < len-1; i++) {
+				w--;
+				if (*w == 0)
+					return -1; // reached the start of the text
+			}
+		} else
+			w = word;
+
+		// If the current group matches the word
+		while ((*p == *w) && (*w != 0)) {
+			w++;
+			p++;
+		}
+		if (*p == 0) { // Matched the current group.
+			if (pre)
+				return len;
+			return (int) (w - word);
+		}
+
+		// No match, so skip the rest of this group.
+		while (*p++ != 0)
+			;
+	}
+	return -1;
+}
+
+static void DollarRule(char *word[], char *word_start, int consumed, int group_length, char *word_buf, unsigned int command, int *failed, int *add_points) {
+	// $list or $p_alt
+	// make a copy of the word up to the post-match characters
+	int ix = (int) (*word - word_start) + consumed + group_length + 1;
+	unsigned int *flags;
+
+	if (ix >= N_WORD_BYTES-1) {
+		*failed = 1;
+		return;
+	}
+	memcpy(word_buf, word_start-1, ix);
+	word_buf[ix] = ' ';
+	word_buf[ix+1] = 0;
+	flags = LookupFlags(&word_buf[1]);
+
+	if ((command == DOLLAR_LIST) && (flags[0] & FLAG_FOUND) && !(flags[1] & FLAG_ONLY))
+		*add_points = 23;
+	else if (flags[0] & (1 << (BITNUM_FLAG_ALT + (command & 0xf))))
+		*add_points = 23;
+	else
+		*failed = 1;
+}
+
+static void MatchRule(char *word[], char *word_start, int group_length, const char *rule, MatchRecord *match_out, int word_flags, unsigned int dict_flags) {
 	unsigned char rb;     // current instuction from rule
 	unsigned char letter; // current letter from input word, single byte
 	int letter_w;         // current letter, wide character
@@ -2027,8 +2424,13 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 	char *post_ptr;       // pointer to first character after group
 	char *p;
 	int match_type;       // left, right, or consume
+	int syllable_count;
+	int vowel;
 	int letter_group;
+	int lg_pts;
+	int n_bytes;
 	int add_points;
+	unsigned int command;
 	MatchRecord match;
 	MatchRecord best;
 
@@ -2036,6 +2438,7 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 
 	unsigned char condition_num;
 	const char *common_phonemes; // common to a group of entries
+	char word_buf[N_WORD_BYTES];
 	if (rule == NULL) {
 		match_out->points = 0;
 		(*word)++;
@@ -2048,6 +2451,7 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 	best.points = 0;
 	best.phonemes = "";
 	best.end_type = 0;
+	best.del_fwd = NULL;
 	// search through dictionary rules
 	while (rule[0] != RULE_GROUP_END) {
 		bool check_atstart = false;
@@ -2062,6 +2466,7 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 
 		match.points = 1;
 		match.end_type = 0;
+		match.del_fwd = NULL;
 		pre_ptr = *word;
 		post_ptr = *word + group_length;
 		while (!failed) {
@@ -2085,6 +2490,11 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 					rule--; // so we are still pointing at the 0
 					failed = 2; // matched OK
 					break;
+				case RULE_PRE_ATSTART: // pre rule with implied 'start of word'
+					check_atstart = true;
+					unpron_ignore = 0;
+					match_type = RULE_PRE;
+					break;
 				case RULE_PRE:
 					match_type = RULE_PRE;
 					if (word_flags & FLAG_UNPRON_TEST) {
@@ -2161,27 +2571,159 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 				case RULE_LETTERGP:
 					letter_group = LetterGroupNo(rule++);
 					if (IsLetter(letter_w, letter_group)) {
+						lg_pts = 20;
+						if (letter_group == LETTERGP_C)
+							lg_pts = 19; // fewer points for C, general consonant
+						add_points = (lg_pts-distance_right);
 						post_ptr += letter_xbytes;
 					} else
 						failed = 1;
 					break;
+				case RULE_LETTERGP2: // match against a list of utf-8 strings
+					letter_group = LetterGroupNo(rule++);
+					if ((n_bytes = IsLetterGroup(post_ptr-1, letter_group, 0)) > 0) {
+						add_points = (20-distance_right);
+						post_ptr += (n_bytes-1);
+					} else
+						failed = 1;
+					break;
 				case RULE_NOTVOWEL:
-					add_points = (20-distance_right);
-					post_ptr += letter_xbytes;
+					if (IsLetter(letter_w, 0) || ((letter_w == ' ') && (word_flags & FLAG_SUFFIX_VOWEL)))
+						failed = 1;
+					else {
+						add_points = (20-distance_right);
+						post_ptr += letter_xbytes;
+					}
 					break;
-				case RULE_DOUBLE:
-					if (letter_w == last_letter_w) {
+				case RULE_DIGIT:
+					if (IsDigit09(letter_w)) {
+						add_points = (20-distance_right);
+						post_ptr += letter_xbytes;
+					} else
+						failed = 1;
+					break;
+				case RULE_NONALPHA:
+					if (!iswalpha((wint_t) letter_w)) {
 						add_points = (21-distance_right);
 						post_ptr += letter_xbytes;
 					} else
 						failed = 1;
 					break;
+				case RULE_DOUBLE:
+					if (letter_w == last_letter_w)
+						add_points = (21-distance_right);
+					else
+						failed = 1;
+					break;
+				case RULE_DOLLAR:
+					post_ptr--;
+					command = *rule++;
+					if (command == DOLLAR_UNPR)
+						match.end_type = SUFX_UNPRON; // $unpron
+					else if (command == DOLLAR_NOPREFIX) { // $noprefix
+						if (word_flags & FLAG_PREFIX_REMOVED)
+							failed = 1; // a prefix has been removed
+						else
+							add_points = 1;
+					} else if ((command & 0xf0) == 0x10) {
+						// $w_alt
+						if (dict_flags & (1 << (BITNUM_FLAG_ALT + (command & 0xf))))
+							add_points = 23;
+						else
+							failed = 1;
+					} else if (((command & 0xf0) == 0x20) || (command == DOLLAR_LIST))
+						DollarRule(word, word_start, consumed, group_length, word_buf, command, &failed, &add_points);
+					break;
 				case '-':
-					failed = 1;
+					if ((letter == '-') || ((letter == ' ') && (word_flags & FLAG_HYPHEN_AFTER)))
+						add_points = (22-distance_right); // one point more than match against space
+					else
+						failed = 1;
 					break;
-				case RULE_NO_SUFFIX:
+				case RULE_SYLLABLE:
+				{
+					// more than specified number of vowel letters to the right
+					int vowel_count = 0;
+
+					p = post_ptr + letter_xbytes;
+					syllable_count = 1;
+					while (*rule == RULE_SYLLABLE) {
+						rule++;
+						syllable_count += 1; // number of syllables to match
+					}
+					vowel = 0;
+					while ((letter_w != RULE_SPACE) && (letter_w != 0)) {
+						if ((vowel == 0) && IsLetter(letter_w, LETTERGP_VOWEL2)) {
+							// this is counting vowels which are separated by non-vowel letters
+							vowel_count++;
+						}
+						vowel = IsLetter(letter_w, LETTERGP_VOWEL2);
+						p += utf8_in2(&letter_w, p);
+					}
+					if (syllable_count <= vowel_count)
+						add_points = (18+syllable_count-distance_right);
+					else
+						failed = 1;
+				}
+					break;
+				case RULE_NOVOWELS:
+					p = post_ptr + letter_xbytes;
+					while ((letter_w != RULE_SPACE) && (letter_w != 0)) {
+						if (IsLetter(letter_w, LETTERGP_VOWEL2)) {
+							failed = 1;
+							break;
+						}
+						p += utf8_in2(&letter_w, p);
+					}
+					if (!failed)
+						add_points = (19-distance_right);
+					break;
+				case RULE_SKIPCHARS:
+				{
+					// Jx  means 'skip characters until x', where 'x' may be '_' for 'end of word'
+					char *p2;
+					int rule_w; // skip characters until this
+
+					p = post_ptr + letter_xbytes;
+					p2 = p;
+					utf8_in2(&rule_w, rule);
+					while ((letter_w != rule_w) && (letter_w != RULE_SPACE) && (letter_w != 0)) {
+						p2 = p;
+						p += utf8_in2(&letter_w, p);
+					}
+					if (letter_w == rule_w)
+						post_ptr = p2;
+				}
+					break;
+				case RULE_INC_SCORE:
+					post_ptr--;
+					add_points = 20; // force an increase in points
+					break;
+				case RULE_DEC_SCORE:
 					post_ptr--;
-					add_points = 1;
+					add_points = -20; // force an decrease in points
+					break;
+				case RULE_DEL_FWD:
+					// find the next 'e' in the word and replace by 'E'
+					for (p = *word + group_length; p < post_ptr; p++) {
+						if (*p == 'e') {
+							match.del_fwd = p;
+							break;
+						}
+					}
+					break;
+				case RULE_ENDING:
+					// next 3 bytes are a (non-zero) ending type. 2 bytes of flags + suffix length
+					match.end_type = ((unsigned char)rule[0] << 16) + (((unsigned char)rule[1] & 0x7f) << 8) + ((unsigned char)rule[2] & 0x7f);
+					rule += 3;
+					break;
+				case RULE_NO_SUFFIX:
+					if (word_flags & FLAG_SUFFIX_REMOVED)
+						failed = 1; // a suffix has been removed
+					else {
+						post_ptr--;
+						add_points = 1;
+					}
 					break;
 				default:
 					if (letter == rb) {
@@ -2208,7 +2750,7 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 				}
 				utf8_in2(&last_letter_w, pre_ptr);
 				pre_ptr--;
-				letter_xbytes = utf8_in2(&letter_w, pre_ptr)-1;
+				letter_xbytes = utf8_in_back(&letter_w, pre_ptr)-1;
 				letter = *pre_ptr;
 
 				switch (rb)
@@ -2216,10 +2758,22 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 				case RULE_LETTERGP:
 					letter_group = LetterGroupNo(rule++);
 					if (IsLetter(letter_w, letter_group)) {
+						lg_pts = 20;
+						if (letter_group == LETTERGP_C)
+							lg_pts = 19; // fewer points for C, general consonant
+						add_points = (lg_pts-distance_left);
 						pre_ptr -= letter_xbytes;
 					} else
 						failed = 1;
 					break;
+				case RULE_LETTERGP2: // match against a list of utf-8 strings
+					letter_group = LetterGroupNo(rule++);
+					if ((n_bytes = IsLetterGroup(pre_ptr, letter_group, 1)) > 0) {
+						add_points = (20-distance_right);
+						pre_ptr -= (n_bytes-1);
+					} else
+						failed = 1;
+					break;
 				case RULE_NOTVOWEL:
 					if (!IsLetter(letter_w, 0)) {
 						add_points = (20-distance_left);
@@ -2228,12 +2782,73 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 						failed = 1;
 					break;
 				case RULE_DOUBLE:
-					if (letter_w == last_letter_w) {
+					if (letter_w == last_letter_w)
 						add_points = (21-distance_left);
+					else
+						failed = 1;
+					break;
+				case RULE_DIGIT:
+					if (IsDigit09(letter_w)) {
+						add_points = (21-distance_left);
+						pre_ptr -= letter_xbytes;
+					} else
+						failed = 1;
+					break;
+				case RULE_NONALPHA:
+					if (!iswalpha((wint_t) letter_w)) {
+						add_points = (21-distance_right);
 						pre_ptr -= letter_xbytes;
 					} else
 						failed = 1;
 					break;
+				case RULE_DOLLAR:
+					pre_ptr++;
+					command = *rule++;
+					if ((command == DOLLAR_LIST) || ((command & 0xf0) == 0x20))
+						DollarRule(word, word_start, consumed, group_length, word_buf, command, &failed, &add_points);
+					break;
+				case RULE_SYLLABLE:
+					// more than specified number of vowels to the left
+					syllable_count = 1;
+					while (*rule == RULE_SYLLABLE) {
+						rule++;
+						syllable_count++; // number of syllables to match
+					}
+					if (syllable_count <= word_vowel_count)
+						add_points = (18+syllable_count-distance_left);
+					else
+						failed = 1;
+					break;
+				case RULE_STRESSED:
+					if (word_stressed_count > 0)
+						add_points = 19;
+					else
+						failed = 1;
+					break;
+				case RULE_NOVOWELS:
+					p = pre_ptr - letter_xbytes;
+					while ((letter_w != RULE_SPACE) && (letter_w != 0)) {
+						if (IsLetter(letter_w, LETTERGP_VOWEL2)) {
+							failed = 1;
+							break;
+						}
+						p -= utf8_in_back(&letter_w, p-1);
+					}
+					if (!failed)
+						add_points = 3;
+					break;
+				case RULE_IFVERB:
+					if (expect_verb)
+						add_points = 1;
+					else
+						failed = 1;
+					break;
+				case RULE_CAPITAL:
+					if (word_flags & FLAG_FIRST_UPPER)
+						add_points = RULE_CAPITAL;
+					else
+						failed = 1;
+					break;
 				case '.':
 					// dot in pre- section, match on any dot before this point in the word
 					for (p = pre_ptr; *p != ' '; p--) {
@@ -2246,11 +2861,16 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 						failed = 1;
 					break;
 				case '-':
-					failed = 1;
+					if ((letter == '-') || ((letter == ' ') && (word_flags & FLAG_HYPHEN)))
+						add_points = (22-distance_right); // one point more than match against space
+					else
+						failed = 1;
 					break;
 				default:
 					if (letter == rb) {
-						if ((letter & 0xc0) != 0x80) {
+						if (letter == RULE_SPACE)
+							add_points = 4;
+						else if ((letter & 0xc0) != 0x80) {
 							// not for non-initial UTF-8 bytes
 							add_points = (21-distance_left);
 						}
@@ -2296,6 +2916,9 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 }
 
 static void AppendPhonemes(char *string, int size, const char *ph) {
+	// Add new phoneme string "ph" to "string"
+	// Keeps count of the number of vowel phonemes in the word, and whether these
+	// can be stressed syllables.  These values can be used in translation rules
 	const char *p;
 	unsigned char c;
 	int length;
@@ -2315,8 +2938,10 @@ static void AppendPhonemes(char *string, int size, const char *ph) {
 			if (phoneme_tab[c].type == phVOWEL) {
 				if (((phoneme_tab[c].phflags & phUNSTRESSED) == 0) &&
 				    (unstress_mark == false)) {
+					word_stressed_count++;
 				}
 				unstress_mark = false;
+				word_vowel_count++;
 			}
 		}
 	}
@@ -2325,7 +2950,9 @@ static void AppendPhonemes(char *string, int size, const char *ph) {
 		strcat(string, ph);
 }
 
-static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_phonemes, int word_flags) {
+static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1);
+
+static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_phonemes, int word_flags, unsigned int *dict_flags) {
 	/* Translate a word bounded by space characters
 	   Append the result to 'phonemes' and any standard prefix/suffix in 'end_phonemes' */
 	unsigned char c, c2;
@@ -2338,12 +2965,18 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 	int letter;
 	int any_alpha = 0;
 	int ix;
+	unsigned int digit_count = 0;
 	char *p;
+	unsigned int dict_flags0 = 0;
 	MatchRecord match1 = { 0 };
 	MatchRecord match2 = { 0 };
+	char ph_buf[40];
 	char word_copy[N_WORD_BYTES];
+	static const char str_pause[2] = { phonPAUSE_NOLINK, 0 };
 	if (data_dictrules == NULL)
 		return 0;
+	if (dict_flags != NULL)
+		dict_flags0 = dict_flags[0];
 	for (ix = 0; ix < (N_WORD_BYTES-1);) {
 		c = p_start[ix];
 		word_copy[ix++] = c;
@@ -2352,6 +2985,8 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 	}
 	word_copy[ix] = 0;
 	p = p_start;
+	word_vowel_count = 0;
+	word_stressed_count = 0;
 	if (end_phonemes != NULL)
 		end_phonemes[0] = 0;
 
@@ -2360,7 +2995,30 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 		if (IsAlpha(wc))
 			any_alpha++;
 		int n = groups2_count[c];
+		if (IsDigit09(wc)) {
+			// lookup the number in *_list not *_rules
+			char string[8];
+			char buf[40];
+			string[0] = '_';
+			memcpy(&string[1], p, wc_bytes);
+			string[1+wc_bytes] = 0;
+			Lookup(string, buf);
+			if (++digit_count >= 2) {
+				strcat(buf, str_pause);
+				digit_count = 0;
+			}
+			AppendPhonemes(phonemes, ph_size, buf);
+			p += wc_bytes;
+			continue;
+		}
+		digit_count = 0;
 		found = 0;
+		if (((ix = wc - letter_bits_offset) >= 0) && (ix < 128)) {
+			if (groups3[ix] != NULL) {
+				MatchRule(&p, p_start, wc_bytes, groups3[ix], &match1, word_flags, dict_flags0);
+				found = 1;
+			}
+		}
 		if (!found && (n > 0)) {
 			// there are some 2 byte chains for this initial letter
 			c2 = p[1];
@@ -2372,12 +3030,12 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 					found = 1;
 
 					p2 = p;
-					MatchRule(&p2, 2, groups2[g], &match2, word_flags);
+					MatchRule(&p2, p_start, 2, groups2[g], &match2, word_flags, dict_flags0);
 					if (match2.points > 0)
 						match2.points += 35; // to acount for 2 letters matching
 
 					// now see whether single letter chain gives a better match ?
-					MatchRule(&p, 1, groups1[c], &match1, word_flags);
+					MatchRule(&p, p_start, 1, groups1[c], &match1, word_flags, dict_flags0);
 
 					if (match2.points >= match1.points) {
 						// use match from the 2-letter group
@@ -2391,13 +3049,30 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 		if (!found) {
 			// alphabetic, single letter chain
 			if (groups1[c] != NULL)
-				MatchRule(&p, 1, groups1[c], &match1, word_flags);
+				MatchRule(&p, p_start, 1, groups1[c], &match1, word_flags, dict_flags0);
 			else {
 				// no group for this letter, use default group
-				MatchRule(&p, 0, groups1[0], &match1, word_flags);
+				MatchRule(&p, p_start, 0, groups1[0], &match1, word_flags, dict_flags0);
 
 				if (match1.points == 0) {
 					n = utf8_in2(&letter, p-1)-1;
+
+					// no match, try removing the accent and re-translating the word
+					if ((letter >= 0xc0) && (letter < N_REMOVE_ACCENT) && ((ix = remove_accent[letter-0xc0]) != 0)) {
+						// within range of the remove_accent table
+						if ((p[-2] != ' ') || (p[n] != ' ')) {
+							// not the only letter in the word
+							p[-1] = (char) ix;
+							while ((p[0] = p[n]) != ' ')  p++;
+							while (n-- > 0) *p++ = ' '; // replacement character must be no longer than original
+
+							phonemes[0] = 0; // delete any phonemes which have been produced so far
+							p = p_start;
+							word_vowel_count = 0;
+							word_stressed_count = 0;
+							continue; // start again at the beginning of the word
+						}
+					}
 				}
 			}
 
@@ -2410,6 +3085,12 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 						phonemes[0] = 0;
 						break;
 					}
+				} else {
+					LookupLetter(wc, -1, ph_buf);
+					if (ph_buf[0]) {
+						match1.phonemes = ph_buf;
+						match1.points = 1;
+					}
 				}
 				p += (wc_bytes-1);
 			}
@@ -2421,11 +3102,25 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 		if (match1.points > 0) {
 			if (word_flags & FLAG_UNPRON_TEST)
 				return match1.end_type | 1;
+
+			match1.end_type &= ~SUFX_UNPRON;
+
 			if ((match1.end_type != 0) && (end_phonemes != NULL)) {
-				strcpy(end_phonemes, match1.phonemes);
-				memcpy(p_start, word_copy, strlen(word_copy));
-				return match1.end_type;
+				// a standard ending has been found, re-translate the word without it
+				if ((match1.end_type & SUFX_P) && (word_flags & FLAG_NO_PREFIX)) {
+					// ignore the match on a prefix
+				} else {
+					if ((match1.end_type & SUFX_P) && ((match1.end_type & 0x7f) == 0)) {
+						// no prefix length specified
+						match1.end_type |= (int) (p - p_start);
+					}
+					strcpy(end_phonemes, match1.phonemes);
+					memcpy(p_start, word_copy, strlen(word_copy));
+					return match1.end_type;
+				}
 			}
+			if (match1.del_fwd != NULL)
+				*match1.del_fwd = REPLACED_E;
 			AppendPhonemes(phonemes, ph_size, match1.phonemes);
 		}
 	}
@@ -2435,6 +3130,117 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 	return 0;
 }
 
+static int RemoveEnding(char *word, int end_type, char *word_copy) {
+	/* Removes a standard suffix from a word, once it has been indicated by the dictionary rules.
+	   end_type: bits 0-6  number of letters
+	             bits 8-14  suffix flags
+
+	    word_copy: make a copy of the original word
+	    This routine is language specific.  In English it deals with reversing y->i and e-dropping
+	    that were done when the suffix was added to the original word.
+	 */
+	int i;
+	char *word_end;
+	int len_ending;
+	int end_flags;
+	const char *p;
+	int len;
+	char ending[50] = { 0 };
+
+	// these lists are language specific, but are only relevant if the 'e' suffix flag is used
+	static const char *add_e_exceptions[] = {
+		"ion", NULL
+	};
+
+	static const char *add_e_additions[] = {
+		"c", "rs", "ir", "ur", "ath", "ns", "u",
+		"spong", // sponge
+		"rang", // strange
+		"larg", // large
+		NULL
+	};
+
+	for (word_end = word; *word_end != ' '; word_end++) {
+		// replace discarded 'e's
+		if (*word_end == REPLACED_E)
+			*word_end = 'e';
+	}
+	i = (int) (word_end - word);
+
+	if (word_copy != NULL) {
+		memcpy(word_copy, word, i);
+		word_copy[i] = 0;
+	}
+
+	// look for multibyte characters to increase the number of bytes to remove
+	for (len_ending = i = (end_type & 0x3f); i > 0; i--) { // num.of characters of the suffix
+		word_end--;
+		while ((*word_end & 0xc0) == 0x80) {
+			word_end--; // for multibyte characters
+			len_ending++;
+		}
+	}
+
+	// remove bytes from the end of the word and replace them by spaces
+	for (i = 0; (i < len_ending) && (i < (int)sizeof(ending)-1); i++) {
+		ending[i] = word_end[i];
+		word_end[i] = ' ';
+	}
+	ending[i] = 0;
+	word_end--; // now pointing at last character of stem
+
+	end_flags = (end_type & 0xfff0) | FLAG_SUFX;
+
+	/* add an 'e' to the stem if appropriate,
+	    if  stem ends in vowel+consonant
+	    or  stem ends in 'c'  (add 'e' to soften it) */
+
+	if (end_type & SUFX_I) {
+		if (word_end[0] == 'i')
+			word_end[0] = 'y';
+	}
+
+	if (end_type & SUFX_E) {
+		if (translator_name == L('e', 'n')) {
+			// add 'e' to end of stem
+			if (IsLetter(word_end[-1], LETTERGP_VOWEL2) && IsLetter(word_end[0], 1)) {
+				// vowel(incl.'y') + hard.consonant
+
+				for (i = 0; (p = add_e_exceptions[i]) != NULL; i++) {
+					len = (int) strlen(p);
+					if (memcmp(p, &word_end[1-len], len) == 0)
+						break;
+				}
+				if (p == NULL)
+					end_flags |= FLAG_SUFX_E_ADDED; // no exception found
+			} else {
+				for (i = 0; (p = add_e_additions[i]) != NULL; i++) {
+					len = (int) strlen(p);
+					if (memcmp(p, &word_end[1-len], len) == 0) {
+						end_flags |= FLAG_SUFX_E_ADDED;
+						break;
+					}
+				}
+			}
+		} else if (langopts.suffix_add_e != 0)
+			end_flags |= FLAG_SUFX_E_ADDED;
+
+		if (end_flags & FLAG_SUFX_E_ADDED)
+			utf8_out(langopts.suffix_add_e, &word_end[1]);
+	}
+
+	if ((end_type & SUFX_V) && (expect_verb == 0))
+		expect_verb = 1; // this suffix indicates the verb pronunciation
+
+	if ((strcmp(ending, "s") == 0) || (strcmp(ending, "es") == 0))
+		end_flags |= FLAG_SUFX_S;
+
+	if (ending[0] == '\'')
+		end_flags &= ~FLAG_SUFX; // don't consider 's as an added suffix
+
+	return end_flags;
+}
+
 static int GetVowelStress(unsigned char *phonemes, signed char *vowel_stress, int *vowel_count, int *stressed_syllable, int control) {
 	// control = 1, set stress to 1 for forced unstressed vowels
 	unsigned char phcode;
@@ -2443,6 +3249,7 @@ static int GetVowelStress(unsigned char *phonemes, signed char *vowel_stress, in
 	int count = 1;
 	int max_stress = -1;
 	int ix;
+	int j;
 	int stress = -1;
 	int primary_posn = 0;
 
@@ -2454,11 +3261,35 @@ static int GetVowelStress(unsigned char *phonemes, signed char *vowel_stress, in
 		if ((ph->type == phSTRESS) && (ph->program == 0)) {
 			// stress marker, use this for the following vowel
 
-			if ((ph->std_length < 4) || (*stressed_syllable == 0)) {
-				stress = ph->std_length;
+			if (phcode == phonSTRESS_PREV) {
+				// primary stress on preceding vowel
+				j = count - 1;
+				while ((j > 0) && (*stressed_syllable == 0) && (vowel_stress[j] < STRESS_IS_PRIMARY)) {
+					if ((vowel_stress[j] != STRESS_IS_DIMINISHED) && (vowel_stress[j] != STRESS_IS_UNSTRESSED)) {
+						// don't promote a phoneme which must be unstressed
+						vowel_stress[j] = STRESS_IS_PRIMARY;
+
+						if (max_stress < STRESS_IS_PRIMARY) {
+							max_stress = STRESS_IS_PRIMARY;
+							primary_posn = j;
+						}
 
-				if (stress > max_stress)
-					max_stress = stress;
+						// reduce any preceding primary stress markers
+						for (ix = 1; ix < j; ix++) {
+							if (vowel_stress[ix] == STRESS_IS_PRIMARY)
+								vowel_stress[ix] = STRESS_IS_SECONDARY;
+						}
+						break;
+					}
+					j--;
+				}
+			} else {
+				if ((ph->std_length < 4) || (*stressed_syllable == 0)) {
+					stress = ph->std_length;
+
+					if (stress > max_stress)
+						max_stress = stress;
+				}
 			}
 			continue;
 		}
@@ -2501,6 +3332,13 @@ static int GetVowelStress(unsigned char *phonemes, signed char *vowel_stress, in
 	if (max_stress == STRESS_IS_PRIORITY) {
 		// priority stress, replaces any other primary stress marker
 		for (ix = 1; ix < count; ix++) {
+			if (vowel_stress[ix] == STRESS_IS_PRIMARY) {
+				if (langopts.stress_flags & S_PRIORITY_STRESS)
+					vowel_stress[ix] = STRESS_IS_UNSTRESSED;
+				else
+					vowel_stress[ix] = STRESS_IS_SECONDARY;
+			}
+
 			if (vowel_stress[ix] == STRESS_IS_PRIORITY) {
 				vowel_stress[ix] = STRESS_IS_PRIMARY;
 				primary_posn = ix;
@@ -2520,7 +3358,6 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 	PHONEME_TAB *ph;
 	int stress;
 	int max_stress;
-	int max_stress_input; // any stress specified in the input?
 	int vowel_count; // num of vowels + 1
 	int ix;
 	int v;
@@ -2528,14 +3365,25 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 	int stressed_syllable; // position of stressed syllable
 	int max_stress_posn;
 	char *max_output;
+	int final_ph;
+	int final_ph2;
+	unsigned int mnem;
+	int opt_length;
+	int stressflags;
 	int dflags = 0;
 	int first_primary;
+	bool unstressed_word = false;
+	bool done = false;
 	signed char vowel_stress[N_WORD_PHONEMES/2];
 	char syllable_weight[N_WORD_PHONEMES/2];
 	char vowel_length[N_WORD_PHONEMES/2];
 	unsigned char phonetic[N_WORD_PHONEMES];
+
+	static const char consonant_types[16] = { 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0 };
+
 	memset(syllable_weight, 0, sizeof(syllable_weight));
 	memset(vowel_length, 0, sizeof(vowel_length));
+	stressflags = langopts.stress_flags;
 	if (dictionary_flags != NULL)
 		dflags = dictionary_flags[0];
 
@@ -2546,14 +3394,19 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 			break;
 	}
 	if (ix == 0) return;
+	final_ph = phonetic[ix-1];
+	final_ph2 = phonetic[(ix > 1) ? ix-2 : ix-1];
 	max_output = output + (N_WORD_PHONEMES-3); // check for overrun
+
+	// any stress position marked in the xx_list dictionary ?
 	stressed_syllable = dflags & 0x7;
 	if (dflags & 0x8) {
 		// this indicates a word without a primary stress
 		stressed_syllable = dflags & 0x3;
+		unstressed_word = true;
 	}
 
-	max_stress = max_stress_input = GetVowelStress(phonetic, vowel_stress, &vowel_count, &stressed_syllable, 1);
+	max_stress = GetVowelStress(phonetic, vowel_stress, &vowel_count, &stressed_syllable, 1);
 	if ((max_stress < 0) && dictionary_flags)
 		max_stress = STRESS_IS_DIMINISHED;
 
@@ -2566,35 +3419,218 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 
 			if (phoneme_tab[p[1]].code == phonLENGTHEN)
 				lengthened = true;
+
+			if (lengthened || (phoneme_tab[p[0]].phflags & phLONG)) {
+				// long vowel, increase syllable weight
+				weight++;
+			}
 			vowel_length[ix] = (char) weight;
 
 			if (lengthened) p++; // advance over phonLENGTHEN
+
+			if (consonant_types[phoneme_tab[p[1]].type] && ((phoneme_tab[p[2]].type != phVOWEL) || (phoneme_tab[p[1]].phflags & phLONG))) {
+				// followed by two consonants, a long consonant, or consonant and end-of-word
+				weight++;
+			}
 			syllable_weight[ix] = (char) weight;
 			ix++;
 		}
 	}
-	// now guess the complete stress pattern
-	if (max_stress < STRESS_IS_PRIMARY)
-		stress = STRESS_IS_PRIMARY; // no primary stress marked, use for 1st syllable
-	else
-		stress = STRESS_IS_SECONDARY;
-	first_primary = 0;
-	for (v = 1; v < vowel_count; v++) {
-		if (vowel_stress[v] < STRESS_IS_DIMINISHED) {
-			if ((vowel_stress[v-1] <= STRESS_IS_UNSTRESSED) && ((vowel_stress[v+1] <= STRESS_IS_UNSTRESSED) || ((stress == STRESS_IS_PRIMARY) && (vowel_stress[v+1] <= STRESS_IS_NOT_STRESSED)))) {
-				// trochaic: give stress to vowel surrounded by unstressed vowels
-				// should start with secondary stress on the first syllable, or should it count back from
-				// the primary stress and put secondary stress on alternate syllables?
-				vowel_stress[v] = (char)stress;
-				stress = STRESS_IS_SECONDARY; // use secondary stress for remaining syllables
-			}
-		}
 
-		if (vowel_stress[v] >= STRESS_IS_PRIMARY) {
-			if (first_primary == 0)
+	switch (langopts.stress_rule)
+	{
+	case 8:
+		// stress on first syllable, unless it is a light syllable followed by a heavy syllable
+		if ((syllable_weight[1] > 0) || (syllable_weight[2] == 0))
+			break;
+		// fallthrough
+	case 1:
+		// stress on second syllable
+		if ((stressed_syllable == 0) && (vowel_count > 2)) {
+			stressed_syllable = 2;
+			if (max_stress == STRESS_IS_DIMINISHED)
+				vowel_stress[stressed_syllable] = STRESS_IS_PRIMARY;
+			max_stress = STRESS_IS_PRIMARY;
+		}
+		break;
+	case 10: // penultimate, but final if only 1 or 2 syllables
+		if (stressed_syllable == 0) {
+			if (vowel_count < 4) {
+				vowel_stress[vowel_count - 1] = STRESS_IS_PRIMARY;
+				max_stress = STRESS_IS_PRIMARY;
+				break;
+			}
+		}
+		// fallthrough
+	case 2:
+		// a language with stress on penultimate vowel
+		if (stressed_syllable == 0) {
+			// no explicit stress - stress the penultimate vowel
+			max_stress = STRESS_IS_PRIMARY;
+
+			if (vowel_count > 2) {
+				stressed_syllable = vowel_count - 2;
+
+				if (stressflags & S_FINAL_SPANISH) {
+					// LANG=Spanish, stress on last vowel if the word ends in a consonant other than 'n' or 's'
+					if (phoneme_tab[final_ph].type != phVOWEL) {
+						mnem = phoneme_tab[final_ph].mnemonic;
+
+						if ((mnem == 's') && (phoneme_tab[final_ph2].type == phNASAL)) {
+							// -ns  stress remains on penultimate syllable
+						} else if (((phoneme_tab[final_ph].type != phNASAL) && (mnem != 's')) || (phoneme_tab[final_ph2].type != phVOWEL))
+							stressed_syllable = vowel_count - 1;
+					}
+				}
+
+				if (stressflags & S_FINAL_LONG) {
+					// stress on last syllable if it has a long vowel, but previous syllable has a short vowel
+					if (vowel_length[vowel_count - 1] > vowel_length[vowel_count - 2])
+						stressed_syllable = vowel_count - 1;
+				}
+
+				if ((vowel_stress[stressed_syllable] == STRESS_IS_DIMINISHED) || (vowel_stress[stressed_syllable] == STRESS_IS_UNSTRESSED)) {
+					// but this vowel is explicitly marked as unstressed
+					if (stressed_syllable > 1)
+						stressed_syllable--;
+					else
+						stressed_syllable++;
+				}
+			} else
+				stressed_syllable = 1;
+
+			// only set the stress if it's not already marked explicitly
+			if (vowel_stress[stressed_syllable] < 0) {
+				// don't stress if next and prev syllables are stressed
+				if ((vowel_stress[stressed_syllable-1] < STRESS_IS_PRIMARY) || (vowel_stress[stressed_syllable+1] < STRESS_IS_PRIMARY))
+					vowel_stress[stressed_syllable] = (char)max_stress;
+			}
+		}
+		break;
+	case 3:
+		// stress on last vowel
+		if (stressed_syllable == 0) {
+			// no explicit stress - stress the final vowel
+			stressed_syllable = vowel_count - 1;
+
+			while (stressed_syllable > 0) {
+				// find the last vowel which is not unstressed
+				if (vowel_stress[stressed_syllable] < STRESS_IS_DIMINISHED) {
+					vowel_stress[stressed_syllable] = STRESS_IS_PRIMARY;
+					break;
+				} else
+					stressed_syllable--;
+			}
+			max_stress = STRESS_IS_PRIMARY;
+		}
+		break;
+	case 4: // stress on antipenultimate vowel
+		if (stressed_syllable == 0) {
+			stressed_syllable = vowel_count - 3;
+			if (stressed_syllable < 1)
+				stressed_syllable = 1;
+
+			if (max_stress == STRESS_IS_DIMINISHED)
+				vowel_stress[stressed_syllable] = STRESS_IS_PRIMARY;
+			max_stress = STRESS_IS_PRIMARY;
+		}
+		break;
+	case 6: // LANG=hi stress on the last heaviest syllable
+		if (stressed_syllable == 0) {
+			int wt;
+			int max_weight = -1;
+
+			// find the heaviest syllable, excluding the final syllable
+			for (ix = 1; ix < (vowel_count-1); ix++) {
+				if (vowel_stress[ix] < STRESS_IS_DIMINISHED) {
+					if ((wt = syllable_weight[ix]) >= max_weight) {
+						max_weight = wt;
+						stressed_syllable = ix;
+					}
+				}
+			}
+
+			if ((syllable_weight[vowel_count-1] == 2) && (max_weight < 2)) {
+				// the only double=heavy syllable is the final syllable, so stress this
+				stressed_syllable = vowel_count-1;
+			} else if (max_weight <= 0) {
+				// all syllables, exclusing the last, are light. Stress the first syllable
+				stressed_syllable = 1;
+			}
+
+			vowel_stress[stressed_syllable] = STRESS_IS_PRIMARY;
+			max_stress = STRESS_IS_PRIMARY;
+		}
+		break;
+	}
+
+	// now guess the complete stress pattern
+	if (max_stress < STRESS_IS_PRIMARY)
+		stress = STRESS_IS_PRIMARY; // no primary stress marked, use for 1st syllable
+	else
+		stress = STRESS_IS_SECONDARY;
+
+	if (unstressed_word == false) {
+		if ((stressflags & S_2_SYL_2) && (vowel_count == 3)) {
+			// Two syllable word, if one syllable has primary stress, then give the other secondary stress
+			if (vowel_stress[1] == STRESS_IS_PRIMARY)
+				vowel_stress[2] = STRESS_IS_SECONDARY;
+			if (vowel_stress[2] == STRESS_IS_PRIMARY)
+				vowel_stress[1] = STRESS_IS_SECONDARY;
+		}
+
+		if ((stressflags & S_INITIAL_2) && (vowel_stress[1] < STRESS_IS_DIMINISHED)) {
+			// If there is only one syllable before the primary stress, give it a secondary stress
+			if ((vowel_count > 3) && (vowel_stress[2] >= STRESS_IS_PRIMARY))
+				vowel_stress[1] = STRESS_IS_SECONDARY;
+		}
+	}
+
+	first_primary = 0;
+	for (v = 1; v < vowel_count; v++) {
+		if (vowel_stress[v] < STRESS_IS_DIMINISHED) {
+			if ((stressflags & S_FINAL_NO_2) && (stress < STRESS_IS_PRIMARY) && (v == vowel_count-1)) {
+				// flag: don't give secondary stress to final vowel
+			} else if ((stressflags & 0x8000) && (done == false)) {
+				vowel_stress[v] = (char)stress;
+				done = true;
+				stress = STRESS_IS_SECONDARY; // use secondary stress for remaining syllables
+			} else if ((vowel_stress[v-1] <= STRESS_IS_UNSTRESSED) && ((vowel_stress[v+1] <= STRESS_IS_UNSTRESSED) || ((stress == STRESS_IS_PRIMARY) && (vowel_stress[v+1] <= STRESS_IS_NOT_STRESSED)))) {
+				// trochaic: give stress to vowel surrounded by unstressed vowels
+
+				if ((stress == STRESS_IS_SECONDARY) && (stressflags & S_NO_AUTO_2))
+					continue; // don't use secondary stress
+
+				if ((v > 1) && (stressflags & S_2_TO_HEAVY) && (syllable_weight[v] == 0) && (syllable_weight[v+1] > 0)) {
+					// don't put secondary stress on a light syllable which is followed by a heavy syllable
+					continue;
+				}
+
+				// should start with secondary stress on the first syllable, or should it count back from
+				// the primary stress and put secondary stress on alternate syllables?
+				vowel_stress[v] = (char)stress;
+				done = true;
+				stress = STRESS_IS_SECONDARY; // use secondary stress for remaining syllables
+			}
+		}
+
+		if (vowel_stress[v] >= STRESS_IS_PRIMARY) {
+			if (first_primary == 0)
 				first_primary = v;
+			else if (stressflags & S_FIRST_PRIMARY) {
+				// reduce primary stresses after the first to secondary
+				vowel_stress[v] = STRESS_IS_SECONDARY;
+			}
 		}
 	}
+
+	if ((unstressed_word) && (tonic < 0)) {
+		if (vowel_count <= 2)
+			tonic = langopts.unstressed_wd1; // monosyllable - unstressed
+		else
+			tonic = langopts.unstressed_wd2; // more than one syllable, used secondary stress as the main stress
+	}
+
 	max_stress = STRESS_IS_DIMINISHED;
 	max_stress_posn = 0;
 	for (v = 1; v < vowel_count; v++) {
@@ -2616,18 +3652,31 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 	// produce output phoneme string
 	p = phonetic;
 	v = 1;
-	p = phonetic;
 	/* Note: v progression has to strictly follow the vowel_stress production in GetVowelStress */
 	while (((phcode = *p++) != 0) && (output < max_output)) {
 		if ((ph = &phoneme_tab[phcode]) == NULL)
 			continue;
 
 		if (((ph->type == phVOWEL) && !(ph->phflags & phNONSYLLABIC)) || (*p == phonSYLLABIC)) {
+			// a vowel, or a consonant followed by a syllabic consonant marker
 			v_stress = vowel_stress[v];
+
 			if (v_stress <= STRESS_IS_UNSTRESSED) {
-				if ((v == (vowel_count-2)) && (vowel_stress[vowel_count-1] <= STRESS_IS_UNSTRESSED)) {
+				if ((v > 1) && (max_stress >= 2) && (stressflags & S_FINAL_DIM) && (v == (vowel_count-1))) {
+					// option: mark unstressed final syllable as diminished
+					v_stress = STRESS_IS_DIMINISHED;
+				} else if ((stressflags & S_NO_DIM) || (v == 1) || (v == (vowel_count-1))) {
+					// first or last syllable, or option 'don't set diminished stress'
+					v_stress = STRESS_IS_UNSTRESSED;
+				} else if ((v == (vowel_count-2)) && (vowel_stress[vowel_count-1] <= STRESS_IS_UNSTRESSED)) {
 					// penultimate syllable, followed by an unstressed final syllable
 					v_stress = STRESS_IS_UNSTRESSED;
+				} else {
+					// unstressed syllable within a word
+					if ((vowel_stress[v-1] < STRESS_IS_DIMINISHED) || ((stressflags & S_MID_DIM) == 0)) {
+						v_stress = STRESS_IS_DIMINISHED;
+						vowel_stress[v] = (char)v_stress;
+					}
 				}
 			}
 
@@ -2636,6 +3685,21 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 
 			if (vowel_stress[v] > max_stress)
 				max_stress = vowel_stress[v];
+
+			if ((*p == phonLENGTHEN) && ((opt_length = langopts.param[LOPT_IT_LENGTHEN]) & 1)) {
+				// remove lengthen indicator from non-stressed syllables
+				if (v_stress < STRESS_IS_PRIMARY)
+					p++;
+			}
+
+			if ((v_stress >= STRESS_IS_PRIMARY) && (langopts.param[LOPT_IT_LENGTHEN] == 2)) {
+				// LANG=Italian, lengthen penultimate stressed vowels, unless followed by 2 consonants
+				if ((v == (vowel_count - 2)) && (syllable_weight[v] == 0)) {
+					*output++ = phcode;
+					phcode = phonLENGTHEN;
+				}
+			}
+
 			v++;
 		}
 
@@ -2643,8 +3707,6 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 			*output++ = phcode;
 	}
 	*output++ = 0;
-
-	return;
 }
 
 static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
@@ -2686,7 +3748,7 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 	if (Lookup(&single_letter[1], ph_buf3) == 0) {
 		single_letter[1] = ' ';
 		if (Lookup(&single_letter[2], ph_buf3) == 0)
-			TranslateRules(&single_letter[2], ph_buf3, sizeof(ph_buf3), NULL, FLAG_NO_TRACE);
+			TranslateRules(&single_letter[2], ph_buf3, sizeof(ph_buf3), NULL, FLAG_NO_TRACE, NULL);
 	}
 	strcpy(ph_buf1, ph_buf3);
 	if ((ph_buf1[0] == 0) || (ph_buf1[0] == phonSWITCH))
@@ -2697,7 +3759,7 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 	SetWordStress(ph_buf1, dict_flags, -1);
 }
 
-static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags) {
+static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags, int end_flags) {
 	int length;
 	const char *found;
 	const char *word1;
@@ -2728,12 +3790,34 @@ static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flag
 	}
 	word[length] = 0;
 
-	found = LookupDict2(word, word1, ph_out, flags);
+	found = LookupDict2(word, word1, ph_out, flags, end_flags);
 	if (found == 0 && length >= 2) {
 		ph_out[0] = 0;
+
+		// try modifications to find a recognised word
+		if ((end_flags & FLAG_SUFX_E_ADDED) && (word[length-1] == 'e')) {
+			// try removing an 'e' which has been added by RemoveEnding
+			word[length-1] = 0;
+			found = LookupDict2(word, word1, ph_out, flags, end_flags);
+		} else if ((end_flags & SUFX_D) && (word[length-1] == word[length-2])) {
+			// try removing a double letter
+			word[length-1] = 0;
+			found = LookupDict2(word, word1, ph_out, flags, end_flags);
+		}
 	}
 
 	if (found) {
+		if (flags[0] & FLAG_TEXTMODE) {
+			// the dictionary gives replacement text rather than phonemes
+			if (end_flags & FLAG_ALLOW_TEXTMODE) {
+				word_replacement[0] = 0;
+				word_replacement[1] = ' ';
+				snprintf(&word_replacement[2], sizeof(word_replacement)-2, "%s ", ph_out); // replacement word, preceded by zerochar and space
+				*wordptr = &word_replacement[2];
+			}
+			ph_out[0] = 0;
+			return 0;
+		}
 		return 1;
 	}
 
@@ -2794,12 +3878,42 @@ static void SetSpellingStress(char *phonemes, int control, int n_chars) {
 	*phonemes = 0;
 }
 
+
+static int Unpronouncable2(char *word) {
+	int c;
+	int end_flags;
+	char ph_buf[N_WORD_PHONEMES];
+
+	ph_buf[0] = 0;
+	c = word[-1];
+	word[-1] = ' '; // ensure there is a space before the "word"
+	end_flags = TranslateRules(word, ph_buf, sizeof(ph_buf), NULL, FLAG_UNPRON_TEST, NULL);
+	word[-1] = (char) c;
+	if ((end_flags == 0) || (end_flags & SUFX_UNPRON))
+		return 1;
+	return 0;
+}
+
 static int Unpronouncable(char *word, int posn) {
+	/* Determines whether a word in 'unpronouncable', i.e. whether it should be spoken as individual letters.
+
+	   This function may be language specific. This is a generic version.
+	 */
 	int c;
+	int c1 = 0;
 	int vowel_posn = 9;
 	int index;
 	int count;
+
 	utf8_in2(&c, word);
+	if ((letter_bits_offset > 0) && (c < 0x241)) {
+		// Latin characters for a language with a non-latin alphabet
+		return 0;
+	}
+
+	if (langopts.param[LOPT_UNPRONOUNCABLE] == 1)
+		return 0;
+
 	if (((c = *word) == ' ') || (c == 0) || (c == '\''))
 		return 0;
 
@@ -2812,6 +3926,9 @@ static int Unpronouncable(char *word, int posn) {
 
 		if ((c == '\'') && ((count > 1) || (posn > 0)))
 			break; // "tv'" but not "l'"
+
+		if (count == 0)
+			c1 = c;
 		count++;
 
 		if (IsLetter(c, LETTERGP_VOWEL2)) {
@@ -2822,27 +3939,51 @@ static int Unpronouncable(char *word, int posn) {
 		if ((c != '\'') && !iswalpha((wint_t) c))
 			return 0;
 	}
-	if (vowel_posn > 4)
+
+	if ((vowel_posn > 2) && (langopts.param[LOPT_UNPRONOUNCABLE] == 2)) {
+		// Lookup unpronounable rules in *_rules
+		return Unpronouncable2(word);
+	}
+
+	if (c1 == langopts.param[LOPT_UNPRONOUNCABLE])
+		vowel_posn--; // disregard this as the initial letter when counting
+
+	if (vowel_posn > (langopts.max_initial_consonants+1))
 		return 1; // no vowel, or no vowel in first few letters
 
 	return 0;
 }
 
-static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes[], size_t size_word_phonemes) {
+static int TranslateWord3(char *word_start, WORD_TAB *wtab, char *word_out, char a_word_phonemes[], size_t size_word_phonemes) {
 	// word1 is terminated by space (0x20) character
 
 	const char *word1;
 	int word_length;
+	int ix;
 	unsigned int dictionary_flags[2];
+	unsigned int dictionary_flags2[2];
+	int end_type = 0;
+	int end_type1 = 0;
+	int prefix_type = 0;
 	char *wordx;
 	char phonemes[N_WORD_PHONEMES];
+	char phonemes2[N_WORD_PHONEMES];
+	char prefix_phonemes[N_WORD_PHONEMES];
 	char unpron_phonemes[N_WORD_PHONEMES];
 	char end_phonemes[N_WORD_PHONEMES];
+	char end_phonemes2[N_WORD_PHONEMES];
+	char word_copy[N_WORD_BYTES];
 	char word_copy2[N_WORD_BYTES];
 	int word_copy_length;
 	bool found = false;
+	int end_flags;
+	int c_temp; // save a character byte while we temporarily replace it with space
 	int last_char = 0;
+	int prefix_flags = 0;
+	bool more_suffixes;
+	bool confirm_prefix;
 	int wflags;
+	int loopcount;
 	WORD_TAB wtab_null[8];
 
 	if (wtab == NULL) {
@@ -2853,8 +3994,11 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 
 	dictionary_flags[0] = 0;
 	dictionary_flags[1] = 0;
+	dictionary_flags2[0] = 0;
+	dictionary_flags2[1] = 0;
 	phonemes[0] = 0;
 	unpron_phonemes[0] = 0;
+	prefix_phonemes[0] = 0;
 	end_phonemes[0] = 0;
 	// count the length of the word
 	word1 = word_start;
@@ -2873,8 +4017,13 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 	memcpy(word_copy2, word_start, word_copy_length);
 	// try an initial lookup in the dictionary list, we may find a pronunciation specified, or
 	// we may just find some flags
-	if (!found)
-		found = LookupDictList(&word1, phonemes, dictionary_flags);   // the original word
+	found = LookupDictList(&word1, phonemes, dictionary_flags, FLAG_ALLOW_TEXTMODE);   // the original word
+	if ((dictionary_flags[0] & FLAG_TEXTMODE) && (word_out != NULL)) {
+		// the word is replaced by other text, which is translated by TranslateWord()
+		strcpy(word_out, word1);
+		return dictionary_flags[0];
+	}
+
 	if (found == false) {
 		// word's pronunciation is not given in the dictionary list, although
 		// dictionary_flags may have ben set there
@@ -2887,6 +4036,8 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 		wordx = (char *) word1;
 
 		while (((length < 3) && (length > 0)) || (word_length > 1 && Unpronouncable(wordx, posn))) {
+			// This word looks "unpronouncable", so speak letters individually until we
+			// find a remainder that we can pronounce.
 			if (wordx[0] == '\'')
 				break;
 			wordx += TranslateLetter(wordx, unpron_phonemes);
@@ -2904,17 +4055,231 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 			}
 
 			// Translate the stem
-			TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags);
+			end_type = TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags, dictionary_flags);
+
+			c_temp = wordx[-1];
+
 			found = false;
+			confirm_prefix = true;
+			for (loopcount = 0; (loopcount < 50) && (end_type & SUFX_P); loopcount++) {
+				// Found a standard prefix, remove it and retranslate
+				// loopcount guards against an endless loop
+				if (confirm_prefix && !(end_type & SUFX_B)) {
+					int end2;
+
+					// remove any standard suffix and confirm that the prefix is still recognised
+					phonemes2[0] = 0;
+					end2 = TranslateRules(wordx, phonemes2, N_WORD_PHONEMES, end_phonemes2, wflags|FLAG_NO_PREFIX|FLAG_NO_TRACE, dictionary_flags);
+					if (end2) {
+						RemoveEnding(wordx, end2, word_copy);
+						end_type = TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags|FLAG_NO_TRACE, dictionary_flags);
+						memcpy(wordx, word_copy, strlen(word_copy));
+						if ((end_type & SUFX_P) == 0) {
+							// after removing the suffix, the prefix is no longer recognised.
+							// Keep the suffix, but don't use the prefix
+							end_type = end2;
+							strcpy(phonemes, phonemes2);
+							strcpy(end_phonemes, end_phonemes2);
+							break;
+						}
+					}
+				}
+
+				prefix_type = end_type;
+
+				if (prefix_type & SUFX_V)
+					expect_verb = 1; // use the verb form of the word
+
+				wordx[-1] = (char) c_temp;
+
+				if ((prefix_type & SUFX_B) == 0) {
+					for (ix = (prefix_type & 0xf); ix > 0; ix--) { // num. of characters to remove
+						wordx++;
+						while ((*wordx & 0xc0) == 0x80) wordx++; // for multibyte characters
+					}
+				} else {
+					// the prefix length is given in bytes
+					wordx += (prefix_type & 0x3f);
+				}
+				c_temp = wordx[-1];
+				wordx[-1] = ' ';
+				confirm_prefix = true;
+				wflags |= FLAG_PREFIX_REMOVED;
+
+				strcat(prefix_phonemes, end_phonemes);
+				end_phonemes[0] = 0;
+
+				end_type = 0;
+				found = LookupDictList((const char **) &wordx, phonemes, dictionary_flags2, SUFX_P); // without prefix
+				if (dictionary_flags[0] == 0) {
+					dictionary_flags[0] = dictionary_flags2[0];
+					dictionary_flags[1] = dictionary_flags2[1];
+				} else
+					prefix_flags = 1;
+				if (found == false)
+					end_type = TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, 0, dictionary_flags);
+			}
+
+			if ((end_type != 0) && !(end_type & SUFX_P)) {
+				end_type1 = end_type;
+				strcpy(phonemes2, phonemes);
+
+				// The word has a standard ending, re-translate without this ending
+				end_flags = RemoveEnding(wordx, end_type, word_copy);
+				more_suffixes = true;
+
+				while (more_suffixes) {
+					more_suffixes = false;
+					phonemes[0] = 0;
+
+					if (prefix_phonemes[0] != 0) {
+						// lookup the stem without the prefix removed
+						wordx[-1] = (char) c_temp;
+						found = LookupDictList(&word1, phonemes, dictionary_flags2, end_flags); // include prefix, but not suffix
+						wordx[-1] = ' ';
+						if (dictionary_flags[0] == 0) {
+							dictionary_flags[0] = dictionary_flags2[0];
+							dictionary_flags[1] = dictionary_flags2[1];
+						}
+						if (found)
+							prefix_phonemes[0] = 0; // matched whole word, don't need prefix now
+
+						if ((found == false) && (dictionary_flags2[0] != 0))
+							prefix_flags = 1;
+					}
+					if (found == false) {
+						found = LookupDictList((const char **) &wordx, phonemes, dictionary_flags2, end_flags); // without prefix and suffix
+						if (dictionary_flags[0] == 0) {
+							dictionary_flags[0] = dictionary_flags2[0];
+							dictionary_flags[1] = dictionary_flags2[1];
+						} else
+							prefix_flags = 1;
+					}
+					if (found == false) {
+						if (end_type & SUFX_Q) {
+							// don't retranslate, use the original lookup result
+							strcpy(phonemes, phonemes2);
+						} else {
+							if (end_flags & FLAG_SUFX)
+								wflags |= FLAG_SUFFIX_REMOVED;
+							if (end_type & SUFX_A)
+								wflags |= FLAG_SUFFIX_VOWEL;
+
+							if (end_type & SUFX_M) {
+								// allow more suffixes before this suffix
+								strcpy(end_phonemes2, end_phonemes);
+								end_type = TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags, dictionary_flags);
+								strcat(end_phonemes, end_phonemes2); // add the phonemes for the previous suffixes after this one
+
+								if ((end_type != 0) && !(end_type & SUFX_P)) {
+									// there is another suffix
+									end_flags = RemoveEnding(wordx, end_type, NULL);
+									more_suffixes = true;
+								}
+							} else {
+								// don't remove any previous suffix
+								TranslateRules(wordx, phonemes, N_WORD_PHONEMES, NULL, wflags, dictionary_flags);
+								end_type = 0;
+							}
+						}
+					}
+				}
+
+				if ((end_type1 & SUFX_T) == 0) {
+					// the default is to add the suffix and then determine the word's stress pattern
+					AppendPhonemes(phonemes, N_WORD_PHONEMES, end_phonemes);
+					end_phonemes[0] = 0;
+				}
+				memcpy(wordx, word_copy, strlen(word_copy));
+			}
+
+			wordx[-1] = (char) c_temp;
 		}
 	}
-	SetWordStress(phonemes, dictionary_flags, -1);
-	snprintf(a_word_phonemes, size_word_phonemes, "%s%s", unpron_phonemes, phonemes);
-	a_word_phonemes[N_WORD_PHONEMES-1] = 0;
+
 	memcpy(word_start, word_copy2, word_copy_length);
+
+	// copy the word to word_phonemes, with the prefix
+	if (prefix_flags || (strchr(prefix_phonemes, phonSTRESS_P) != NULL)) {
+		if (prefix_type & SUFX_T) {
+			char *p;
+			// keep a secondary stress on the stem
+			SetWordStress(phonemes, dictionary_flags, 3);
+
+			// reduce all but the first primary stress
+			ix = 0;
+			for (p = prefix_phonemes; *p != 0; p++) {
+				if (*p == phonSTRESS_P) {
+					if (ix == 0)
+						ix = 1;
+					else
+						*p = phonSTRESS_3;
+				}
+			}
+			snprintf(a_word_phonemes, size_word_phonemes, "%s%s%s", unpron_phonemes, prefix_phonemes, phonemes);
+			a_word_phonemes[N_WORD_PHONEMES-1] = 0;
+			SetWordStress(a_word_phonemes, dictionary_flags, -1);
+		} else {
+			// stress position affects the whole word, including prefix
+			snprintf(a_word_phonemes, size_word_phonemes, "%s%s%s", unpron_phonemes, prefix_phonemes, phonemes);
+			a_word_phonemes[N_WORD_PHONEMES-1] = 0;
+			SetWordStress(a_word_phonemes, dictionary_flags, -1);
+		}
+	} else {
+		SetWordStress(phonemes, dictionary_flags, -1);
+		snprintf(a_word_phonemes, size_word_phonemes, "%s%s%s", unpron_phonemes, prefix_phonemes, phonemes);
+		a_word_phonemes[N_WORD_PHONEMES-1] = 0;
+	}
+
+	if (end_phonemes[0] != 0) {
+		// a suffix had the SUFX_T option set, add the suffix after the stress pattern has been determined
+		ix = (int) strlen(a_word_phonemes);
+		end_phonemes[N_WORD_PHONEMES-1-ix] = 0; // ensure no buffer overflow
+		strcpy(&a_word_phonemes[ix], end_phonemes);
+	}
 	return dictionary_flags[0];
 }
 
+static int TranslateWord(char *word_start, WORD_TAB *wtab, char *word_out) {
+	char words_phonemes[N_WORD_PHONEMES]; // a word translated into phoneme codes
+	char *phonemes = words_phonemes;
+	int available = N_WORD_PHONEMES;
+	bool first_word = true;
+
+	int flags = TranslateWord3(word_start, wtab, word_out, word_phonemes, sizeof(word_phonemes));
+	if ((flags & FLAG_TEXTMODE) && word_out) {
+		// Ensure that start of word rules match with the replaced text
+		char word[N_WORD_BYTES+1];
+		word[0] = 0;
+		word[1] = ' ';
+		strncpy0(word+2, word_out, N_WORD_BYTES-1);
+		word_out = word+2;
+
+		while (*word_out && (available > 1)) {
+			int n;
+
+			TranslateWord3(word_out, wtab, NULL, word_phonemes, sizeof(word_phonemes));
+
+			if (first_word) {
+				n = snprintf(phonemes, available, "%s", word_phonemes);
+				first_word = false;
+			} else
+				n = snprintf(phonemes, available, "%c%s", phonEND_WORD, word_phonemes);
+
+			available -= n;
+			phonemes += n;
+
+			// skip to the next word in a multi-word replacement
+			while ((*word_out != 0) && (*word_out != ' ')) ++word_out;
+			while (*word_out == ' ') ++word_out;
+		}
+
+		if (phonemes != words_phonemes)
+			snprintf(word_phonemes, sizeof(word_phonemes), "%s", words_phonemes);
+	}
+	return flags;
+}
+
 static int TranslateWord2(char *word, WORD_TAB *wtab) {
 	int flags = 0;
 	int stress;
@@ -2925,15 +4290,17 @@ static int TranslateWord2(char *word, WORD_TAB *wtab) {
 	PHONEME_TAB *ph;
 	int max_stress;
 	int prev_vowel = -1;
-	char word_copy[N_WORD_BYTES+1];
+	int found_dict_flag;
+	char word_out[N_WORD_BYTES+1];
 	p = (unsigned char *)word_phonemes;
-	int c2;
-	int ix = 0;
-	while (((c2 = word_copy[ix] = word[ix]) != ' ') && (c2 != 0) && (ix < N_WORD_BYTES)) ix++;
-	flags = TranslateWord3(word, wtab, word_phonemes, sizeof(word_phonemes));
+	word_out[0] = 0;
+	flags = TranslateWord(word, wtab, word_out);
+	found_dict_flag = 0;
+	if ((flags & FLAG_FOUND) && !(flags & FLAG_TEXTMODE))
+		found_dict_flag = SFLAG_DICTIONARY;
 	stress = 0;
 	next_stress = 1;
-	srcix = 0;
+	srcix = 1;
 	max_stress = -1;
 	while (((ph_code = *p++) != 0) && (n_ph_list2 < N_PHONEME_LIST-3-2)) {
 		if (ph_code == 255)
@@ -2957,9 +4324,16 @@ static int TranslateWord2(char *word, WORD_TAB *wtab) {
 			prev_vowel = n_ph_list2-1;
 			ph_list2[prev_vowel].synthflags |= SFLAG_SYLLABLE;
 			ph_list2[prev_vowel].stresslevel = (unsigned char) next_stress;
+		} else if (ph_code == phonLENGTHEN) {
+			ph_list2[n_ph_list2-1].synthflags |= SFLAG_LENGTHEN;
+		} else if (ph_code == phonEND_WORD) {
+			// a || symbol in a phoneme string was used to indicate a word boundary
+			// Don't add this phoneme to the list, but make sure the next phoneme has
+			// a newword indication
+			srcix = 1;
 		} else {
 			ph_list2[n_ph_list2].phcode = ph_code;
-			ph_list2[n_ph_list2].synthflags = 0;
+			ph_list2[n_ph_list2].synthflags = (unsigned short) found_dict_flag;
 			ph_list2[n_ph_list2].sourceix = (unsigned short) srcix;
 			srcix = 0;
 
@@ -3049,12 +4423,257 @@ static int NumInstnWords(const unsigned short *prog) {
 	}
 }
 
-static void InterpretPhoneme(PHONEME_LIST *plist, PHONEME_DATA *phdata, PHONEME_LIST *worddata) {
+
+static bool StressCondition(PHONEME_LIST *plist, int condition, int control) {
+	// control & 0x100: the phoneme list is being built, apply the language's vowel reduction options
+	int stress_level;
+	PHONEME_LIST *pl;
+	static const int condition_level[4] = { 1, 2, 4, 15 };
+
+	if (phoneme_tab[plist[0].phcode].type == phVOWEL)
+		pl = plist;
+	else {
+		// consonant, get stress setting from the following vowel
+		if (phoneme_tab[plist[1].phcode].type == phVOWEL)
+			pl = &plist[1];
+		else
+			return false; // no stress elevel for this consonant
+	}
+
+	stress_level = pl->stresslevel & 0xf;
+
+	if (control & 0x100) {
+		if ((control & 1) && (plist->synthflags & SFLAG_DICTIONARY) && ((langopts.param[LOPT_REDUCE] & 1) == 0)) {
+			// change phoneme.  Don't change phonemes which are given for the word in the dictionary.
+			return false;
+		}
+
+		if ((langopts.param[LOPT_REDUCE] & 0x2) && (stress_level >= pl->wordstress)) {
+			// treat the most stressed syllable in an unstressed word as stressed
+			stress_level = STRESS_IS_PRIMARY;
+		}
+	}
+
+	if (condition == STRESS_IS_PRIMARY)
+		return stress_level >= pl->wordstress;
+
+	if (condition == STRESS_IS_SECONDARY) {
+		if (stress_level > STRESS_IS_SECONDARY)
+			return true;
+	} else {
+		if (stress_level < condition_level[condition])
+			return true;
+	}
+	return false;
+}
+
+static int CountVowelPosition(PHONEME_LIST *plist) {
+	int count = 0;
+
+	for (;;) {
+		if (plist->ph->type == phVOWEL)
+			count++;
+		if ((plist->sourceix != 0) || (plist->ph->type == phPAUSE))
+			break;
+		plist--;
+	}
+	return count;
+}
+
+static bool InterpretCondition(int control, PHONEME_LIST *plist, const unsigned short *p_prog, PHONEME_LIST *worddata) {
+	unsigned int data;
+	int instn;
+	int instn2;
+	int which;
+	int check_endtype = 0;
+	PHONEME_TAB *ph;
+
+	// instruction: 2xxx, 3xxx
+
+	// bits 8-10 = 0 to 5,  which phoneme, =6 the 'which' information is in the next instruction.
+	// bit 11 = 0, bits 0-7 are a phoneme code
+	// bit 11 = 1, bits 5-7 type of data, bits 0-4 data value
+
+	// bits 8-10 = 7,  other conditions
+
+	instn = (*p_prog) & 0xfff;
+	data = instn & 0xff;
+	instn2 = instn >> 8;
+
+	if (instn2 < 14) {
+		which = instn2 % 7;
+
+		if (which == 6) {
+			// the 'which' code is in the next instruction
+			which = p_prog[1];
+		}
+
+		if (which == 4) {
+			// nextPhW not word boundary
+			if (plist[1].sourceix)
+				return false;
+		}
+		if (which == 5) {
+			// prevPhW, not word boundary
+			if (plist[0].sourceix)
+				return false;
+		}
+		if (which == 6) {
+			// next2PhW, not word boundary
+			if (plist[1].sourceix || plist[2].sourceix)
+				return false;
+		}
+
+		switch (which)
+		{
+		case 0: // prevPh
+		case 5: // prevPhW
+			plist--;
+			check_endtype = 1;
+			break;
+		case 1: // thisPh
+			break;
+		case 2: // nextPh
+		case 4: // nextPhW
+			plist++;
+			break;
+		case 3: // next2Ph
+		case 6: // next2PhW
+			plist += 2;
+			break;
+		case 7:
+			// nextVowel, not word boundary
+			for (which = 1;; which++) {
+				if (plist[which].sourceix)
+					return false;
+				if (phoneme_tab[plist[which].phcode].type == phVOWEL) {
+					plist = &plist[which];
+					break;
+				}
+			}
+			break;
+		case 8: // prevVowel in this word
+			if ((worddata == NULL) || (worddata->ph == NULL))
+				return false; // no previous vowel
+			plist = worddata;
+			check_endtype = 1;
+			break;
+		case 9: // next3PhW
+			for (which = 1; which <= 3; which++) {
+				if (plist[which].sourceix)
+					return false;
+			}
+			plist = &plist[3];
+			break;
+		case 10: // prev2PhW
+			if ((plist[0].sourceix) || (plist[-1].sourceix))
+				return false;
+			plist -= 2;
+			check_endtype = 1;
+			break;
+		}
+
+		if ((which == 0) || (which == 5)) {
+			if (plist->phcode == 1) {
+				// This is a NULL phoneme, a phoneme has been deleted so look at the previous phoneme
+				plist--;
+			}
+		}
+
+		if (control & 0x100) {
+			// "change phonemes" pass
+			plist->ph = &phoneme_tab[plist->phcode];
+		}
+		ph = plist->ph;
+
+		if (instn2 < 7) {
+			// 'data' is a phoneme number
+			if (phoneme_tab[data].mnemonic == ph->mnemonic)
+				return true;
+
+			//  not an exact match, check for a vowel type (eg. #i )
+			if ((check_endtype) && (ph->type == phVOWEL))
+				return data == ph->end_type; // prevPh() match on end_type
+			return data == ph->start_type; // thisPh() or nextPh(), match on start_type
+		}
+
+		data = instn & 0x1f;
+
+		switch (instn & 0xe0)
+		{
+		case 0x00: // phoneme type
+			return ph->type == data;
+		case 0x20: // place of articulation
+			return ((ph->phflags >> 16) & 0xf) == data;
+		case 0x40: // phoneme flag
+			return (ph->phflags & (1 << data)) != 0;
+		case 0x80:
+			switch (data)
+			{
+			case STRESS_IS_DIMINISHED:
+			case STRESS_IS_UNSTRESSED:
+			case STRESS_IS_NOT_STRESSED:
+			case STRESS_IS_SECONDARY:
+			case STRESS_IS_PRIMARY:
+				return StressCondition(plist, data, control & 0x100);
+			case isBreak:
+				return ph->type == phPAUSE;
+			case isWordStart:
+				return plist->sourceix != 0;
+			case isWordEnd:
+				return plist[1].sourceix || (phoneme_tab[plist[1].phcode].type == phPAUSE);
+			case isAfterStress:
+				if (plist->sourceix != 0)
+					return false;
+				do {
+					plist--;
+					if ((plist->stresslevel & 0xf) >= 4)
+						return true;
+
+				} while ((plist->sourceix == 0) && (phoneme_tab[plist->phcode].type != phPAUSE));
+				break;
+			case isNotVowel:
+				return ph->type != phVOWEL;
+			case isFinalVowel:
+				for (;;) {
+					plist++;
+					if (plist->sourceix || (phoneme_tab[plist->phcode].type == phPAUSE))
+						return true; // start of next word, so this is the final vowel
+					if (phoneme_tab[plist->phcode].type == phVOWEL)
+						return false;
+				}
+			case isVoiced:
+				return (ph->type == phVOWEL) || (ph->type == phLIQUID) || (ph->phflags & phVOICED);
+			case isFirstVowel:
+				return CountVowelPosition(plist) == 1;
+			case isSecondVowel:
+				return CountVowelPosition(plist) == 2;
+			case isTranslationGiven:
+				return (plist->synthflags & SFLAG_DICTIONARY) != 0;
+			}
+			break;
+		}
+		return false;
+	} else if (instn2 == 0xf) {
+		// Other conditions
+		if (data == 1) // PreVoicing
+			return control & 1;
+	}
+	return false;
+}
+
+static void InterpretPhoneme(int control, PHONEME_LIST *plist, PHONEME_DATA *phdata, PHONEME_LIST *worddata) {
+	// control:
+	// bit 0:  PreVoicing
+	// bit 8:  change phonemes
 	PHONEME_TAB *ph;
 	const unsigned short *prog;
 	int data;
 	int end_flag;
 	int ix;
+	bool truth;
+	bool truth2;
+	int or_flag;
 
 	#define N_RETURN 10
 	int n_return = 0;
@@ -3089,6 +4708,9 @@ static void InterpretPhoneme(PHONEME_LIST *plist, PHONEME_DATA *phdata, PHONEME_
 				case INSTN_CONTINUE:
 					break;
 				}
+			} else if (instn2 == i_APPEND_IFNEXTVOWEL) {
+				if (phoneme_tab[plist[1].phcode].type == phVOWEL)
+					phdata->pd_param[i_APPEND_PHONEME] = data;
 			} else if (instn2 == i_IPA_NAME) {
 				// followed by utf-8 characters, 2 per instn word
 				for (ix = 0; (ix < data) && (ix < 16); ix += 2) {
@@ -3099,14 +4721,51 @@ static void InterpretPhoneme(PHONEME_LIST *plist, PHONEME_DATA *phdata, PHONEME_
 				phdata->ipa_string[ix] = 0;
 			} else if (instn2 < N_PHONEME_DATA_PARAM) {
 				phdata->pd_param[instn2] = data;
+				if ((instn2 == i_CHANGE_PHONEME) && (control & 0x100)) {
+					// found ChangePhoneme() in PhonemeList mode, exit
+					end_flag = 1;
+				}
+			}
+			break;
+		case 1: // 1xxx
+			// ChangeIf, bits 8-11 = stress condition
+			if (StressCondition(plist, instn2 & 7, control | 1)) {
+				phdata->pd_param[i_CHANGE_PHONEME] = instn & 0xff;
+				end_flag = 1; // change phoneme, exit
 			}
 			break;
 		case 2:
 		case 3:
+			// conditions
+			or_flag = 0;
+			truth = true;
 			while ((instn & 0xe000) == 0x2000) {
+				// process a sequence of conditions, using  boolean accumulator
+				truth2 = InterpretCondition(control, plist, prog, worddata);
 				prog += NumInstnWords(prog);
+				if (*prog == i_NOT) {
+					truth2 = truth2 ^ 1;
+					prog++;
+				}
+
+				if (or_flag)
+					truth = truth || truth2;
+				else
+					truth = truth && truth2;
+				or_flag = instn & 0x1000;
 				instn = *prog;
 			}
+
+			if (truth == false) {
+				if ((instn & 0xf800) == i_JUMP_FALSE)
+					prog += instn & 0xff;
+				else {
+					// instruction after a condition is not JUMP_FALSE, so skip the instruction.
+					prog += NumInstnWords(prog);
+					if ((prog[0] & 0xfe00) == 0x6000)
+						prog++; // and skip ELSE jump
+				}
+			}
 			prog--;
 			break;
 		case 6:
@@ -3199,7 +4858,7 @@ static void InterpretPhoneme2(int phcode, PHONEME_DATA *phdata) {
 	plist[1].ph = &phoneme_tab[phcode];
 	plist[2].sourceix = 1;
 
-	InterpretPhoneme(&plist[1], phdata, NULL);
+	InterpretPhoneme(0, &plist[1], phdata, NULL);
 }
 
 static char *WritePhMnemonic(char *phon_out, PHONEME_TAB *ph, PHONEME_LIST *plist, int *flags) {
@@ -3216,7 +4875,7 @@ static char *WritePhMnemonic(char *phon_out, PHONEME_TAB *ph, PHONEME_LIST *plis
 	if (plist == NULL)
 		InterpretPhoneme2(ph->code, &phdata);
 	else
-		InterpretPhoneme(plist, &phdata, NULL);
+		InterpretPhoneme(0, plist, &phdata, NULL);
 
 	p = phdata.ipa_string;
 	if (*p == 0x20) {
@@ -3248,6 +4907,11 @@ static char *WritePhMnemonic(char *phon_out, PHONEME_TAB *ph, PHONEME_LIST *plis
 
 		if ((c == '#') && (ph->type == phVOWEL))
 			break; // # is subscript-h, but only for consonants
+
+		// ignore digits after the first character
+		if (!first && IsDigit09(c))
+			continue;
+
 		if ((c >= 0x20) && (c < 128))
 			c = ipa1[c-0x20];
 
@@ -3287,6 +4951,9 @@ static const char *GetTranslatedPhonemeString() {
 		plist = &phoneme_list[ix];
 
 		WritePhMnemonic(phon_buf2, plist->ph, plist, &flags);
+		if (plist->sourceix && (ix > 1))
+			*buf++ = ' '; // start of a following word in a multi-word replacement
+
 		if (plist->synthflags & SFLAG_SYLLABLE) {
 			if ((stress = plist->stresslevel) > 1) {
 				c = 0;
@@ -3313,6 +4980,8 @@ static const char *GetTranslatedPhonemeString() {
 		}
 
 		if (plist->ph->code != phonSWITCH) {
+			if (plist->synthflags & SFLAG_LENGTHEN)
+				buf = WritePhMnemonic(buf, &phoneme_tab[phonLENGTHEN], NULL, NULL);
 			if ((plist->synthflags & SFLAG_SYLLABLE) && (plist->type != phVOWEL)) {
 				// syllablic consonant
 				buf = WritePhMnemonic(buf, &phoneme_tab[phonSYLLABIC], plist, NULL);
@@ -3354,6 +5023,7 @@ void Initialize(const char *data_dictlist) {
 	unsigned char c, c2;
 
 	dict_condition = 0;
+	letter_bits_offset = 0;
 	data_dictrules = NULL; // language_1   translation rules file
 	memset(letter_bits, 0, sizeof(letter_bits));
 	// 0-6 sets of characters matched by A B C H F G Y  in pronunciation rules
@@ -3376,11 +5046,15 @@ void Initialize(const char *data_dictlist) {
 	data_dictrules = &data_dictlist[length];
 
 	n_groups2 = 0;
+	for (ix = 0; ix < 128; ix++)
+		groups3[ix] = NULL;
 	for (ix = 0; ix < 256; ix++) {
 		groups1[ix] = NULL;
 		groups2_count[ix] = 0;
 		groups2_start[ix] = 255; // indicates "not set"
 	}
+	for (ix = 0; ix < N_LETTER_GROUPS; ix++)
+		letterGroups[ix] = NULL;
 	p = data_dictrules;
 	// If there are no rules in the dictionary, compile_dictrules will not
 	// write a RULE_GROUP_START (written in the for loop), but will write
@@ -3391,22 +5065,43 @@ void Initialize(const char *data_dictlist) {
 			break;
 		}
 		p++;
-		length = (int) strlen(p);
-		p_name = p;
-		c = p_name[0];
-		c2 = p_name[1];
-
-		p += (length + 1);
-		if (length == 1)
-			groups1[c] = p;
-		else if (length == 0)
-			groups1[0] = p;
-		else {
-			if (groups2_start[c] == 255)
-				groups2_start[c] = (unsigned char) n_groups2;
-			groups2_count[c]++;
-			groups2[n_groups2] = p;
-			groups2_name[n_groups2++] = (c + (c2 << 8));
+
+		if (p[0] == RULE_REPLACEMENTS) {
+			// character replacements are not used, skip over this section
+			while (*p != RULE_GROUP_END)
+				p++;
+			p++;
+			continue;
+		}
+
+		if (p[0] == RULE_LETTERGP2) {
+			ix = p[1] - 'A';
+			if (ix < 0)
+				ix += 256;
+			p += 2;
+			if ((ix >= 0) && (ix < N_LETTER_GROUPS))
+				letterGroups[ix] = p;
+		} else {
+			length = (int) strlen(p);
+			p_name = p;
+			c = p_name[0];
+			c2 = p_name[1];
+
+			p += (length + 1);
+			if (length == 1)
+				groups1[c] = p;
+			else if (length == 0)
+				groups1[0] = p;
+			else if (c == 1) {
+				// index by offset letter
+				groups3[c2 - 1] = p;
+			} else if (n_groups2 < N_RULE_GROUP2) {
+				if (groups2_start[c] == 255)
+					groups2_start[c] = (unsigned char) n_groups2;
+				groups2_count[c]++;
+				groups2[n_groups2] = p;
+				groups2_name[n_groups2++] = (c + (c2 << 8));
+			}
 		}
 
 		// skip over all the rules in this group
@@ -3425,6 +5120,147 @@ void Initialize(const char *data_dictlist) {
 	}
 }
 
+static int SetUpPhonemeTable(const char *data_phontab, int number) {
+	// copy the phonemes of table 'number' into phoneme_tab, after those of the table it includes
+	int n_tables;
+	int ix;
+	const char *p;
+	unsigned char n_phonemes = 0;
+	unsigned char includes = 0;
+
+	memcpy(&n_tables, data_phontab, sizeof(int));
+	if ((number < 0) || (number >= n_tables))
+		return -1;
+
+	p = &data_phontab[4];
+	for (ix = 0; ix <= number; ix++) {
+		n_phonemes = p[0];
+		includes = p[1];
+		if (ix < number)
+			p += 4 + 32 + n_phonemes * sizeof(PHONEME_TAB);
+	}
+	if (includes > 0)
+		SetUpPhonemeTable(data_phontab, includes - 1);
+
+	p += 4 + 32;
+	for (ix = 0; ix < n_phonemes; ix++) {
+		PHONEME_TAB ph;
+		memcpy(&ph, &p[ix * sizeof(PHONEME_TAB)], sizeof(PHONEME_TAB));
+		phoneme_tab[ph.code] = ph;
+	}
+	return 0;
+}
+
+int SelectPhonemeTable(const char *data_phontab, const char *name) {
+	// select a phoneme table from the 'phontab' data by name, eg. "en", "en-us", "es"
+	int n_tables;
+	int ix;
+	const char *p;
+
+	memcpy(&n_tables, data_phontab, sizeof(int));
+	p = &data_phontab[4];
+	for (ix = 0; ix < n_tables; ix++) {
+		if (strcmp(&p[4], name) == 0) {
+			memset(phoneme_tab, 0, sizeof(phoneme_tab));
+			return SetUpPhonemeTable(data_phontab, ix);
+		}
+		p += 4 + 32 + (unsigned char)p[0] * sizeof(PHONEME_TAB);
+	}
+	fprintf(stderr, "Phoneme table '%s' not found\n", name);
+	return -1;
+}
+
+void SetIndicLetters(int offset) {
+	// set letter types for Indic scripts, Devanagari, Tamil, etc
+	static const char dev_consonants2[] = { 0x02, 0x03, 0x58, 0x59, 0x5a, 0x5b, 0x5c, 0x5d, 0x5e, 0x5f, 0x7b, 0x7c, 0x7e, 0x7f, 0 };
+	static const char dev_vowels2[] = { 0x60, 0x61, 0x55, 0x56, 0x57, 0x62, 0x63, 0 };
+
+	letter_bits_offset = offset;
+	memset(letter_bits, 0, sizeof(letter_bits));
+	SetLetterBitsRange(0, 0x04, 0x14); // vowel letters only
+	SetLetterBitsRange(0, 0x3e, 0x4d); // + vowel signs, and virama
+	SetLetterBits(0, dev_vowels2);     // + extra vowels and vowel signs
+
+	SetLetterBitsRange(1, 0x3e, 0x4d); // vowel signs, and virama
+	SetLetterBits(1, dev_vowels2);     // + extra vowels and vowel signs
+
+	SetLetterBitsRange(2, 0x15, 0x39); // the main consonant range
+	SetLetterBits(2, dev_consonants2); // + additional consonants
+
+	SetLetterBitsRange(6, 0x04, 0x14); // vowel letters
+	SetLetterBitsRange(6, 0x3e, 0x4c); // + vowel signs
+	SetLetterBits(6, dev_vowels2);     // + extra vowels and vowel signs
+}
+
+void SetDictRules(int condition) {
+	// the 'dictrules' attribute of an eSpeak voice, enables conditional rules in the dictionary
+	dict_condition = condition;
+}
+
+
+void SelectTranslator(const char *name) {
+	// set the language specific options of an eSpeak translator, eg. "en", "es", "pt"
+	// the letter groups are changed, so this is called once after Initialize()
+	int name2 = 0;
+
+	while (*name != 0)
+		name2 = (name2 << 8) + *name++;
+
+	memset(&langopts, 0, sizeof(langopts));
+	langopts.stress_rule = 2;
+	langopts.unstressed_wd1 = 1;
+	langopts.unstressed_wd2 = 3;
+	langopts.param[LOPT_UNPRONOUNCABLE] = 's'; // don't count this character at start of word
+	langopts.max_initial_consonants = 3;
+	translator_name = name2;
+
+	switch (name2)
+	{
+	case L('e', 'n'):
+		langopts.stress_rule = 0;
+		langopts.stress_flags = 0x08;
+		langopts.suffix_add_e = 'e';
+		langopts.param[LOPT_UNPRONOUNCABLE] = 2; // use en_rules for unpronouncable rules
+		SetLetterBits(6, "aeiouy"); // Group Y: vowels, including y
+		break;
+	case L('e', 's'):
+		// stress last syllable if it doesn't end in vowel or "s" or "n"
+		// 'diminished' is an unstressed final syllable
+		langopts.stress_flags = S_FINAL_SPANISH | S_FINAL_DIM_ONLY | S_FINAL_NO_2;
+		langopts.unstressed_wd1 = 0;
+		langopts.unstressed_wd2 = 2;
+		langopts.param[LOPT_UNPRONOUNCABLE] = 2; // use es_rules for unpronouncable rules
+		break;
+	case L('f', 'r'):
+		langopts.stress_rule = 3; // stress on final syllable
+		langopts.stress_flags = S_NO_AUTO_2 | S_FINAL_DIM; // don't use secondary stress
+		langopts.param[LOPT_IT_LENGTHEN] = 1; // remove lengthen indicator from unstressed syllables
+		SetLetterVowel('y');
+		break;
+	case L('i', 't'):
+		langopts.stress_flags = S_NO_AUTO_2 | S_FINAL_DIM_ONLY | S_PRIORITY_STRESS;
+		langopts.unstressed_wd1 = 2;
+		langopts.unstressed_wd2 = 2;
+		langopts.param[LOPT_IT_LENGTHEN] = 2; // remove lengthen indicator from unstressed or non-penultimate syllables
+		langopts.param[LOPT_REDUCE] = 1; // reduce vowels even if phonemes are specified in it_list
+		SetLetterVowel('y');
+		break;
+	case L('p', 't'):
+		langopts.stress_rule = 3; // stress on final syllable
+		langopts.stress_flags = S_FINAL_DIM_ONLY | S_FINAL_NO_2 | S_INITIAL_2 | S_PRIORITY_STRESS;
+		SetLetterVowel('y');
+		ResetLetterBits(0x2);
+		SetLetterBits(1, "bcdfgjkmnpqstvxz"); // B  hard consonants, excluding h,l,r,w,y
+		break;
+	case L('h', 'i'):
+		langopts.stress_rule = 6; // stress on last heaviest syllable, excluding final syllable
+		langopts.stress_flags = S_MID_DIM | S_FINAL_DIM; // use 'diminished' for unstressed final syllable
+		langopts.param[LOPT_UNPRONOUNCABLE] = 1; // disable check for unpronouncable words
+		langopts.suffix_add_e = 0x94d; // virama
+		break;
+	}
+}
+
 const char *TextToPhonemes(const char *textptr) {
 	int ix;
 	int c;
@@ -3434,6 +5270,9 @@ const char *TextToPhonemes(const char *textptr) {
 	char sbuf[N_TR_SOURCE];
 	int j;
 	int insert_ph = 0;
+	bool inserted;
+	bool deleted;
+	int alternative;
 	PHONEME_TAB *ph = NULL;
 	PHONEME_TAB *next;
 	int unstress_count = 0;
@@ -3454,7 +5293,11 @@ const char *TextToPhonemes(const char *textptr) {
 	ph_list2[0].phcode = phonPAUSE_SHORT;
 
 	n_ph_list2 = 1;
-	ix = 0;
+	expect_verb = 0;
+	// the word is preceded by a zero byte and a space, so that rules can match the start of the word
+	sbuf[0] = 0;
+	sbuf[1] = ' ';
+	ix = 2;
 	word.start = (unsigned short) ix;
 	word.flags = 0;
 	while (!finished && (ix < (int)sizeof(sbuf) - 1)) {
@@ -3466,8 +5309,13 @@ const char *TextToPhonemes(const char *textptr) {
 		ix += utf8_out(c, &sbuf[ix]);
 	}
 	sbuf[ix] = 0;
-	TranslateWord2(&sbuf[0], &word);
-	n_ph_list2 += 2;
+	TranslateWord2(&sbuf[2], &word);
+
+	// end of clause pauses
+	memset(&ph_list2[n_ph_list2], 0, 2 * sizeof(ph_list2[0]));
+	ph_list2[n_ph_list2].phcode = phonPAUSE;
+	ph_list2[n_ph_list2++].sourceix = 1;
+	ph_list2[n_ph_list2++].phcode = phonPAUSE_SHORT;
 	memset(&worddata, 0, sizeof(worddata));
 	plist2 = ph_list2;
 	end_sourceix = plist2[n_ph_list2-1].sourceix;
@@ -3516,7 +5364,7 @@ const char *TextToPhonemes(const char *textptr) {
 	}
 	n_ph_list2 -= delete_count;
 	n_ph_list3 = SubstitutePhonemes(ph_list3) - 2;
-	for (j = 0; (j < n_ph_list3) && (ix < N_PHONEME_LIST-3);) {
+	for (j = 0; j < n_ph_list3;) {
 		if (ph_list3[j].sourceix) {
 			// start of a word
 			int k;
@@ -3541,28 +5389,124 @@ const char *TextToPhonemes(const char *textptr) {
 	// transfer all the phonemes of the clause into phoneme_list
 	ph = &phoneme_tab[phonPAUSE];
 	ph_list3[0].ph = ph;
+	word_stress = 0;
 	ix = 0;
 	for (j = 0; insert_ph || ((j < n_ph_list3) && (ix < N_PHONEME_LIST-3)); j++) {
 		plist3 = &ph_list3[j];
-		bool deleted = false;
-		ph = &phoneme_tab[plist3->phcode];
-		plist3[0].ph = ph;
-		next = &phoneme_tab[plist3[1].phcode]; // the phoneme after this one
-		plist3[1].ph = next;
+		inserted = false;
+		deleted = false;
+		if (insert_ph != 0) {
+			// we have a (linking) phoneme which we need to insert here
+			next = &phoneme_tab[plist3->phcode]; // this phoneme, i.e. after the insert
+
+			// re-use the previous entry for the inserted phoneme.
+			// That's OK because we don't look backwards from plist3
+			j--;
+			plist3 = plist3-1;
+			plist3->phcode = (unsigned char) insert_ph;
+			ph = &phoneme_tab[insert_ph];
+			plist3->ph = ph;
+			insert_ph = 0;
+			inserted = true; // don't insert the same phoneme repeatedly
+		} else {
+			// otherwise get the next phoneme from the list
+			if (plist3->sourceix != 0) {
+				word_stress = plist3->wordstress;
+				memset(&worddata, 0, sizeof(worddata));
+			}
+			ph = &phoneme_tab[plist3->phcode];
+			plist3[0].ph = ph;
+			next = &phoneme_tab[plist3[1].phcode]; // the phoneme after this one
+			plist3[1].ph = next;
+		}
 		if (ph == NULL) continue;
 
-		InterpretPhoneme(plist3, &phdata, &worddata);
+		memset(&phdata, 0, sizeof(phdata));
+		if (j > 0)
+			InterpretPhoneme(0x100, plist3, &phdata, &worddata);
+
+		if ((alternative = phdata.pd_param[i_REPLACE_NEXT_PHONEME]) > 0) {
+			ph_list3[j+1].ph = &phoneme_tab[alternative];
+			ph_list3[j+1].phcode = (unsigned char) alternative;
+			ph_list3[j+1].type = phoneme_tab[alternative].type;
+			next = &phoneme_tab[alternative];
+		}
+
+		if (((alternative = phdata.pd_param[i_INSERT_PHONEME]) > 0) && (inserted == false)) {
+			// PROBLEM: if we insert a phoneme before a vowel then we loose the stress.
+			PHONEME_TAB *ph2;
+			ph2 = ph;
+
+			insert_ph = plist3->phcode;
+			ph = &phoneme_tab[alternative];
+			plist3->ph = ph;
+			plist3->phcode = (unsigned char) alternative;
+
+			if (ph->type == phVOWEL) {
+				plist3->synthflags |= SFLAG_SYLLABLE;
+				if (ph2->type != phVOWEL)
+					plist3->stresslevel = 0; // change from non-vowel to vowel, make sure it's unstressed
+			} else
+				plist3->synthflags &= ~SFLAG_SYLLABLE;
+
+			// re-interpret the changed phoneme
+			// But it doesn't obey a second ChangePhoneme()
+			InterpretPhoneme(0x100, plist3, &phdata, &worddata);
+		}
+
+		if ((alternative = phdata.pd_param[i_CHANGE_PHONEME]) > 0) {
+			PHONEME_TAB *ph2;
+			ph2 = ph;
+			ph = &phoneme_tab[alternative];
+			plist3->ph = ph;
+			plist3->phcode = (unsigned char) alternative;
+
+			if (alternative == 1) {
+				deleted = true; // NULL phoneme, discard
+				if ((plist3->sourceix != 0) && (ph_list3[j+1].sourceix == 0))
+					ph_list3[j+1].sourceix = plist3->sourceix; // keep the start of the word
+			} else {
+				if (ph->type == phVOWEL) {
+					plist3->synthflags |= SFLAG_SYLLABLE;
+					if (ph2->type != phVOWEL)
+						plist3->stresslevel = 0; // change from non-vowel to vowel, make sure it's unstressed
+				} else
+					plist3->synthflags &= ~SFLAG_SYLLABLE;
+			}
+		}
+
 		if ((ph->type == phVOWEL) && (deleted == false)) {
+			PHONEME_LIST *p;
+
 			// Check for consecutive unstressed syllables, even across word boundaries.
 			// Do this after changing phonemes according to stress level.
 			if (plist3->stresslevel <= 1) {
 				// an unstressed vowel
 				unstress_count++;
 
-				if ((unstress_count > 1) && ((unstress_count & 1) == 0)) {
-					// in a sequence of unstressed syllables, reduce alternate syllables to 'diminished'
-					// stress.  But not for the last phoneme of a stressed word
-					plist3->stresslevel = 0; // change stress to 'diminished'
+				if (langopts.stress_flags & 0x08) {
+					// change sequences of consecutive unstressed vowels in unstressed words to diminished stress
+					for (p = plist3+1; phoneme_tab[p->phcode].type != phPAUSE; p++) {
+						if (phoneme_tab[p->phcode].type == phVOWEL) {
+							if (p->stresslevel <= 1) {
+								if (plist3->wordstress < 4)
+									plist3->stresslevel = 0;
+								if (p->wordstress < 4)
+									p->stresslevel = 0;
+							}
+							break;
+						}
+					}
+				} else {
+					if ((unstress_count > 1) && ((unstress_count & 1) == 0)) {
+						// in a sequence of unstressed syllables, reduce alternate syllables to 'diminished'
+						// stress.  But not for the last phoneme of a stressed word
+						if ((langopts.stress_flags & S_NO_DIM) || ((word_stress > 3) && ((plist3+1)->sourceix != 0))) {
+							// An unstressed final vowel of a stressed word
+							unstress_count = 1; // try again for next syllable
+						} else
+							plist3->stresslevel = 0; // change stress to 'diminished'
+					}
 				}
 			} else
 				unstress_count = 0;
@@ -3581,16 +5525,33 @@ const char *TextToPhonemes(const char *textptr) {
 				phoneme_list[ix].sourceix = plist3->sourceix;
 			}
 			ix++;
+
+			if ((alternative = phdata.pd_param[i_APPEND_PHONEME]) > 0) {
+				// add an additional phoneme after this one
+				ph = &phoneme_tab[alternative];
+				phoneme_list[ix].ph = ph;
+				phoneme_list[ix].type = ph->type;
+				phoneme_list[ix].synthflags = 0;
+				phoneme_list[ix].stresslevel = 0;
+				phoneme_list[ix].wordstress = plist3->wordstress;
+				phoneme_list[ix].sourceix = 0;
+				phoneme_list[ix].phcode = (unsigned char) alternative;
+				if (ph->type == phVOWEL)
+					phoneme_list[ix].synthflags |= SFLAG_SYLLABLE;
+				ix++;
+			}
 		}
 	}
 	phoneme_list[ix].phcode = phonPAUSE;
 	phoneme_list[ix].sourceix = (unsigned short) end_sourceix;
 	phoneme_list[ix].synthflags = 0;
+	phoneme_list[ix].type = phPAUSE;
 	phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE];
 	phoneme_list[ix].phcode = phonPAUSE;
 	phoneme_list[ix].length = 0;
 	phoneme_list[ix].sourceix = 0;
 	phoneme_list[ix].synthflags = 0;
+	phoneme_list[ix].type = phPAUSE;
 	phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE_SHORT];
 	n_phoneme_list = ix;
 	return GetTranslatedPhonemeString();
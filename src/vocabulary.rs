@@ -0,0 +1,203 @@
+use {
+    crate::{KokoroError, ModelVersion, UnknownPhoneme, VOCAB_V019, VOCAB_V10, VOCAB_V11},
+    ort::session::Session,
+    serde_json::Value,
+    std::{collections::HashMap, path::Path},
+    tokio::fs::read_to_string,
+};
+
+/// 音素符号到模型输入编号的词表
+///
+/// 内置了v0.19、v1.0和v1.1模型的词表，也可以从Kokoro的`config.json`、`tokens.txt`或ONNX模型的元数据中加载，
+/// 再通过`KokoroTts::with_vocabulary`交给引擎使用。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{ModelVersion, Vocabulary};
+///
+/// let vocabulary = Vocabulary::from_config_json(r#"{"n_token": 178, "vocab": {"h": 50, "ə": 83, "l": 54}}"#).unwrap();
+/// let (tokens, unknown) = vocabulary.encode("həlo");
+/// assert_eq!(vec![0, 50, 83, 54, 0], tokens);
+/// assert_eq!('o', unknown[0].symbol);
+/// assert_eq!(Some(16), Vocabulary::builtin(ModelVersion::V019).get(' '));
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    map: HashMap<char, i64>,
+}
+
+impl From<HashMap<char, i64>> for Vocabulary {
+    fn from(map: HashMap<char, i64>) -> Self {
+        Self { map }
+    }
+}
+
+impl FromIterator<(char, i64)> for Vocabulary {
+    fn from_iter<T: IntoIterator<Item = (char, i64)>>(iter: T) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Vocabulary {
+    /// ONNX模型元数据中存放词表的键
+    pub const METADATA_KEY: &'static str = "vocab";
+
+    /// 内置的词表
+    pub fn builtin(model_version: ModelVersion) -> Self {
+        match model_version {
+            ModelVersion::V019 => VOCAB_V019.clone(),
+            ModelVersion::V10 => VOCAB_V10.clone(),
+            ModelVersion::V11 => VOCAB_V11.clone(),
+        }
+    }
+
+    /// 从Kokoro的`config.json`加载，读取其中的`vocab`对象；没有`vocab`时把整个对象当作词表
+    pub fn from_config_json(json: &str) -> Result<Self, KokoroError> {
+        let value = serde_json::from_str::<Value>(json)?;
+        let vocab = value.get("vocab").unwrap_or(&value);
+        let Some(vocab) = vocab.as_object() else {
+            return Err(KokoroError::VocabularyInvalid("vocab is not a JSON object".to_owned()));
+        };
+
+        vocab
+            .iter()
+            .map(|(symbol, id)| {
+                let mut chars = symbol.chars();
+                match (chars.next(), chars.next(), id.as_i64()) {
+                    (Some(c), None, Some(id)) => Ok((c, id)),
+                    _ => Err(KokoroError::VocabularyInvalid(format!("invalid entry {:?}: {}", symbol, id))),
+                }
+            })
+            .collect()
+    }
+
+    /// 从`tokens.txt`加载，每行是一个符号和它的编号，以空白分隔。符号本身可以是空格，例如`"  16"`。
+    pub fn from_tokens_txt(tokens: &str) -> Result<Self, KokoroError> {
+        let mut map = HashMap::new();
+        for (n, line) in tokens.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            // 编号在最后，不能先去掉两端的空白，否则空格符号会丢失
+            let entry = line
+                .rsplit_once(char::is_whitespace)
+                .and_then(|(symbol, id)| {
+                    let mut chars = symbol.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => Some((c, id.parse().ok()?)),
+                        _ => None,
+                    }
+                });
+            match entry {
+                Some((c, id)) => map.insert(c, id),
+                None => {
+                    return Err(KokoroError::VocabularyInvalid(format!(
+                        "line {}: {:?} is not a symbol and a token id",
+                        n + 1,
+                        line
+                    )));
+                }
+            };
+        }
+
+        Ok(map.into())
+    }
+
+    /// 从ONNX模型的自定义元数据中加载，值的格式与`config.json`相同，模型中没有[`Vocabulary::METADATA_KEY`]时返回`None`
+    pub fn from_model_metadata(model: &Session) -> Result<Option<Self>, KokoroError> {
+        match model.metadata()?.custom(Self::METADATA_KEY)? {
+            Some(json) => Self::from_config_json(&json).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 从文件加载，扩展名为`json`时按`config.json`解析，否则按`tokens.txt`解析
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, KokoroError> {
+        let path = path.as_ref();
+        let content = read_to_string(path).await?;
+        if path.extension().is_some_and(|i| i.eq_ignore_ascii_case("json")) {
+            Self::from_config_json(&content)
+        } else {
+            Self::from_tokens_txt(&content)
+        }
+    }
+
+    /// 符号的编号
+    pub fn get(&self, symbol: char) -> Option<i64> {
+        self.map.get(&symbol).copied()
+    }
+
+    /// 所有的符号和编号，顺序不确定
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (char, i64)> + '_ {
+        self.map.iter().map(|(symbol, id)| (*symbol, *id))
+    }
+
+    /// 词表中的符号数量
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 将音素转换为编号，首尾加上边界符号`0`，同时返回所有被跳过的未知符号。
+    /// 词表之外的空白没有读音，直接跳过而不报告。
+    pub fn encode(&self, phonemes: &str) -> (Vec<i64>, Vec<UnknownPhoneme>) {
+        let mut tokens = Vec::with_capacity(phonemes.len() + 2);
+        let mut unknown = Vec::new();
+        tokens.push(0);
+
+        for (position, (offset, i)) in phonemes.char_indices().enumerate() {
+            match self.get(i) {
+                Some(t) => tokens.push(t),
+                None if i.is_whitespace() => (),
+                None => unknown.push(UnknownPhoneme::new(phonemes, i, position, offset)),
+            }
+        }
+
+        tokens.push(0);
+        (tokens, unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tokens_txt() {
+        let vocabulary = Vocabulary::from_tokens_txt("$ 0\r\n; 1\n  16\n\nɝ 88\n").unwrap();
+        assert_eq!(4, vocabulary.len());
+        assert_eq!(Some(16), vocabulary.get(' '));
+        assert_eq!(Some(88), vocabulary.get('ɝ'));
+        assert!(Vocabulary::from_tokens_txt("ab 1").is_err());
+        assert!(Vocabulary::from_tokens_txt("a x").is_err());
+    }
+
+    #[test]
+    fn test_from_config_json() {
+        let vocabulary = Vocabulary::from_config_json(r#"{"vocab": {" ": 16, "ˈ": 156}}"#).unwrap();
+        assert_eq!(Some(156), vocabulary.get('ˈ'));
+        assert_eq!(vocabulary, Vocabulary::from_config_json(r#"{" ": 16, "ˈ": 156}"#).unwrap());
+        assert!(Vocabulary::from_config_json(r#"{"vocab": {"ab": 1}}"#).is_err());
+        assert!(Vocabulary::from_config_json(r#"{"vocab": [1]}"#).is_err());
+    }
+
+    #[test]
+    fn test_builtin() {
+        for version in [ModelVersion::V019, ModelVersion::V10, ModelVersion::V11] {
+            let vocabulary = Vocabulary::builtin(version);
+            assert_eq!(Some(16), vocabulary.get(' '));
+            assert_eq!(Some(1), vocabulary.get(';'));
+        }
+        let (tokens, unknown) = Vocabulary::builtin(ModelVersion::V11).encode("ni3\nhao3");
+        assert!(unknown.is_empty());
+        assert_eq!(9, tokens.len());
+    }
+}
@@ -1,673 +1,708 @@
-use crate::KokoroError;
+use crate::Language;
+
+/// 模型版本
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModelVersion {
+    V019,
+    V10,
+    V11,
+}
+
+/// 语音的口音
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Accent {
+    #[default]
+    American,
+    British,
+    Brazilian,
+    Mandarin,
+    Standard,
+}
+
+/// 发音人的性别
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Gender {
+    Female,
+    Male,
+}
+
+/// 语音的元数据
+///
+/// Kokoro的语音名称由两个字母的前缀和名字组成，例如`bf_emma`：第一个字母表示语言和口音，第二个字母表示性别。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VoiceInfo {
+    pub name: String,
+    pub language: Language,
+    pub accent: Accent,
+    pub gender: Gender,
+    pub model_version: ModelVersion,
+}
+
+impl VoiceInfo {
+    /// 从语音名称解析元数据，前缀无法识别时返回`None`
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Accent, Gender, Language, ModelVersion, VoiceInfo};
+    ///
+    /// let info = VoiceInfo::from_name("bf_emma", ModelVersion::V10).unwrap();
+    /// assert_eq!(info.language, Language::English);
+    /// assert_eq!(info.accent, Accent::British);
+    /// assert_eq!(info.gender, Gender::Female);
+    /// ```
+    ///
+    pub fn from_name(name: &str, model_version: ModelVersion) -> Option<Self> {
+        let mut prefix = name.chars();
+        let (language, accent) = match prefix.next()? {
+            'a' => (Language::English, Accent::American),
+            'b' => (Language::English, Accent::British),
+            'e' => (Language::Spanish, Accent::Standard),
+            'f' => (Language::French, Accent::Standard),
+            'h' => (Language::Hindi, Accent::Standard),
+            'i' => (Language::Italian, Accent::Standard),
+            'j' => (Language::Japanese, Accent::Standard),
+            'p' => (Language::Portuguese, Accent::Brazilian),
+            'z' => (Language::Chinese, Accent::Mandarin),
+            _ => return None,
+        };
+        let gender = match prefix.next()? {
+            'f' => Gender::Female,
+            'm' => Gender::Male,
+            _ => return None,
+        };
+
+        Some(Self {
+            name: name.to_owned(),
+            language,
+            accent,
+            gender,
+            model_version,
+        })
+    }
+}
 
 //noinspection SpellCheckingInspection
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Voice {
     // v1.0
-    ZmYunyang(f32),
-    ZfXiaoni(f32),
-    AfJessica(f32),
-    BfLily(f32),
-    ZfXiaobei(f32),
-    ZmYunxia(f32),
-    AfHeart(f32),
-    BfEmma(f32),
-    AmPuck(f32),
-    BfAlice(f32),
-    HfAlpha(f32),
-    BfIsabella(f32),
-    AfNova(f32),
-    AmFenrir(f32),
-    EmAlex(f32),
-    ImNicola(f32),
-    PmAlex(f32),
-    AfAlloy(f32),
-    ZmYunxi(f32),
-    AfSarah(f32),
-    JfNezumi(f32),
-    BmDaniel(f32),
-    JfTebukuro(f32),
-    JfAlpha(f32),
-    JmKumo(f32),
-    EmSanta(f32),
-    AmLiam(f32),
-    AmSanta(f32),
-    AmEric(f32),
-    BmFable(f32),
-    AfBella(f32),
-    BmLewis(f32),
-    PfDora(f32),
-    AfNicole(f32),
-    BmGeorge(f32),
-    AmOnyx(f32),
-    HmPsi(f32),
-    HfBeta(f32),
-    HmOmega(f32),
-    ZfXiaoxiao(f32),
-    FfSiwis(f32),
-    EfDora(f32),
-    AfAoede(f32),
-    AmEcho(f32),
-    AmMichael(f32),
-    AfKore(f32),
-    ZfXiaoyi(f32),
-    JfGongitsune(f32),
-    AmAdam(f32),
-    IfSara(f32),
-    AfSky(f32),
-    PmSanta(f32),
-    AfRiver(f32),
-    ZmYunjian(f32),
+    ZmYunyang,
+    ZfXiaoni,
+    AfJessica,
+    BfLily,
+    ZfXiaobei,
+    ZmYunxia,
+    AfHeart,
+    BfEmma,
+    AmPuck,
+    BfAlice,
+    HfAlpha,
+    BfIsabella,
+    AfNova,
+    AmFenrir,
+    EmAlex,
+    ImNicola,
+    PmAlex,
+    AfAlloy,
+    ZmYunxi,
+    AfSarah,
+    JfNezumi,
+    BmDaniel,
+    JfTebukuro,
+    JfAlpha,
+    JmKumo,
+    EmSanta,
+    AmLiam,
+    AmSanta,
+    AmEric,
+    BmFable,
+    AfBella,
+    BmLewis,
+    PfDora,
+    AfNicole,
+    BmGeorge,
+    AmOnyx,
+    HmPsi,
+    HfBeta,
+    HmOmega,
+    ZfXiaoxiao,
+    FfSiwis,
+    EfDora,
+    AfAoede,
+    AmEcho,
+    AmMichael,
+    AfKore,
+    ZfXiaoyi,
+    JfGongitsune,
+    AmAdam,
+    IfSara,
+    AfSky,
+    PmSanta,
+    AfRiver,
+    ZmYunjian,
 
     // v1.1
-    Zm029(i32),
-    Zf048(i32),
-    Zf008(i32),
-    Zm014(i32),
-    Zf003(i32),
-    Zf047(i32),
-    Zm080(i32),
-    Zf094(i32),
-    Zf046(i32),
-    Zm054(i32),
-    Zf001(i32),
-    Zm062(i32),
-    BfVale(i32),
-    Zf044(i32),
-    Zf005(i32),
-    Zf028(i32),
-    Zf059(i32),
-    Zm030(i32),
-    Zf074(i32),
-    Zm009(i32),
-    Zf004(i32),
-    Zf021(i32),
-    Zm095(i32),
-    Zm041(i32),
-    Zf087(i32),
-    Zf039(i32),
-    Zm031(i32),
-    Zf007(i32),
-    Zf038(i32),
-    Zf092(i32),
-    Zm056(i32),
-    Zf099(i32),
-    Zm010(i32),
-    Zm069(i32),
-    Zm016(i32),
-    Zm068(i32),
-    Zf083(i32),
-    Zf093(i32),
-    Zf006(i32),
-    Zf026(i32),
-    Zm053(i32),
-    Zm064(i32),
-    AfSol(i32),
-    Zf042(i32),
-    Zf084(i32),
-    Zf073(i32),
-    Zf067(i32),
-    Zm025(i32),
-    Zm020(i32),
-    Zm050(i32),
-    Zf070(i32),
-    Zf002(i32),
-    Zf032(i32),
-    Zm091(i32),
-    Zm066(i32),
-    Zm089(i32),
-    Zm034(i32),
-    Zm100(i32),
-    Zf086(i32),
-    Zf040(i32),
-    Zm011(i32),
-    Zm098(i32),
-    Zm015(i32),
-    Zf051(i32),
-    Zm065(i32),
-    Zf076(i32),
-    Zf036(i32),
-    Zm033(i32),
-    Zf018(i32),
-    Zf017(i32),
-    Zf049(i32),
-    AfMaple(i32),
-    Zm082(i32),
-    Zm057(i32),
-    Zf079(i32),
-    Zf022(i32),
-    Zm063(i32),
-    Zf060(i32),
-    Zf019(i32),
-    Zm097(i32),
-    Zm096(i32),
-    Zf023(i32),
-    Zf027(i32),
-    Zf085(i32),
-    Zf077(i32),
-    Zm035(i32),
-    Zf088(i32),
-    Zf024(i32),
-    Zf072(i32),
-    Zm055(i32),
-    Zm052(i32),
-    Zf071(i32),
-    Zm061(i32),
-    Zf078(i32),
-    Zm013(i32),
-    Zm081(i32),
-    Zm037(i32),
-    Zf090(i32),
-    Zf043(i32),
-    Zm058(i32),
-    Zm012(i32),
-    Zm045(i32),
-    Zf075(i32),
+    Zm029,
+    Zf048,
+    Zf008,
+    Zm014,
+    Zf003,
+    Zf047,
+    Zm080,
+    Zf094,
+    Zf046,
+    Zm054,
+    Zf001,
+    Zm062,
+    BfVale,
+    Zf044,
+    Zf005,
+    Zf028,
+    Zf059,
+    Zm030,
+    Zf074,
+    Zm009,
+    Zf004,
+    Zf021,
+    Zm095,
+    Zm041,
+    Zf087,
+    Zf039,
+    Zm031,
+    Zf007,
+    Zf038,
+    Zf092,
+    Zm056,
+    Zf099,
+    Zm010,
+    Zm069,
+    Zm016,
+    Zm068,
+    Zf083,
+    Zf093,
+    Zf006,
+    Zf026,
+    Zm053,
+    Zm064,
+    AfSol,
+    Zf042,
+    Zf084,
+    Zf073,
+    Zf067,
+    Zm025,
+    Zm020,
+    Zm050,
+    Zf070,
+    Zf002,
+    Zf032,
+    Zm091,
+    Zm066,
+    Zm089,
+    Zm034,
+    Zm100,
+    Zf086,
+    Zf040,
+    Zm011,
+    Zm098,
+    Zm015,
+    Zf051,
+    Zm065,
+    Zf076,
+    Zf036,
+    Zm033,
+    Zf018,
+    Zf017,
+    Zf049,
+    AfMaple,
+    Zm082,
+    Zm057,
+    Zf079,
+    Zf022,
+    Zm063,
+    Zf060,
+    Zf019,
+    Zm097,
+    Zm096,
+    Zf023,
+    Zf027,
+    Zf085,
+    Zf077,
+    Zm035,
+    Zf088,
+    Zf024,
+    Zf072,
+    Zm055,
+    Zm052,
+    Zf071,
+    Zm061,
+    Zf078,
+    Zm013,
+    Zm081,
+    Zm037,
+    Zf090,
+    Zf043,
+    Zm058,
+    Zm012,
+    Zm045,
+    Zf075,
 }
 
 impl Voice {
     //noinspection SpellCheckingInspection
-    pub(super) fn get_name(&self) -> &str {
+    pub(super) fn get_name(&self) -> &'static str {
         match self {
-            Self::ZmYunyang(_) => "zm_yunyang",
-            Self::ZfXiaoni(_) => "zf_xiaoni",
-            Self::AfJessica(_) => "af_jessica",
-            Self::BfLily(_) => "bf_lily",
-            Self::ZfXiaobei(_) => "zf_xiaobei",
-            Self::ZmYunxia(_) => "zm_yunxia",
-            Self::AfHeart(_) => "af_heart",
-            Self::BfEmma(_) => "bf_emma",
-            Self::AmPuck(_) => "am_puck",
-            Self::BfAlice(_) => "bf_alice",
-            Self::HfAlpha(_) => "hf_alpha",
-            Self::BfIsabella(_) => "bf_isabella",
-            Self::AfNova(_) => "af_nova",
-            Self::AmFenrir(_) => "am_fenrir",
-            Self::EmAlex(_) => "em_alex",
-            Self::ImNicola(_) => "im_nicola",
-            Self::PmAlex(_) => "pm_alex",
-            Self::AfAlloy(_) => "af_alloy",
-            Self::ZmYunxi(_) => "zm_yunxi",
-            Self::AfSarah(_) => "af_sarah",
-            Self::JfNezumi(_) => "jf_nezumi",
-            Self::BmDaniel(_) => "bm_daniel",
-            Self::JfTebukuro(_) => "jf_tebukuro",
-            Self::JfAlpha(_) => "jf_alpha",
-            Self::JmKumo(_) => "jm_kumo",
-            Self::EmSanta(_) => "em_santa",
-            Self::AmLiam(_) => "am_liam",
-            Self::AmSanta(_) => "am_santa",
-            Self::AmEric(_) => "am_eric",
-            Self::BmFable(_) => "bm_fable",
-            Self::AfBella(_) => "af_bella",
-            Self::BmLewis(_) => "bm_lewis",
-            Self::PfDora(_) => "pf_dora",
-            Self::AfNicole(_) => "af_nicole",
-            Self::BmGeorge(_) => "bm_george",
-            Self::AmOnyx(_) => "am_onyx",
-            Self::HmPsi(_) => "hm_psi",
-            Self::HfBeta(_) => "hf_beta",
-            Self::HmOmega(_) => "hm_omega",
-            Self::ZfXiaoxiao(_) => "zf_xiaoxiao",
-            Self::FfSiwis(_) => "ff_siwis",
-            Self::EfDora(_) => "ef_dora",
-            Self::AfAoede(_) => "af_aoede",
-            Self::AmEcho(_) => "am_echo",
-            Self::AmMichael(_) => "am_michael",
-            Self::AfKore(_) => "af_kore",
-            Self::ZfXiaoyi(_) => "zf_xiaoyi",
-            Self::JfGongitsune(_) => "jf_gongitsune",
-            Self::AmAdam(_) => "am_adam",
-            Self::IfSara(_) => "if_sara",
-            Self::AfSky(_) => "af_sky",
-            Self::PmSanta(_) => "pm_santa",
-            Self::AfRiver(_) => "af_river",
-            Self::ZmYunjian(_) => "zm_yunjian",
-            Self::Zm029(_) => "zm_029",
-            Self::Zf048(_) => "zf_048",
-            Self::Zf008(_) => "zf_008",
-            Self::Zm014(_) => "zm_014",
-            Self::Zf003(_) => "zf_003",
-            Self::Zf047(_) => "zf_047",
-            Self::Zm080(_) => "zm_080",
-            Self::Zf094(_) => "zf_094",
-            Self::Zf046(_) => "zf_046",
-            Self::Zm054(_) => "zm_054",
-            Self::Zf001(_) => "zf_001",
-            Self::Zm062(_) => "zm_062",
-            Self::BfVale(_) => "bf_vale",
-            Self::Zf044(_) => "zf_044",
-            Self::Zf005(_) => "zf_005",
-            Self::Zf028(_) => "zf_028",
-            Self::Zf059(_) => "zf_059",
-            Self::Zm030(_) => "zm_030",
-            Self::Zf074(_) => "zf_074",
-            Self::Zm009(_) => "zm_009",
-            Self::Zf004(_) => "zf_004",
-            Self::Zf021(_) => "zf_021",
-            Self::Zm095(_) => "zm_095",
-            Self::Zm041(_) => "zm_041",
-            Self::Zf087(_) => "zf_087",
-            Self::Zf039(_) => "zf_039",
-            Self::Zm031(_) => "zm_031",
-            Self::Zf007(_) => "zf_007",
-            Self::Zf038(_) => "zf_038",
-            Self::Zf092(_) => "zf_092",
-            Self::Zm056(_) => "zm_056",
-            Self::Zf099(_) => "zf_099",
-            Self::Zm010(_) => "zm_010",
-            Self::Zm069(_) => "zm_069",
-            Self::Zm016(_) => "zm_016",
-            Self::Zm068(_) => "zm_068",
-            Self::Zf083(_) => "zf_083",
-            Self::Zf093(_) => "zf_093",
-            Self::Zf006(_) => "zf_006",
-            Self::Zf026(_) => "zf_026",
-            Self::Zm053(_) => "zm_053",
-            Self::Zm064(_) => "zm_064",
-            Self::AfSol(_) => "af_sol",
-            Self::Zf042(_) => "zf_042",
-            Self::Zf084(_) => "zf_084",
-            Self::Zf073(_) => "zf_073",
-            Self::Zf067(_) => "zf_067",
-            Self::Zm025(_) => "zm_025",
-            Self::Zm020(_) => "zm_020",
-            Self::Zm050(_) => "zm_050",
-            Self::Zf070(_) => "zf_070",
-            Self::Zf002(_) => "zf_002",
-            Self::Zf032(_) => "zf_032",
-            Self::Zm091(_) => "zm_091",
-            Self::Zm066(_) => "zm_066",
-            Self::Zm089(_) => "zm_089",
-            Self::Zm034(_) => "zm_034",
-            Self::Zm100(_) => "zm_100",
-            Self::Zf086(_) => "zf_086",
-            Self::Zf040(_) => "zf_040",
-            Self::Zm011(_) => "zm_011",
-            Self::Zm098(_) => "zm_098",
-            Self::Zm015(_) => "zm_015",
-            Self::Zf051(_) => "zf_051",
-            Self::Zm065(_) => "zm_065",
-            Self::Zf076(_) => "zf_076",
-            Self::Zf036(_) => "zf_036",
-            Self::Zm033(_) => "zm_033",
-            Self::Zf018(_) => "zf_018",
-            Self::Zf017(_) => "zf_017",
-            Self::Zf049(_) => "zf_049",
-            Self::AfMaple(_) => "af_maple",
-            Self::Zm082(_) => "zm_082",
-            Self::Zm057(_) => "zm_057",
-            Self::Zf079(_) => "zf_079",
-            Self::Zf022(_) => "zf_022",
-            Self::Zm063(_) => "zm_063",
-            Self::Zf060(_) => "zf_060",
-            Self::Zf019(_) => "zf_019",
-            Self::Zm097(_) => "zm_097",
-            Self::Zm096(_) => "zm_096",
-            Self::Zf023(_) => "zf_023",
-            Self::Zf027(_) => "zf_027",
-            Self::Zf085(_) => "zf_085",
-            Self::Zf077(_) => "zf_077",
-            Self::Zm035(_) => "zm_035",
-            Self::Zf088(_) => "zf_088",
-            Self::Zf024(_) => "zf_024",
-            Self::Zf072(_) => "zf_072",
-            Self::Zm055(_) => "zm_055",
-            Self::Zm052(_) => "zm_052",
-            Self::Zf071(_) => "zf_071",
-            Self::Zm061(_) => "zm_061",
-            Self::Zf078(_) => "zf_078",
-            Self::Zm013(_) => "zm_013",
-            Self::Zm081(_) => "zm_081",
-            Self::Zm037(_) => "zm_037",
-            Self::Zf090(_) => "zf_090",
-            Self::Zf043(_) => "zf_043",
-            Self::Zm058(_) => "zm_058",
-            Self::Zm012(_) => "zm_012",
-            Self::Zm045(_) => "zm_045",
-            Self::Zf075(_) => "zf_075",
+            Self::ZmYunyang => "zm_yunyang",
+            Self::ZfXiaoni => "zf_xiaoni",
+            Self::AfJessica => "af_jessica",
+            Self::BfLily => "bf_lily",
+            Self::ZfXiaobei => "zf_xiaobei",
+            Self::ZmYunxia => "zm_yunxia",
+            Self::AfHeart => "af_heart",
+            Self::BfEmma => "bf_emma",
+            Self::AmPuck => "am_puck",
+            Self::BfAlice => "bf_alice",
+            Self::HfAlpha => "hf_alpha",
+            Self::BfIsabella => "bf_isabella",
+            Self::AfNova => "af_nova",
+            Self::AmFenrir => "am_fenrir",
+            Self::EmAlex => "em_alex",
+            Self::ImNicola => "im_nicola",
+            Self::PmAlex => "pm_alex",
+            Self::AfAlloy => "af_alloy",
+            Self::ZmYunxi => "zm_yunxi",
+            Self::AfSarah => "af_sarah",
+            Self::JfNezumi => "jf_nezumi",
+            Self::BmDaniel => "bm_daniel",
+            Self::JfTebukuro => "jf_tebukuro",
+            Self::JfAlpha => "jf_alpha",
+            Self::JmKumo => "jm_kumo",
+            Self::EmSanta => "em_santa",
+            Self::AmLiam => "am_liam",
+            Self::AmSanta => "am_santa",
+            Self::AmEric => "am_eric",
+            Self::BmFable => "bm_fable",
+            Self::AfBella => "af_bella",
+            Self::BmLewis => "bm_lewis",
+            Self::PfDora => "pf_dora",
+            Self::AfNicole => "af_nicole",
+            Self::BmGeorge => "bm_george",
+            Self::AmOnyx => "am_onyx",
+            Self::HmPsi => "hm_psi",
+            Self::HfBeta => "hf_beta",
+            Self::HmOmega => "hm_omega",
+            Self::ZfXiaoxiao => "zf_xiaoxiao",
+            Self::FfSiwis => "ff_siwis",
+            Self::EfDora => "ef_dora",
+            Self::AfAoede => "af_aoede",
+            Self::AmEcho => "am_echo",
+            Self::AmMichael => "am_michael",
+            Self::AfKore => "af_kore",
+            Self::ZfXiaoyi => "zf_xiaoyi",
+            Self::JfGongitsune => "jf_gongitsune",
+            Self::AmAdam => "am_adam",
+            Self::IfSara => "if_sara",
+            Self::AfSky => "af_sky",
+            Self::PmSanta => "pm_santa",
+            Self::AfRiver => "af_river",
+            Self::ZmYunjian => "zm_yunjian",
+            Self::Zm029 => "zm_029",
+            Self::Zf048 => "zf_048",
+            Self::Zf008 => "zf_008",
+            Self::Zm014 => "zm_014",
+            Self::Zf003 => "zf_003",
+            Self::Zf047 => "zf_047",
+            Self::Zm080 => "zm_080",
+            Self::Zf094 => "zf_094",
+            Self::Zf046 => "zf_046",
+            Self::Zm054 => "zm_054",
+            Self::Zf001 => "zf_001",
+            Self::Zm062 => "zm_062",
+            Self::BfVale => "bf_vale",
+            Self::Zf044 => "zf_044",
+            Self::Zf005 => "zf_005",
+            Self::Zf028 => "zf_028",
+            Self::Zf059 => "zf_059",
+            Self::Zm030 => "zm_030",
+            Self::Zf074 => "zf_074",
+            Self::Zm009 => "zm_009",
+            Self::Zf004 => "zf_004",
+            Self::Zf021 => "zf_021",
+            Self::Zm095 => "zm_095",
+            Self::Zm041 => "zm_041",
+            Self::Zf087 => "zf_087",
+            Self::Zf039 => "zf_039",
+            Self::Zm031 => "zm_031",
+            Self::Zf007 => "zf_007",
+            Self::Zf038 => "zf_038",
+            Self::Zf092 => "zf_092",
+            Self::Zm056 => "zm_056",
+            Self::Zf099 => "zf_099",
+            Self::Zm010 => "zm_010",
+            Self::Zm069 => "zm_069",
+            Self::Zm016 => "zm_016",
+            Self::Zm068 => "zm_068",
+            Self::Zf083 => "zf_083",
+            Self::Zf093 => "zf_093",
+            Self::Zf006 => "zf_006",
+            Self::Zf026 => "zf_026",
+            Self::Zm053 => "zm_053",
+            Self::Zm064 => "zm_064",
+            Self::AfSol => "af_sol",
+            Self::Zf042 => "zf_042",
+            Self::Zf084 => "zf_084",
+            Self::Zf073 => "zf_073",
+            Self::Zf067 => "zf_067",
+            Self::Zm025 => "zm_025",
+            Self::Zm020 => "zm_020",
+            Self::Zm050 => "zm_050",
+            Self::Zf070 => "zf_070",
+            Self::Zf002 => "zf_002",
+            Self::Zf032 => "zf_032",
+            Self::Zm091 => "zm_091",
+            Self::Zm066 => "zm_066",
+            Self::Zm089 => "zm_089",
+            Self::Zm034 => "zm_034",
+            Self::Zm100 => "zm_100",
+            Self::Zf086 => "zf_086",
+            Self::Zf040 => "zf_040",
+            Self::Zm011 => "zm_011",
+            Self::Zm098 => "zm_098",
+            Self::Zm015 => "zm_015",
+            Self::Zf051 => "zf_051",
+            Self::Zm065 => "zm_065",
+            Self::Zf076 => "zf_076",
+            Self::Zf036 => "zf_036",
+            Self::Zm033 => "zm_033",
+            Self::Zf018 => "zf_018",
+            Self::Zf017 => "zf_017",
+            Self::Zf049 => "zf_049",
+            Self::AfMaple => "af_maple",
+            Self::Zm082 => "zm_082",
+            Self::Zm057 => "zm_057",
+            Self::Zf079 => "zf_079",
+            Self::Zf022 => "zf_022",
+            Self::Zm063 => "zm_063",
+            Self::Zf060 => "zf_060",
+            Self::Zf019 => "zf_019",
+            Self::Zm097 => "zm_097",
+            Self::Zm096 => "zm_096",
+            Self::Zf023 => "zf_023",
+            Self::Zf027 => "zf_027",
+            Self::Zf085 => "zf_085",
+            Self::Zf077 => "zf_077",
+            Self::Zm035 => "zm_035",
+            Self::Zf088 => "zf_088",
+            Self::Zf024 => "zf_024",
+            Self::Zf072 => "zf_072",
+            Self::Zm055 => "zm_055",
+            Self::Zm052 => "zm_052",
+            Self::Zf071 => "zf_071",
+            Self::Zm061 => "zm_061",
+            Self::Zf078 => "zf_078",
+            Self::Zm013 => "zm_013",
+            Self::Zm081 => "zm_081",
+            Self::Zm037 => "zm_037",
+            Self::Zf090 => "zf_090",
+            Self::Zf043 => "zf_043",
+            Self::Zm058 => "zm_058",
+            Self::Zm012 => "zm_012",
+            Self::Zm045 => "zm_045",
+            Self::Zf075 => "zf_075",
         }
     }
 
-    pub(super) fn is_v10_supported(&self) -> bool {
-        matches!(
-            self,
-            Self::ZmYunyang(_)
-                | Self::ZfXiaoni(_)
-                | Self::AfJessica(_)
-                | Self::BfLily(_)
-                | Self::ZfXiaobei(_)
-                | Self::ZmYunxia(_)
-                | Self::AfHeart(_)
-                | Self::BfEmma(_)
-                | Self::AmPuck(_)
-                | Self::BfAlice(_)
-                | Self::HfAlpha(_)
-                | Self::BfIsabella(_)
-                | Self::AfNova(_)
-                | Self::AmFenrir(_)
-                | Self::EmAlex(_)
-                | Self::ImNicola(_)
-                | Self::PmAlex(_)
-                | Self::AfAlloy(_)
-                | Self::ZmYunxi(_)
-                | Self::AfSarah(_)
-                | Self::JfNezumi(_)
-                | Self::BmDaniel(_)
-                | Self::JfTebukuro(_)
-                | Self::JfAlpha(_)
-                | Self::JmKumo(_)
-                | Self::EmSanta(_)
-                | Self::AmLiam(_)
-                | Self::AmSanta(_)
-                | Self::AmEric(_)
-                | Self::BmFable(_)
-                | Self::AfBella(_)
-                | Self::BmLewis(_)
-                | Self::PfDora(_)
-                | Self::AfNicole(_)
-                | Self::BmGeorge(_)
-                | Self::AmOnyx(_)
-                | Self::HmPsi(_)
-                | Self::HfBeta(_)
-                | Self::HmOmega(_)
-                | Self::ZfXiaoxiao(_)
-                | Self::FfSiwis(_)
-                | Self::EfDora(_)
-                | Self::AfAoede(_)
-                | Self::AmEcho(_)
-                | Self::AmMichael(_)
-                | Self::AfKore(_)
-                | Self::ZfXiaoyi(_)
-                | Self::JfGongitsune(_)
-                | Self::AmAdam(_)
-                | Self::IfSara(_)
-                | Self::AfSky(_)
-                | Self::PmSanta(_)
-                | Self::AfRiver(_)
-                | Self::ZmYunjian(_)
-        )
+    /// 根据语音名称查找语音
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::Voice;
+    ///
+    /// assert!(Voice::from_name("af_heart").is_some());
+    /// assert!(Voice::from_name("xx_unknown").is_none());
+    /// ```
+    ///
+    //noinspection SpellCheckingInspection
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zm_yunyang" => Some(Self::ZmYunyang),
+            "zf_xiaoni" => Some(Self::ZfXiaoni),
+            "af_jessica" => Some(Self::AfJessica),
+            "bf_lily" => Some(Self::BfLily),
+            "zf_xiaobei" => Some(Self::ZfXiaobei),
+            "zm_yunxia" => Some(Self::ZmYunxia),
+            "af_heart" => Some(Self::AfHeart),
+            "bf_emma" => Some(Self::BfEmma),
+            "am_puck" => Some(Self::AmPuck),
+            "bf_alice" => Some(Self::BfAlice),
+            "hf_alpha" => Some(Self::HfAlpha),
+            "bf_isabella" => Some(Self::BfIsabella),
+            "af_nova" => Some(Self::AfNova),
+            "am_fenrir" => Some(Self::AmFenrir),
+            "em_alex" => Some(Self::EmAlex),
+            "im_nicola" => Some(Self::ImNicola),
+            "pm_alex" => Some(Self::PmAlex),
+            "af_alloy" => Some(Self::AfAlloy),
+            "zm_yunxi" => Some(Self::ZmYunxi),
+            "af_sarah" => Some(Self::AfSarah),
+            "jf_nezumi" => Some(Self::JfNezumi),
+            "bm_daniel" => Some(Self::BmDaniel),
+            "jf_tebukuro" => Some(Self::JfTebukuro),
+            "jf_alpha" => Some(Self::JfAlpha),
+            "jm_kumo" => Some(Self::JmKumo),
+            "em_santa" => Some(Self::EmSanta),
+            "am_liam" => Some(Self::AmLiam),
+            "am_santa" => Some(Self::AmSanta),
+            "am_eric" => Some(Self::AmEric),
+            "bm_fable" => Some(Self::BmFable),
+            "af_bella" => Some(Self::AfBella),
+            "bm_lewis" => Some(Self::BmLewis),
+            "pf_dora" => Some(Self::PfDora),
+            "af_nicole" => Some(Self::AfNicole),
+            "bm_george" => Some(Self::BmGeorge),
+            "am_onyx" => Some(Self::AmOnyx),
+            "hm_psi" => Some(Self::HmPsi),
+            "hf_beta" => Some(Self::HfBeta),
+            "hm_omega" => Some(Self::HmOmega),
+            "zf_xiaoxiao" => Some(Self::ZfXiaoxiao),
+            "ff_siwis" => Some(Self::FfSiwis),
+            "ef_dora" => Some(Self::EfDora),
+            "af_aoede" => Some(Self::AfAoede),
+            "am_echo" => Some(Self::AmEcho),
+            "am_michael" => Some(Self::AmMichael),
+            "af_kore" => Some(Self::AfKore),
+            "zf_xiaoyi" => Some(Self::ZfXiaoyi),
+            "jf_gongitsune" => Some(Self::JfGongitsune),
+            "am_adam" => Some(Self::AmAdam),
+            "if_sara" => Some(Self::IfSara),
+            "af_sky" => Some(Self::AfSky),
+            "pm_santa" => Some(Self::PmSanta),
+            "af_river" => Some(Self::AfRiver),
+            "zm_yunjian" => Some(Self::ZmYunjian),
+            "zm_029" => Some(Self::Zm029),
+            "zf_048" => Some(Self::Zf048),
+            "zf_008" => Some(Self::Zf008),
+            "zm_014" => Some(Self::Zm014),
+            "zf_003" => Some(Self::Zf003),
+            "zf_047" => Some(Self::Zf047),
+            "zm_080" => Some(Self::Zm080),
+            "zf_094" => Some(Self::Zf094),
+            "zf_046" => Some(Self::Zf046),
+            "zm_054" => Some(Self::Zm054),
+            "zf_001" => Some(Self::Zf001),
+            "zm_062" => Some(Self::Zm062),
+            "bf_vale" => Some(Self::BfVale),
+            "zf_044" => Some(Self::Zf044),
+            "zf_005" => Some(Self::Zf005),
+            "zf_028" => Some(Self::Zf028),
+            "zf_059" => Some(Self::Zf059),
+            "zm_030" => Some(Self::Zm030),
+            "zf_074" => Some(Self::Zf074),
+            "zm_009" => Some(Self::Zm009),
+            "zf_004" => Some(Self::Zf004),
+            "zf_021" => Some(Self::Zf021),
+            "zm_095" => Some(Self::Zm095),
+            "zm_041" => Some(Self::Zm041),
+            "zf_087" => Some(Self::Zf087),
+            "zf_039" => Some(Self::Zf039),
+            "zm_031" => Some(Self::Zm031),
+            "zf_007" => Some(Self::Zf007),
+            "zf_038" => Some(Self::Zf038),
+            "zf_092" => Some(Self::Zf092),
+            "zm_056" => Some(Self::Zm056),
+            "zf_099" => Some(Self::Zf099),
+            "zm_010" => Some(Self::Zm010),
+            "zm_069" => Some(Self::Zm069),
+            "zm_016" => Some(Self::Zm016),
+            "zm_068" => Some(Self::Zm068),
+            "zf_083" => Some(Self::Zf083),
+            "zf_093" => Some(Self::Zf093),
+            "zf_006" => Some(Self::Zf006),
+            "zf_026" => Some(Self::Zf026),
+            "zm_053" => Some(Self::Zm053),
+            "zm_064" => Some(Self::Zm064),
+            "af_sol" => Some(Self::AfSol),
+            "zf_042" => Some(Self::Zf042),
+            "zf_084" => Some(Self::Zf084),
+            "zf_073" => Some(Self::Zf073),
+            "zf_067" => Some(Self::Zf067),
+            "zm_025" => Some(Self::Zm025),
+            "zm_020" => Some(Self::Zm020),
+            "zm_050" => Some(Self::Zm050),
+            "zf_070" => Some(Self::Zf070),
+            "zf_002" => Some(Self::Zf002),
+            "zf_032" => Some(Self::Zf032),
+            "zm_091" => Some(Self::Zm091),
+            "zm_066" => Some(Self::Zm066),
+            "zm_089" => Some(Self::Zm089),
+            "zm_034" => Some(Self::Zm034),
+            "zm_100" => Some(Self::Zm100),
+            "zf_086" => Some(Self::Zf086),
+            "zf_040" => Some(Self::Zf040),
+            "zm_011" => Some(Self::Zm011),
+            "zm_098" => Some(Self::Zm098),
+            "zm_015" => Some(Self::Zm015),
+            "zf_051" => Some(Self::Zf051),
+            "zm_065" => Some(Self::Zm065),
+            "zf_076" => Some(Self::Zf076),
+            "zf_036" => Some(Self::Zf036),
+            "zm_033" => Some(Self::Zm033),
+            "zf_018" => Some(Self::Zf018),
+            "zf_017" => Some(Self::Zf017),
+            "zf_049" => Some(Self::Zf049),
+            "af_maple" => Some(Self::AfMaple),
+            "zm_082" => Some(Self::Zm082),
+            "zm_057" => Some(Self::Zm057),
+            "zf_079" => Some(Self::Zf079),
+            "zf_022" => Some(Self::Zf022),
+            "zm_063" => Some(Self::Zm063),
+            "zf_060" => Some(Self::Zf060),
+            "zf_019" => Some(Self::Zf019),
+            "zm_097" => Some(Self::Zm097),
+            "zm_096" => Some(Self::Zm096),
+            "zf_023" => Some(Self::Zf023),
+            "zf_027" => Some(Self::Zf027),
+            "zf_085" => Some(Self::Zf085),
+            "zf_077" => Some(Self::Zf077),
+            "zm_035" => Some(Self::Zm035),
+            "zf_088" => Some(Self::Zf088),
+            "zf_024" => Some(Self::Zf024),
+            "zf_072" => Some(Self::Zf072),
+            "zm_055" => Some(Self::Zm055),
+            "zm_052" => Some(Self::Zm052),
+            "zf_071" => Some(Self::Zf071),
+            "zm_061" => Some(Self::Zm061),
+            "zf_078" => Some(Self::Zf078),
+            "zm_013" => Some(Self::Zm013),
+            "zm_081" => Some(Self::Zm081),
+            "zm_037" => Some(Self::Zm037),
+            "zf_090" => Some(Self::Zf090),
+            "zf_043" => Some(Self::Zf043),
+            "zm_058" => Some(Self::Zm058),
+            "zm_012" => Some(Self::Zm012),
+            "zm_045" => Some(Self::Zm045),
+            "zf_075" => Some(Self::Zf075),
+            _ => None,
+        }
+    }
+
+    /// 获取语音的元数据（语言、口音、性别和模型版本）
+    pub fn info(&self) -> VoiceInfo {
+        let model_version = if self.is_v11_supported() {
+            ModelVersion::V11
+        } else {
+            ModelVersion::V10
+        };
+        VoiceInfo::from_name(self.get_name(), model_version)
+            .expect("voice names always carry a valid prefix")
     }
 
     pub(super) fn is_v11_supported(&self) -> bool {
         matches!(
             self,
-            Self::Zm029(_)
-                | Self::Zf048(_)
-                | Self::Zf008(_)
-                | Self::Zm014(_)
-                | Self::Zf003(_)
-                | Self::Zf047(_)
-                | Self::Zm080(_)
-                | Self::Zf094(_)
-                | Self::Zf046(_)
-                | Self::Zm054(_)
-                | Self::Zf001(_)
-                | Self::Zm062(_)
-                | Self::BfVale(_)
-                | Self::Zf044(_)
-                | Self::Zf005(_)
-                | Self::Zf028(_)
-                | Self::Zf059(_)
-                | Self::Zm030(_)
-                | Self::Zf074(_)
-                | Self::Zm009(_)
-                | Self::Zf004(_)
-                | Self::Zf021(_)
-                | Self::Zm095(_)
-                | Self::Zm041(_)
-                | Self::Zf087(_)
-                | Self::Zf039(_)
-                | Self::Zm031(_)
-                | Self::Zf007(_)
-                | Self::Zf038(_)
-                | Self::Zf092(_)
-                | Self::Zm056(_)
-                | Self::Zf099(_)
-                | Self::Zm010(_)
-                | Self::Zm069(_)
-                | Self::Zm016(_)
-                | Self::Zm068(_)
-                | Self::Zf083(_)
-                | Self::Zf093(_)
-                | Self::Zf006(_)
-                | Self::Zf026(_)
-                | Self::Zm053(_)
-                | Self::Zm064(_)
-                | Self::AfSol(_)
-                | Self::Zf042(_)
-                | Self::Zf084(_)
-                | Self::Zf073(_)
-                | Self::Zf067(_)
-                | Self::Zm025(_)
-                | Self::Zm020(_)
-                | Self::Zm050(_)
-                | Self::Zf070(_)
-                | Self::Zf002(_)
-                | Self::Zf032(_)
-                | Self::Zm091(_)
-                | Self::Zm066(_)
-                | Self::Zm089(_)
-                | Self::Zm034(_)
-                | Self::Zm100(_)
-                | Self::Zf086(_)
-                | Self::Zf040(_)
-                | Self::Zm011(_)
-                | Self::Zm098(_)
-                | Self::Zm015(_)
-                | Self::Zf051(_)
-                | Self::Zm065(_)
-                | Self::Zf076(_)
-                | Self::Zf036(_)
-                | Self::Zm033(_)
-                | Self::Zf018(_)
-                | Self::Zf017(_)
-                | Self::Zf049(_)
-                | Self::AfMaple(_)
-                | Self::Zm082(_)
-                | Self::Zm057(_)
-                | Self::Zf079(_)
-                | Self::Zf022(_)
-                | Self::Zm063(_)
-                | Self::Zf060(_)
-                | Self::Zf019(_)
-                | Self::Zm097(_)
-                | Self::Zm096(_)
-                | Self::Zf023(_)
-                | Self::Zf027(_)
-                | Self::Zf085(_)
-                | Self::Zf077(_)
-                | Self::Zm035(_)
-                | Self::Zf088(_)
-                | Self::Zf024(_)
-                | Self::Zf072(_)
-                | Self::Zm055(_)
-                | Self::Zm052(_)
-                | Self::Zf071(_)
-                | Self::Zm061(_)
-                | Self::Zf078(_)
-                | Self::Zm013(_)
-                | Self::Zm081(_)
-                | Self::Zm037(_)
-                | Self::Zf090(_)
-                | Self::Zf043(_)
-                | Self::Zm058(_)
-                | Self::Zm012(_)
-                | Self::Zm045(_)
-                | Self::Zf075(_)
+            Self::Zm029
+                | Self::Zf048
+                | Self::Zf008
+                | Self::Zm014
+                | Self::Zf003
+                | Self::Zf047
+                | Self::Zm080
+                | Self::Zf094
+                | Self::Zf046
+                | Self::Zm054
+                | Self::Zf001
+                | Self::Zm062
+                | Self::BfVale
+                | Self::Zf044
+                | Self::Zf005
+                | Self::Zf028
+                | Self::Zf059
+                | Self::Zm030
+                | Self::Zf074
+                | Self::Zm009
+                | Self::Zf004
+                | Self::Zf021
+                | Self::Zm095
+                | Self::Zm041
+                | Self::Zf087
+                | Self::Zf039
+                | Self::Zm031
+                | Self::Zf007
+                | Self::Zf038
+                | Self::Zf092
+                | Self::Zm056
+                | Self::Zf099
+                | Self::Zm010
+                | Self::Zm069
+                | Self::Zm016
+                | Self::Zm068
+                | Self::Zf083
+                | Self::Zf093
+                | Self::Zf006
+                | Self::Zf026
+                | Self::Zm053
+                | Self::Zm064
+                | Self::AfSol
+                | Self::Zf042
+                | Self::Zf084
+                | Self::Zf073
+                | Self::Zf067
+                | Self::Zm025
+                | Self::Zm020
+                | Self::Zm050
+                | Self::Zf070
+                | Self::Zf002
+                | Self::Zf032
+                | Self::Zm091
+                | Self::Zm066
+                | Self::Zm089
+                | Self::Zm034
+                | Self::Zm100
+                | Self::Zf086
+                | Self::Zf040
+                | Self::Zm011
+                | Self::Zm098
+                | Self::Zm015
+                | Self::Zf051
+                | Self::Zm065
+                | Self::Zf076
+                | Self::Zf036
+                | Self::Zm033
+                | Self::Zf018
+                | Self::Zf017
+                | Self::Zf049
+                | Self::AfMaple
+                | Self::Zm082
+                | Self::Zm057
+                | Self::Zf079
+                | Self::Zf022
+                | Self::Zm063
+                | Self::Zf060
+                | Self::Zf019
+                | Self::Zm097
+                | Self::Zm096
+                | Self::Zf023
+                | Self::Zf027
+                | Self::Zf085
+                | Self::Zf077
+                | Self::Zm035
+                | Self::Zf088
+                | Self::Zf024
+                | Self::Zf072
+                | Self::Zm055
+                | Self::Zm052
+                | Self::Zf071
+                | Self::Zm061
+                | Self::Zf078
+                | Self::Zm013
+                | Self::Zm081
+                | Self::Zm037
+                | Self::Zf090
+                | Self::Zf043
+                | Self::Zm058
+                | Self::Zm012
+                | Self::Zm045
+                | Self::Zf075
         )
     }
-
-    pub(super) fn get_speed_v10(&self) -> Result<f32, KokoroError> {
-        match self {
-            Self::ZmYunyang(v)
-            | Self::ZfXiaoni(v)
-            | Self::AfJessica(v)
-            | Self::BfLily(v)
-            | Self::ZfXiaobei(v)
-            | Self::ZmYunxia(v)
-            | Self::AfHeart(v)
-            | Self::BfEmma(v)
-            | Self::AmPuck(v)
-            | Self::BfAlice(v)
-            | Self::HfAlpha(v)
-            | Self::BfIsabella(v)
-            | Self::AfNova(v)
-            | Self::AmFenrir(v)
-            | Self::EmAlex(v)
-            | Self::ImNicola(v)
-            | Self::PmAlex(v)
-            | Self::AfAlloy(v)
-            | Self::ZmYunxi(v)
-            | Self::AfSarah(v)
-            | Self::JfNezumi(v)
-            | Self::BmDaniel(v)
-            | Self::JfTebukuro(v)
-            | Self::JfAlpha(v)
-            | Self::JmKumo(v)
-            | Self::EmSanta(v)
-            | Self::AmLiam(v)
-            | Self::AmSanta(v)
-            | Self::AmEric(v)
-            | Self::BmFable(v)
-            | Self::AfBella(v)
-            | Self::BmLewis(v)
-            | Self::PfDora(v)
-            | Self::AfNicole(v)
-            | Self::BmGeorge(v)
-            | Self::AmOnyx(v)
-            | Self::HmPsi(v)
-            | Self::HfBeta(v)
-            | Self::HmOmega(v)
-            | Self::ZfXiaoxiao(v)
-            | Self::FfSiwis(v)
-            | Self::EfDora(v)
-            | Self::AfAoede(v)
-            | Self::AmEcho(v)
-            | Self::AmMichael(v)
-            | Self::AfKore(v)
-            | Self::ZfXiaoyi(v)
-            | Self::JfGongitsune(v)
-            | Self::AmAdam(v)
-            | Self::IfSara(v)
-            | Self::AfSky(v)
-            | Self::PmSanta(v)
-            | Self::AfRiver(v)
-            | Self::ZmYunjian(v) => Ok(*v),
-            _ => Err(KokoroError::VoiceVersionInvalid(
-                "Expect version 1.0".to_owned(),
-            )),
-        }
-    }
-
-    pub(super) fn get_speed_v11(&self) -> Result<i32, KokoroError> {
-        match self {
-            Self::Zm029(v)
-            | Self::Zf048(v)
-            | Self::Zf008(v)
-            | Self::Zm014(v)
-            | Self::Zf003(v)
-            | Self::Zf047(v)
-            | Self::Zm080(v)
-            | Self::Zf094(v)
-            | Self::Zf046(v)
-            | Self::Zm054(v)
-            | Self::Zf001(v)
-            | Self::Zm062(v)
-            | Self::BfVale(v)
-            | Self::Zf044(v)
-            | Self::Zf005(v)
-            | Self::Zf028(v)
-            | Self::Zf059(v)
-            | Self::Zm030(v)
-            | Self::Zf074(v)
-            | Self::Zm009(v)
-            | Self::Zf004(v)
-            | Self::Zf021(v)
-            | Self::Zm095(v)
-            | Self::Zm041(v)
-            | Self::Zf087(v)
-            | Self::Zf039(v)
-            | Self::Zm031(v)
-            | Self::Zf007(v)
-            | Self::Zf038(v)
-            | Self::Zf092(v)
-            | Self::Zm056(v)
-            | Self::Zf099(v)
-            | Self::Zm010(v)
-            | Self::Zm069(v)
-            | Self::Zm016(v)
-            | Self::Zm068(v)
-            | Self::Zf083(v)
-            | Self::Zf093(v)
-            | Self::Zf006(v)
-            | Self::Zf026(v)
-            | Self::Zm053(v)
-            | Self::Zm064(v)
-            | Self::AfSol(v)
-            | Self::Zf042(v)
-            | Self::Zf084(v)
-            | Self::Zf073(v)
-            | Self::Zf067(v)
-            | Self::Zm025(v)
-            | Self::Zm020(v)
-            | Self::Zm050(v)
-            | Self::Zf070(v)
-            | Self::Zf002(v)
-            | Self::Zf032(v)
-            | Self::Zm091(v)
-            | Self::Zm066(v)
-            | Self::Zm089(v)
-            | Self::Zm034(v)
-            | Self::Zm100(v)
-            | Self::Zf086(v)
-            | Self::Zf040(v)
-            | Self::Zm011(v)
-            | Self::Zm098(v)
-            | Self::Zm015(v)
-            | Self::Zf051(v)
-            | Self::Zm065(v)
-            | Self::Zf076(v)
-            | Self::Zf036(v)
-            | Self::Zm033(v)
-            | Self::Zf018(v)
-            | Self::Zf017(v)
-            | Self::Zf049(v)
-            | Self::AfMaple(v)
-            | Self::Zm082(v)
-            | Self::Zm057(v)
-            | Self::Zf079(v)
-            | Self::Zf022(v)
-            | Self::Zm063(v)
-            | Self::Zf060(v)
-            | Self::Zf019(v)
-            | Self::Zm097(v)
-            | Self::Zm096(v)
-            | Self::Zf023(v)
-            | Self::Zf027(v)
-            | Self::Zf085(v)
-            | Self::Zf077(v)
-            | Self::Zm035(v)
-            | Self::Zf088(v)
-            | Self::Zf024(v)
-            | Self::Zf072(v)
-            | Self::Zm055(v)
-            | Self::Zm052(v)
-            | Self::Zf071(v)
-            | Self::Zm061(v)
-            | Self::Zf078(v)
-            | Self::Zm013(v)
-            | Self::Zm081(v)
-            | Self::Zm037(v)
-            | Self::Zf090(v)
-            | Self::Zf043(v)
-            | Self::Zm058(v)
-            | Self::Zm012(v)
-            | Self::Zm045(v)
-            | Self::Zf075(v) => Ok(*v),
-            _ => Err(KokoroError::VoiceVersionInvalid(
-                "Expect version 1.1".to_owned(),
-            )),
-        }
-    }
 }
@@ -0,0 +1,249 @@
+use {
+    crate::{
+        KokoroError, VoicePack, VoiceStore,
+        cache::pack_digest,
+        decode_indexed_voice, index_voices,
+        voices_v019::{VOICES_V019_SIZE, decode_voices_v019, is_voices_v019},
+    },
+    lru::LruCache,
+    std::{
+        collections::HashMap,
+        io::{BufReader, Error as IoError},
+        num::NonZeroUsize,
+        ops::Range,
+        path::Path,
+        sync::{Arc, Mutex, PoisonError, RwLock},
+    },
+    tokio::{
+        fs::File,
+        io::{AsyncReadExt, AsyncSeekExt, SeekFrom},
+        sync::Mutex as AsyncMutex,
+        task::spawn_blocking,
+    },
+};
+
+/// 语音在哪里，已经解码的语音同时记录风格向量的摘要（见[`pack_digest`]）
+enum Slot {
+    /// 在启动时一次性加载的语音包中
+    Preloaded(u64),
+    /// 运行时添加的语音，不会被淘汰
+    Added(Arc<VoiceStore>, u64),
+    /// 按需解码，记录语音在文件中的字节范围
+    Indexed(Range<u64>),
+}
+
+/// 已加载的语音，持有其所在存储的引用
+pub(crate) struct LoadedVoice {
+    store: Arc<VoiceStore>,
+    name: String,
+    digest: u64,
+}
+
+impl LoadedVoice {
+    fn new(store: Arc<VoiceStore>, name: &str, digest: u64) -> Self {
+        Self {
+            store,
+            name: name.to_owned(),
+            digest,
+        }
+    }
+
+    /// 风格向量的摘要，用作缓存键的一部分
+    pub(crate) fn digest(&self) -> u64 {
+        self.digest
+    }
+
+    pub(crate) fn pack(&self) -> Result<VoicePack<'_>, KokoroError> {
+        self.store
+            .get(&self.name)
+            .ok_or_else(|| KokoroError::VoiceNotFound(self.name.clone()))
+    }
+}
+
+/// `KokoroTts`使用的语音库，支持预加载、按需加载以及运行时增删语音
+pub(crate) struct VoiceBank {
+    preloaded: Arc<VoiceStore>,
+    v019: bool,
+    /// 按需加载时打开的语音包文件，所有读取共用这一个句柄
+    file: Option<AsyncMutex<File>>,
+    slots: RwLock<HashMap<String, Slot>>,
+    cache: Mutex<LruCache<String, (Arc<VoiceStore>, u64)>>,
+}
+
+/// `store`中每个语音的名称和风格向量的摘要
+fn digests(store: &VoiceStore) -> impl Iterator<Item = (&str, u64)> {
+    store.names().filter_map(|i| Some((i, pack_digest(store.get(i)?))))
+}
+
+impl VoiceBank {
+    /// 使用已经全部解码的语音包
+    pub(crate) fn new(store: VoiceStore) -> Self {
+        let slots = digests(&store)
+            .map(|(name, digest)| (name.to_owned(), Slot::Preloaded(digest)))
+            .collect();
+        Self {
+            preloaded: Arc::new(store),
+            v019: false,
+            file: None,
+            slots: RwLock::new(slots),
+            cache: Mutex::new(LruCache::unbounded()),
+        }
+    }
+
+    /// 解码语音包，自动识别v0.19的原始张量格式和v1.0/v1.1的bincode格式
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, KokoroError> {
+        if is_voices_v019(bytes) {
+            return Ok(Self {
+                v019: true,
+                ..Self::new(decode_voices_v019(bytes)?)
+            });
+        }
+        Ok(Self::new(VoiceStore::from_bytes(bytes)?))
+    }
+
+    /// 只为语音包文件建立索引，语音在第一次使用时才解码。
+    /// `max_loaded`限制同时保留的已解码语音数量，超出时淘汰最久未使用的语音。
+    pub(crate) async fn index<P: AsRef<Path>>(path: P, max_loaded: Option<NonZeroUsize>) -> Result<Self, KokoroError> {
+        let mut file = File::open(path).await?;
+        if file.metadata().await?.len() == VOICES_V019_SIZE as u64 {
+            // v0.19的语音包很小，直接全部加载
+            let mut bytes = Vec::with_capacity(VOICES_V019_SIZE);
+            file.read_to_end(&mut bytes).await?;
+            return Self::from_bytes(&bytes);
+        }
+        // 索引只读取很短的长度前缀，每次都交给tokio的线程池代价太高，因此整体在一个阻塞任务中完成
+        let file = file.into_std().await;
+        let (file, index) = spawn_blocking(move || {
+            let mut reader = BufReader::with_capacity(64, file);
+            index_voices(&mut reader).map(|i| (reader.into_inner(), i))
+        })
+        .await
+        .map_err(IoError::other)??;
+        let slots = index
+            .into_iter()
+            .map(|(name, range)| (name, Slot::Indexed(range)))
+            .collect();
+        Ok(Self {
+            preloaded: Default::default(),
+            v019: false,
+            file: Some(AsyncMutex::new(File::from_std(file))),
+            slots: RwLock::new(slots),
+            cache: Mutex::new(max_loaded.map_or_else(LruCache::unbounded, LruCache::new)),
+        })
+    }
+
+    /// 语音包是否为v0.19的格式
+    pub(crate) fn is_v019(&self) -> bool {
+        self.v019
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.slots
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_key(name)
+    }
+
+    pub(crate) fn names(&self) -> Vec<String> {
+        self.slots
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// 获取语音，按需加载的语音在第一次使用时从文件中读取并解码
+    pub(crate) async fn get(&self, name: &str) -> Result<LoadedVoice, KokoroError> {
+        let range = match self
+            .slots
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+        {
+            Some(Slot::Preloaded(digest)) => return Ok(LoadedVoice::new(self.preloaded.clone(), name, *digest)),
+            Some(Slot::Added(store, digest)) => return Ok(LoadedVoice::new(store.clone(), name, *digest)),
+            Some(Slot::Indexed(range)) => range.clone(),
+            None => return Err(KokoroError::VoiceNotFound(name.to_owned())),
+        };
+        if let Some((store, digest)) = self.cache.lock().unwrap_or_else(PoisonError::into_inner).get(name) {
+            return Ok(LoadedVoice::new(store.clone(), name, *digest));
+        }
+
+        let Some(file) = &self.file else {
+            return Err(KokoroError::VoiceNotFound(name.to_owned()));
+        };
+        let mut bytes = vec![0; (range.end - range.start) as usize];
+        {
+            let mut file = file.lock().await;
+            file.seek(SeekFrom::Start(range.start)).await?;
+            file.read_exact(&mut bytes).await?;
+        }
+        let store = Arc::new(decode_indexed_voice(name, &bytes)?);
+        let digest = store.get(name).map(pack_digest).unwrap_or_default();
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(name.to_owned(), (store.clone(), digest));
+
+        Ok(LoadedVoice::new(store, name, digest))
+    }
+
+    /// 添加`store`中的所有语音，同名的语音会被替换
+    pub(crate) fn add(&self, store: VoiceStore) {
+        let store = Arc::new(store);
+        let mut slots = self.slots.write().unwrap_or_else(PoisonError::into_inner);
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        for (name, digest) in digests(&store) {
+            cache.pop(name);
+            slots.insert(name.to_owned(), Slot::Added(store.clone(), digest));
+        }
+    }
+
+    /// 移除语音，返回语音是否存在
+    pub(crate) fn remove(&self, name: &str) -> bool {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner).pop(name);
+        self.slots
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(name)
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bincode::{config::standard, encode_to_vec},
+    };
+
+    #[tokio::test]
+    async fn test_index() -> Result<(), KokoroError> {
+        let mut voices = HashMap::new();
+        voices.insert("af_a".to_owned(), vec![vec![vec![1f32, 2.]], vec![vec![3., 4.]]]);
+        voices.insert("zf_b".to_owned(), vec![vec![vec![5f32; 300]]]);
+        let path = std::env::temp_dir().join(format!("kokoro-voice-bank-test-{}.bin", std::process::id()));
+        tokio::fs::write(&path, encode_to_vec(&voices, standard()).unwrap()).await?;
+
+        // 只保留一个已解码的语音，交替读取时会从同一个文件句柄中重新解码
+        let bank = VoiceBank::index(&path, NonZeroUsize::new(1)).await?;
+        for _ in 0..2 {
+            let voice = bank.get("af_a").await?;
+            assert_eq!(Some(&[3f32, 4.][..]), voice.pack()?.style(1));
+            let voice = bank.get("zf_b").await?;
+            assert_eq!(300, voice.pack()?.width());
+        }
+        // 同名语音被替换后摘要随之改变
+        let digest = bank.get("zf_b").await?.digest();
+        let mut store = VoiceStore::default();
+        store.insert("zf_b", &[vec![vec![6f32; 300]]])?;
+        bank.add(store);
+        assert_ne!(digest, bank.get("zf_b").await?.digest());
+        assert!(bank.remove("af_a"));
+        assert!(bank.get("af_a").await.is_err());
+        tokio::fs::remove_file(&path).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+use {
+    crate::KokoroError,
+    ndarray::{ErrorKind, ShapeError},
+    bincode::{
+        Decode,
+        config::standard,
+        de::{Decoder, DecoderImpl, read::SliceReader},
+        decode_from_std_read,
+        error::DecodeError,
+    },
+    std::{
+        collections::HashMap,
+        io::{BufReader, Read, Seek, SeekFrom},
+        ops::Range,
+        path::Path,
+    },
+    tokio::fs::read,
+};
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    offset: usize,
+    rows: usize,
+    stride: usize,
+    width: usize,
+}
+
+/// 语音包存储
+///
+/// 所有语音的风格向量都保存在同一块连续内存中，查找时返回零拷贝的切片，不再为每一行风格向量单独分配内存。
+/// 文件格式与`HashMap<String, Vec<Vec<Vec<f32>>>>`的bincode编码完全兼容。
+/// 加载时会把文件读入内存再解码，不使用内存映射：bincode的长度是变长编码，浮点数在文件中没有对齐，无法直接作为`&[f32]`使用。
+#[derive(Debug, Default)]
+pub struct VoiceStore {
+    data: Vec<f32>,
+    index: HashMap<String, Entry>,
+}
+
+/// 单个语音的风格向量表
+///
+/// 第`i`行风格向量对应长度为`i + 1`的音素序列。
+#[derive(Clone, Copy, Debug)]
+pub struct VoicePack<'a> {
+    data: &'a [f32],
+    rows: usize,
+    stride: usize,
+    width: usize,
+}
+
+impl<'a> VoicePack<'a> {
+    /// 风格向量的行数
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// 每个风格向量的维度
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 获取第`index`行风格向量，越界时返回`None`
+    pub fn style(&self, index: usize) -> Option<&'a [f32]> {
+        if index >= self.rows {
+            return None;
+        }
+        let start = index * self.stride;
+        self.data.get(start..start + self.width)
+    }
+}
+
+impl VoiceStore {
+    /// 从文件加载语音包
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, KokoroError> {
+        Self::from_bytes(read(path).await?)
+    }
+
+    /// 从内存中的bincode数据加载语音包
+    pub fn from_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<Self, KokoroError> {
+        let bytes = bytes.as_ref();
+        let mut decoder = DecoderImpl::new(SliceReader::new(bytes), standard(), ());
+        let voices = decode_len(&mut decoder)?;
+        check_voice_count(voices, bytes.len() as u64)?;
+        let mut store = Self::with_capacity(voices, 0);
+        for _ in 0..voices {
+            let name = String::decode(&mut decoder)?;
+            store.decode_voice(&mut decoder, name, bytes.len())?;
+        }
+
+        Ok(store)
+    }
+
+    /// 按名称查找语音
+    pub fn get(&self, name: &str) -> Option<VoicePack<'_>> {
+        let Entry {
+            offset,
+            rows,
+            stride,
+            width,
+        } = *self.index.get(name)?;
+        Some(VoicePack {
+            data: &self.data[offset..offset + rows * stride],
+            rows,
+            stride,
+            width,
+        })
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    /// 语音包中所有语音的名称，顺序不固定
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// 添加一个语音，`styles`的形状与语音包文件中的相同，通常为`(510, 1, 256)`。
+    /// 同名的语音会被替换：数据大小相同时原地覆盖，否则删除旧数据并前移其后的语音。
+    pub fn insert<S: Into<String>>(&mut self, name: S, styles: &[Vec<Vec<f32>>]) -> Result<(), KokoroError> {
+        let columns = styles.first().map_or(0, Vec::len);
+        let width = styles.first().and_then(|i| i.first()).map_or(0, Vec::len);
+        if styles
+            .iter()
+            .any(|row| row.len() != columns || row.iter().any(|i| i.len() != width))
+        {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        let entry = Entry {
+            offset: 0,
+            rows: styles.len(),
+            stride: columns * width,
+            width,
+        };
+        self.replace(name.into(), entry, styles.iter().flatten().flatten().copied());
+
+        Ok(())
+    }
+
+    pub(crate) fn with_capacity(voices: usize, floats: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(floats),
+            index: HashMap::with_capacity(voices),
+        }
+    }
+
+    /// 追加一个形状为`(rows, width)`的语音
+    pub(crate) fn push<I>(&mut self, name: String, rows: usize, width: usize, values: I)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        let entry = Entry {
+            offset: 0,
+            rows,
+            stride: width,
+            width,
+        };
+        self.replace(name, entry, values);
+    }
+
+    /// 写入一个语音的数据，不足的部分填充0。同名语音的数据大小相同时复用原来的位置，否则删除旧数据后追加到末尾
+    fn replace<I>(&mut self, name: String, mut entry: Entry, values: I)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        let len = entry.rows * entry.stride;
+        entry.offset = match self.index.get(&name).copied() {
+            Some(old) if old.rows * old.stride == len => old.offset,
+            Some(old) => {
+                let old_len = old.rows * old.stride;
+                self.data.drain(old.offset..old.offset + old_len);
+                for i in self.index.values_mut().filter(|i| i.offset > old.offset) {
+                    i.offset -= old_len;
+                }
+                self.data.len()
+            }
+            None => self.data.len(),
+        };
+        if entry.offset == self.data.len() {
+            self.data.extend(values.into_iter().take(len));
+            self.data.resize(entry.offset + len, 0.);
+        } else {
+            let mut values = values.into_iter();
+            for i in &mut self.data[entry.offset..entry.offset + len] {
+                *i = values.next().unwrap_or_default();
+            }
+        }
+        self.index.insert(name, entry);
+    }
+}
+
+fn decode_len<D: Decoder>(decoder: &mut D) -> Result<usize, DecodeError> {
+    let len = u64::decode(decoder)?;
+    len.try_into().map_err(|_| DecodeError::OutsideUsizeRange(len))
+}
+
+/// 每个语音至少有名称和行数两个长度前缀，语音数量超过剩余字节数的一半时数据已经损坏，不按它预分配
+fn check_voice_count(voices: usize, remaining: u64) -> Result<(), DecodeError> {
+    if voices.checked_mul(2).is_none_or(|i| i as u64 > remaining) {
+        return Err(DecodeError::Other("voice count exceeds the size of the voice pack"));
+    }
+    Ok(())
+}
+
+impl VoiceStore {
+    /// 解码一个语音的风格向量并追加到存储末尾，`input_len`是输入的字节数，用来检查文件中的长度前缀
+    pub(crate) fn decode_voice<D: Decoder>(
+        &mut self,
+        decoder: &mut D,
+        name: String,
+        input_len: usize,
+    ) -> Result<(), DecodeError> {
+        let offset = self.data.len();
+        let rows = decode_len(decoder)?;
+        let mut shape = None;
+        for _ in 0..rows {
+            // 每一行是若干个等长的风格向量，按行连续存放
+            let columns = decode_len(decoder)?;
+            let mut width = None;
+            for _ in 0..columns {
+                let len = decode_len(decoder)?;
+                decoder.claim_container_read::<f32>(len)?;
+                if *width.get_or_insert(len) != len {
+                    return Err(DecodeError::Other("voice pack styles must have the same width"));
+                }
+                if self.data.len() == offset {
+                    let bytes = rows
+                        .checked_mul(columns)
+                        .and_then(|i| i.checked_mul(len))
+                        .and_then(|i| i.checked_mul(size_of::<f32>()))
+                        .ok_or(DecodeError::Other("voice pack size overflows usize"))?;
+                    if bytes > input_len {
+                        return Err(DecodeError::UnexpectedEnd {
+                            additional: bytes - input_len,
+                        });
+                    }
+                    self.data.reserve(bytes / size_of::<f32>());
+                }
+                for _ in 0..len {
+                    decoder.unclaim_bytes_read(size_of::<f32>());
+                    self.data.push(f32::decode(decoder)?);
+                }
+            }
+            let row = (columns, width.unwrap_or_default());
+            if *shape.get_or_insert(row) != row {
+                return Err(DecodeError::Other("voice pack rows must have the same shape"));
+            }
+        }
+        let (columns, width) = shape.unwrap_or_default();
+        self.index.insert(
+            name,
+            Entry {
+                offset,
+                rows,
+                stride: columns * width,
+                width,
+            },
+        );
+
+        Ok(())
+    }
+}
+
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, DecodeError> {
+    let len = decode_from_std_read::<u64, _, _>(reader, standard())?;
+    len.try_into().map_err(|_| DecodeError::OutsideUsizeRange(len))
+}
+
+/// 建立语音包的索引：每个语音的风格向量在文件中的字节范围。
+/// 只读取名称和长度前缀，浮点数据直接跳过，不需要把整个文件读入内存。
+pub(crate) fn index_voices<R: Read + Seek>(reader: &mut BufReader<R>) -> Result<Vec<(String, Range<u64>)>, KokoroError> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.rewind()?;
+    let voices = read_len(reader)?;
+    check_voice_count(voices, end - reader.stream_position()?)?;
+    let mut index = Vec::with_capacity(voices);
+    for _ in 0..voices {
+        let name = decode_from_std_read::<String, _, _>(reader, standard())?;
+        let start = reader.stream_position()?;
+        for _ in 0..read_len(reader)? {
+            for _ in 0..read_len(reader)? {
+                let len = read_len(reader)?;
+                let skip = len.checked_mul(size_of::<f32>()).and_then(|i| i64::try_from(i).ok());
+                reader.seek_relative(skip.ok_or(DecodeError::OutsideUsizeRange(len as u64))?)?;
+            }
+        }
+        let pos = reader.stream_position()?;
+        if pos > end {
+            return Err(DecodeError::UnexpectedEnd {
+                additional: (pos - end) as usize,
+            }
+            .into());
+        }
+        index.push((name, start..pos));
+    }
+
+    Ok(index)
+}
+
+/// 解码`index_voices`给出的单个语音的字节
+pub(crate) fn decode_indexed_voice(name: &str, bytes: &[u8]) -> Result<VoiceStore, DecodeError> {
+    let mut store = VoiceStore::default();
+    let mut decoder = DecoderImpl::new(SliceReader::new(bytes), standard(), ());
+    store.decode_voice(&mut decoder, name.to_owned(), bytes.len())?;
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bincode::encode_to_vec;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_decode_voice_store() -> Result<(), KokoroError> {
+        let mut voices = HashMap::new();
+        voices.insert("af_a".to_owned(), vec![vec![vec![1f32, 2.]], vec![vec![3., 4.]]]);
+        voices.insert("zf_b".to_owned(), vec![vec![vec![5f32, 6.], vec![7., 8.]]]);
+        let bytes = encode_to_vec(&voices, standard()).unwrap();
+
+        let store = VoiceStore::from_bytes(bytes)?;
+        assert_eq!(2, store.len());
+        let pack = store.get("af_a").unwrap();
+        assert_eq!(2, pack.len());
+        assert_eq!(Some(&[3f32, 4.][..]), pack.style(1));
+        assert_eq!(None, pack.style(2));
+        // 每行只取第一个风格向量
+        let pack = store.get("zf_b").unwrap();
+        assert_eq!(Some(&[5f32, 6.][..]), pack.style(0));
+        assert!(store.get("af_c").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_voice() -> Result<(), KokoroError> {
+        let mut store = VoiceStore::default();
+        store.insert("af_a", &[vec![vec![1., 2.]], vec![vec![3., 4.]]])?;
+        assert_eq!(Some(&[3f32, 4.][..]), store.get("af_a").unwrap().style(1));
+        assert!(store.insert("af_b", &[vec![vec![1., 2.]], vec![vec![3.]]]).is_err());
+        assert!(!store.contains("af_b"));
+
+        // 大小相同的替换复用原来的位置
+        store.insert("af_b", &[vec![vec![5., 6.]]])?;
+        store.insert("af_a", &[vec![vec![7., 8.]], vec![vec![9., 10.]]])?;
+        assert_eq!(6, store.data.len());
+        assert_eq!(Some(&[9f32, 10.][..]), store.get("af_a").unwrap().style(1));
+        // 大小不同的替换删除旧数据，其后的语音前移
+        store.insert("af_a", &[vec![vec![1., 2.]]])?;
+        assert_eq!(4, store.data.len());
+        assert_eq!(Some(&[5f32, 6.][..]), store.get("af_b").unwrap().style(0));
+        assert_eq!(Some(&[1f32, 2.][..]), store.get("af_a").unwrap().style(0));
+        assert_eq!(None, store.get("af_a").unwrap().style(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_voices() -> Result<(), KokoroError> {
+        let mut voices = HashMap::new();
+        voices.insert("af_a".to_owned(), vec![vec![vec![1f32, 2.]], vec![vec![3., 4.]]]);
+        voices.insert("zf_b".to_owned(), vec![vec![vec![5f32; 300]]]);
+        let bytes = encode_to_vec(&voices, standard()).unwrap();
+
+        let index = index_voices(&mut BufReader::new(Cursor::new(&bytes)))?;
+        assert_eq!(2, index.len());
+        for (name, range) in index {
+            let store = decode_indexed_voice(&name, &bytes[range.start as usize..range.end as usize])?;
+            let expected = &voices[&name];
+            let pack = store.get(&name).unwrap();
+            assert_eq!(expected.len(), pack.len());
+            assert_eq!(Some(&expected[0][0][..]), pack.style(0));
+        }
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(index_voices(&mut BufReader::new(Cursor::new(truncated))).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_lengths() {
+        // 损坏的长度前缀应该返回错误，而不是按它预分配内存
+        let count = encode_to_vec(u64::MAX >> 1, standard()).unwrap();
+        assert!(VoiceStore::from_bytes(&count).is_err());
+        assert!(index_voices(&mut BufReader::new(Cursor::new(&count))).is_err());
+        for len in [u64::MAX >> 4, u64::MAX >> 1] {
+            let bytes = encode_to_vec((1u64, "af_a", 1u64, 1u64, len), standard()).unwrap();
+            assert!(VoiceStore::from_bytes(&bytes).is_err());
+        }
+    }
+}
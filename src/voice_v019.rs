@@ -1,43 +1,68 @@
+use crate::{Accent, voices_v019::VOICE_NAMES_V019};
+
+/// v0.19模型的语音，按官方voices.bin中的顺序排列
 //noinspection SpellCheckingInspection
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum VoiceV019 {
-    // v0.19 has 11 voices in the bin file
-    // We'll map them by index for now until we identify them
-    Voice0(f32),  // Likely af_heart or similar
-    Voice1(f32),
-    Voice2(f32),
-    Voice3(f32),
-    Voice4(f32),
-    Voice5(f32),
-    Voice6(f32),
-    Voice7(f32),
-    Voice8(f32),
-    Voice9(f32),
-    Voice10(f32),
+    /// af_bella和af_sarah的混合，v0.19的默认语音
+    Af,
+    AfBella,
+    AfNicole,
+    AfSarah,
+    AfSky,
+    AmAdam,
+    AmMichael,
+    BfEmma,
+    BfIsabella,
+    BmGeorge,
+    BmLewis,
 }
 
 impl VoiceV019 {
+    /// 所有语音
+    pub const ALL: [Self; 11] = [
+        Self::Af,
+        Self::AfBella,
+        Self::AfNicole,
+        Self::AfSarah,
+        Self::AfSky,
+        Self::AmAdam,
+        Self::AmMichael,
+        Self::BfEmma,
+        Self::BfIsabella,
+        Self::BmGeorge,
+        Self::BmLewis,
+    ];
+
+    /// 语音在voices.bin中的序号
     pub(super) fn get_index(&self) -> usize {
-        match self {
-            Self::Voice0(_) => 0,
-            Self::Voice1(_) => 1,
-            Self::Voice2(_) => 2,
-            Self::Voice3(_) => 3,
-            Self::Voice4(_) => 4,
-            Self::Voice5(_) => 5,
-            Self::Voice6(_) => 6,
-            Self::Voice7(_) => 7,
-            Self::Voice8(_) => 8,
-            Self::Voice9(_) => 9,
-            Self::Voice10(_) => 10,
-        }
+        *self as usize
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        VOICE_NAMES_V019[self.get_index()]
+    }
+
+    /// 根据名称查找语音，例如`af_bella`
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::VoiceV019;
+    ///
+    /// assert_eq!(Some(VoiceV019::BmLewis), VoiceV019::from_name("bm_lewis"));
+    /// assert_eq!("af", VoiceV019::Af.get_name());
+    /// ```
+    ///
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|i| i.get_name() == name)
     }
 
-    pub(super) fn get_speed(&self) -> f32 {
-        match self {
-            Self::Voice0(v) | Self::Voice1(v) | Self::Voice2(v) | Self::Voice3(v) |
-            Self::Voice4(v) | Self::Voice5(v) | Self::Voice6(v) | Self::Voice7(v) |
-            Self::Voice8(v) | Self::Voice9(v) | Self::Voice10(v) => *v
+    /// 语音的口音，名称以`a`开头的是美式英语，以`b`开头的是英式英语
+    pub fn accent(&self) -> Accent {
+        match self.get_name().as_bytes()[0] {
+            b'b' => Accent::British,
+            _ => Accent::American,
         }
     }
 }
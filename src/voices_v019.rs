@@ -2,81 +2,87 @@
 /// Format: Raw f32 tensor with shape (11, 511, 256)
 /// Total: 11 voices × 511 time_steps × 256 features = 1,438,976 floats
 
-use std::path::Path;
-use tokio::fs::read;
-use crate::KokoroError;
+use crate::{KokoroError, VoiceStore};
 
 const NUM_VOICES_V019: usize = 11;
 const TIME_STEPS_V019: usize = 511;
 const FEATURES_V019: usize = 256;
 const FLOATS_PER_VOICE: usize = TIME_STEPS_V019 * FEATURES_V019; // 130,816
 
-pub type VoicesV019 = Vec<Vec<Vec<f32>>>;  // Shape: (11, 511, 256)
+/// Voice names in the order they are stacked in the official v0.19 voices.bin
+pub(crate) const VOICE_NAMES_V019: [&str; NUM_VOICES_V019] = [
+    "af", "af_bella", "af_nicole", "af_sarah", "af_sky", "am_adam", "am_michael", "bf_emma", "bf_isabella",
+    "bm_george", "bm_lewis",
+];
 
-/// Load v0.19 voices.bin as raw tensor
-pub async fn load_voices_v019<P: AsRef<Path>>(voices_path: P) -> Result<VoicesV019, KokoroError> {
-    let bytes = read(voices_path).await?;
+/// Size in bytes of a raw v0.19 voices.bin
+pub(crate) const VOICES_V019_SIZE: usize = NUM_VOICES_V019 * FLOATS_PER_VOICE * 4;
 
+/// Whether the bytes have the exact size of a raw v0.19 voices.bin
+pub(crate) fn is_voices_v019(bytes: &[u8]) -> bool {
+    bytes.len() == VOICES_V019_SIZE
+}
+
+/// Decode a v0.19 voices.bin as raw tensor into a flat voice store.
+/// Voices are stored under the names in `VOICE_NAMES_V019`.
+pub(crate) fn decode_voices_v019(bytes: &[u8]) -> Result<VoiceStore, KokoroError> {
     // Verify size
-    let expected_size = NUM_VOICES_V019 * FLOATS_PER_VOICE * 4; // *4 for f32 bytes
-    if bytes.len() != expected_size {
+    if !is_voices_v019(bytes) {
         return Err(KokoroError::VoiceNotFound(format!(
             "v0.19 voices.bin size mismatch: expected {} bytes, got {}",
-            expected_size, bytes.len()
+            NUM_VOICES_V019 * FLOATS_PER_VOICE * 4, // *4 for f32 bytes
+            bytes.len()
         )));
     }
 
-    // Parse as raw f32 little-endian
-    let mut all_voices = Vec::with_capacity(NUM_VOICES_V019);
-
-    for voice_idx in 0..NUM_VOICES_V019 {
-        let mut voice_data = Vec::with_capacity(TIME_STEPS_V019);
-
-        for time_idx in 0..TIME_STEPS_V019 {
-            let mut features = Vec::with_capacity(FEATURES_V019);
-
-            for feat_idx in 0..FEATURES_V019 {
-                // Calculate byte offset: voice * voice_size + time * features + feat
-                let offset = (voice_idx * FLOATS_PER_VOICE + time_idx * FEATURES_V019 + feat_idx) * 4;
-
-                // Read f32 little-endian
-                let bytes_slice = &bytes[offset..offset+4];
-                let value = f32::from_le_bytes([
-                    bytes_slice[0],
-                    bytes_slice[1],
-                    bytes_slice[2],
-                    bytes_slice[3],
-                ]);
-
-                features.push(value);
-            }
-
-            voice_data.push(features);
-        }
-
-        all_voices.push(voice_data);
+    // Parse as raw f32 little-endian, one contiguous block per voice
+    let mut store = VoiceStore::with_capacity(NUM_VOICES_V019, NUM_VOICES_V019 * FLOATS_PER_VOICE);
+    for (name, voice) in VOICE_NAMES_V019.iter().zip(bytes.chunks_exact(FLOATS_PER_VOICE * 4)) {
+        let values = voice
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+        store.push(name.to_string(), TIME_STEPS_V019, FEATURES_V019, values);
     }
 
-    Ok(all_voices)
+    Ok(store)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, tokio::fs::read};
+
+    #[test]
+    fn test_decode_v019_voices() {
+        // 每个值编码了语音序号、行号和列号
+        let bytes = (0..NUM_VOICES_V019 * FLOATS_PER_VOICE)
+            .flat_map(|i| {
+                let (voice, rest) = (i / FLOATS_PER_VOICE, i % FLOATS_PER_VOICE);
+                let value = voice as f32 * 1000. + (rest / FEATURES_V019) as f32 + (rest % FEATURES_V019) as f32 / 1000.;
+                value.to_le_bytes()
+            })
+            .collect::<Vec<_>>();
+        let voices = decode_voices_v019(&bytes).unwrap();
+        assert_eq!(voices.len(), NUM_VOICES_V019);
+
+        let pack = voices.get("bm_lewis").unwrap();
+        assert_eq!(pack.len(), TIME_STEPS_V019);
+        assert_eq!(pack.width(), FEATURES_V019);
+        assert_eq!(10_000. + 3. + 0.005, pack.style(3).unwrap()[5]);
+
+        assert!(decode_voices_v019(&bytes[4..]).is_err());
+    }
 
     #[tokio::test]
+    #[ignore = "needs the official v0.19 voices.bin at ../kokoro-en-v0_19 2/voices.bin"]
     async fn test_load_v019_voices() {
-        // This will only work if the file exists
-        if let Ok(voices) = load_voices_v019("../kokoro-en-v0_19 2/voices.bin").await {
-            assert_eq!(voices.len(), NUM_VOICES_V019);
-            assert_eq!(voices[0].len(), TIME_STEPS_V019);
-            assert_eq!(voices[0][0].len(), FEATURES_V019);
-
-            // Check that values are in reasonable range
-            let first_val = voices[0][0][0];
-            assert!(first_val > -10.0 && first_val < 10.0);
-
-            println!("✓ v0.19 voices loaded: {} voices", NUM_VOICES_V019);
-        }
+        let voices = decode_voices_v019(&read("../kokoro-en-v0_19 2/voices.bin").await.unwrap()).unwrap();
+        assert_eq!(voices.len(), NUM_VOICES_V019);
+        let pack = voices.get("af").unwrap();
+        assert_eq!(pack.len(), TIME_STEPS_V019);
+        assert_eq!(pack.width(), FEATURES_V019);
+
+        // Check that values are in reasonable range
+        let first_val = pack.style(0).unwrap()[0];
+        assert!(first_val > -10.0 && first_val < 10.0);
     }
 }
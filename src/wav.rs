@@ -0,0 +1,94 @@
+use crate::{KokoroError, SAMPLE_RATE};
+
+/// WAVE_FORMAT_IEEE_FLOAT
+const FORMAT_FLOAT: u16 = 3;
+
+/// WAV文件头的长度，之后紧接着采样数据
+pub(crate) const WAV_HEADER_LEN: u64 = 44;
+
+/// 编码为单声道32位浮点WAV，采样率为[`SAMPLE_RATE`]，无损保存合成结果
+pub(crate) fn encode_wav(samples: &[f32]) -> Result<Vec<u8>, KokoroError> {
+    let mut bytes = wav_header(samples.len())?;
+    bytes.extend(wav_data(samples));
+    Ok(bytes)
+}
+
+/// `samples`个采样的数据长度，RIFF的长度字段只有32位，超过时（约12.4小时）返回错误
+pub(crate) fn wav_data_len(samples: usize) -> Result<u32, KokoroError> {
+    samples
+        .checked_mul(4)
+        .and_then(|i| u32::try_from(i).ok())
+        .filter(|i| i.checked_add(WAV_HEADER_LEN as u32 - 8).is_some())
+        .ok_or(KokoroError::AudioTooLong(samples))
+}
+
+/// 包含`samples`个采样的WAV文件头，可以先写入文件头再分多次追加[`wav_data`]
+pub(crate) fn wav_header(samples: usize) -> Result<Vec<u8>, KokoroError> {
+    let data_len = wav_data_len(samples)?;
+    let mut bytes = Vec::with_capacity(WAV_HEADER_LEN as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(WAV_HEADER_LEN as u32 - 8 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&FORMAT_FLOAT.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 4).to_le_bytes());
+    bytes.extend_from_slice(&4u16.to_le_bytes());
+    bytes.extend_from_slice(&32u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    Ok(bytes)
+}
+
+/// 采样数据部分的字节
+pub(crate) fn wav_data(samples: &[f32]) -> Vec<u8> {
+    samples.iter().flat_map(|i| i.to_le_bytes()).collect()
+}
+
+/// 解码[`encode_wav`]写出的WAV，格式不符时返回`None`
+pub(crate) fn decode_wav(bytes: &[u8]) -> Option<Vec<f32>> {
+    let u16_at = |i: usize| Some(u16::from_le_bytes(bytes.get(i..i + 2)?.try_into().ok()?));
+    let u32_at = |i: usize| Some(u32::from_le_bytes(bytes.get(i..i + 4)?.try_into().ok()?));
+    if bytes.get(..4)? != b"RIFF" || bytes.get(8..16)? != b"WAVEfmt " {
+        return None;
+    }
+    if u16_at(20)? != FORMAT_FLOAT || u16_at(22)? != 1 || u32_at(24)? != SAMPLE_RATE || u16_at(34)? != 32 {
+        return None;
+    }
+    if bytes.get(36..40)? != b"data" {
+        return None;
+    }
+    let data = bytes.get(44..44 + u32_at(40)? as usize)?;
+    Some(
+        data.chunks_exact(4)
+            .map(|i| f32::from_le_bytes([i[0], i[1], i[2], i[3]]))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav() -> Result<(), KokoroError> {
+        let samples = vec![0., 0.5, -1., 1e-7];
+        let bytes = encode_wav(&samples)?;
+        assert_eq!(44 + 16, bytes.len());
+        assert_eq!(Some(samples), decode_wav(&bytes));
+        assert_eq!(None, decode_wav(&bytes[..50]));
+        assert_eq!(None, decode_wav(b"RIFF"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wav_too_long() {
+        // 数据长度加上文件头的36字节必须能放进32位的RIFF长度字段
+        let max = (u32::MAX as usize - 36) / 4;
+        assert!(wav_header(max).is_ok());
+        assert!(matches!(wav_header(max + 1), Err(KokoroError::AudioTooLong(_))));
+        assert!(wav_header(usize::MAX).is_err());
+    }
+}
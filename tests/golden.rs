@@ -0,0 +1,72 @@
+//! 音素和编号的回归测试，不需要模型文件
+//!
+//! `tests/golden`中的每个文件对应一种语言和模型版本，每行是以制表符分隔的原文、音素和编号，
+//! 音素中有词表之外的符号时，第四列列出这些符号。
+//! 修改g2p或词表之后运行`KOKORO_BLESS=1 cargo test --test golden`重新生成，再检查差异是否符合预期。
+//! 只有原文的行会在重新生成时补全。
+//!
+//! 启用`use-cmudict`特性时美式英语和中文里的英语单词改用CMUdict转换，这些文件在`tests/golden/cmudict`中另存一份，
+//! 用`KOKORO_BLESS=1 cargo test --test golden --features use-cmudict`重新生成。
+
+use kokoro_tts::{Accent, G2POptions, Language, ModelVersion, Vocabulary, g2p_with_options};
+use std::{env, fs, path::Path};
+
+/// 文件名、语言、口音、模型版本，以及结果是否取决于`use-cmudict`特性
+const CASES: [(&str, Language, Accent, ModelVersion, bool); 4] = [
+    ("en_us_v10.tsv", Language::English, Accent::American, ModelVersion::V10, true),
+    ("en_gb_v10.tsv", Language::English, Accent::British, ModelVersion::V10, false),
+    ("en_us_v019.tsv", Language::English, Accent::American, ModelVersion::V019, true),
+    ("zh_v10.tsv", Language::Chinese, Accent::Mandarin, ModelVersion::V10, true),
+];
+
+fn render(text: &str, options: &G2POptions, version: ModelVersion) -> String {
+    let phonemes = g2p_with_options(text, options, version == ModelVersion::V11).unwrap();
+    let (tokens, unknown) = Vocabulary::builtin(version).encode(&phonemes);
+    let tokens = tokens.iter().map(i64::to_string).collect::<Vec<_>>().join(" ");
+    let unknown = unknown.iter().map(|i| i.symbol).collect::<String>();
+    if unknown.is_empty() {
+        format!("{}\t{}\t{}", text, phonemes, tokens)
+    } else {
+        format!("{}\t{}\t{}\t{}", text, phonemes, tokens, unknown)
+    }
+}
+
+#[test]
+fn test_golden() {
+    let bless = env::var_os("KOKORO_BLESS").is_some();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut failures = Vec::new();
+    for (file, language, accent, version, uses_cmudict) in CASES {
+        let options = G2POptions {
+            language,
+            accent,
+            ..Default::default()
+        };
+        let path = if uses_cmudict && cfg!(feature = "use-cmudict") {
+            dir.join("cmudict").join(file)
+        } else {
+            dir.join(file)
+        };
+        let golden = fs::read_to_string(&path).unwrap();
+        let mut rendered = String::new();
+        for (n, line) in golden.lines().enumerate().filter(|(_, i)| !i.is_empty()) {
+            let text = line.split('\t').next().unwrap_or_default();
+            let actual = render(text, &options, version);
+            if actual != line {
+                failures.push(format!("{}:{}\n  expected: {}\n    actual: {}", file, n + 1, line, actual));
+            }
+            rendered.push_str(&actual);
+            rendered.push('\n');
+        }
+        if bless {
+            fs::write(&path, rendered).unwrap();
+        }
+    }
+
+    assert!(
+        bless || failures.is_empty(),
+        "{} golden lines differ, rerun with KOKORO_BLESS=1 if the changes are expected:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}